@@ -50,14 +50,15 @@ fn main() {
         max_iter_line_search,
         None,
     ) {
-        Ok(()) => {
+        Ok(report) => {
             let x = solver.x();
             let eval = f_and_g(x);
             println!("✅ Optimization completed successfully!");
             println!("Final iterate: {:?}", x);
-            println!("Function value: {:.8}", eval.f());
-            println!("Gradient norm: {:.8}", eval.g().norm());
-            println!("Iterations: {}", solver.k());
+            println!("Function value: {:.8}", report.f());
+            println!("Gradient norm: {:.8}", report.gradient_norm());
+            println!("Iterations: {}", report.iterations());
+            println!("Termination reason: {}", report.termination_reason());
 
             // Verify optimality conditions
             let gradient_at_solution = eval.g();
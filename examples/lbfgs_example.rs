@@ -0,0 +1,72 @@
+use nalgebra::DVector;
+use optimization_solvers::{FuncEvalMultivariate, LineSearchSolver, MoreThuente, Tracer, LBFGS};
+
+fn main() {
+    // Setting up logging
+    std::env::set_var("RUST_LOG", "info");
+    let _ = Tracer::default().with_normal_stdout_layer().build();
+
+    // Separable convex quadratic in n dimensions: f(x) = sum_i i * (x_i - 1)^2
+    // The minimum is at x = (1, ..., 1) with f = 0
+    let n = 1000;
+    let f_and_g = |x: &DVector<f64>| -> FuncEvalMultivariate {
+        let mut f = 0.0;
+        let mut g = DVector::zeros(x.len());
+        for i in 0..x.len() {
+            let weight = (i + 1) as f64;
+            f += weight * (x[i] - 1.0).powi(2);
+            g[i] = 2.0 * weight * (x[i] - 1.0);
+        }
+        FuncEvalMultivariate::new(f, g)
+    };
+
+    // Setting up the line search (More-Thuente line search)
+    let mut ls = MoreThuente::default();
+
+    // Setting up the solver: only the last m correction pairs are stored
+    let tol = 1e-8;
+    let m = 7;
+    let x0 = DVector::zeros(n); // Starting point
+    let mut solver = LBFGS::new(tol, x0).with_m(m);
+
+    // Running the solver
+    let max_iter_solver = 1000;
+    let max_iter_line_search = 20;
+
+    println!("=== L-BFGS Example ===");
+    println!("Objective: f(x) = sum_i i * (x_i - 1)^2 (convex quadratic)");
+    println!("Dimension: {}", n);
+    println!("History size: {}", m);
+    println!("Tolerance: {}", tol);
+    println!();
+
+    match solver.minimize(
+        &mut ls,
+        f_and_g,
+        max_iter_solver,
+        max_iter_line_search,
+        None,
+    ) {
+        Ok(report) => {
+            println!("✅ Optimization completed successfully!");
+            println!("Function value: {:.8}", report.f());
+            println!("Gradient norm: {:.8}", report.gradient_norm());
+            println!("Iterations: {}", report.iterations());
+            println!("Function evaluations: {}", report.evaluations().f());
+            println!("Termination reason: {}", report.termination_reason());
+
+            let max_error = report
+                .x()
+                .iter()
+                .map(|xi| (xi - 1.0).abs())
+                .fold(0.0, f64::max);
+            println!(
+                "Max distance from the minimum (1, ..., 1): {:.2e}",
+                max_error
+            );
+        }
+        Err(e) => {
+            println!("❌ Optimization failed: {:?}", e);
+        }
+    }
+}
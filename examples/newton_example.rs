@@ -57,14 +57,14 @@ fn main() {
         max_iter_line_search,
         None,
     ) {
-        Ok(()) => {
+        Ok(report) => {
             let x = solver.x();
-            let eval = f_and_g(x);
             println!("✅ Optimization completed successfully!");
             println!("Final iterate: {:?}", x);
-            println!("Function value: {:.6}", eval.f());
-            println!("Gradient norm: {:.6}", eval.g().norm());
-            println!("Iterations: {}", solver.k());
+            println!("Function value: {:.6}", report.f());
+            println!("Gradient norm: {:.6}", report.gradient_norm());
+            println!("Iterations: {}", report.iterations());
+            println!("Termination reason: {}", report.termination_reason());
 
             // Show Newton decrement
             if let Some(decrement_squared) = solver.decrement_squared() {
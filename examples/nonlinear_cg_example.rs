@@ -0,0 +1,65 @@
+use nalgebra::DVector;
+use optimization_solvers::{
+    CGBeta, FuncEvalMultivariate, LineSearchSolver, NonlinearCG, StrongWolfe, Tracer,
+};
+
+fn main() {
+    // Setting up logging
+    std::env::set_var("RUST_LOG", "info");
+    let _ = Tracer::default().with_normal_stdout_layer().build();
+
+    // Extended Rosenbrock function in n dimensions: f(x) = sum_i 100 * (x_{2i} - x_{2i-1}^2)^2 + (1 - x_{2i-1})^2
+    // The minimum is at x = (1, ..., 1) with f = 0
+    let n = 1000;
+    let f_and_g = |x: &DVector<f64>| -> FuncEvalMultivariate {
+        let mut f = 0.0;
+        let mut g = DVector::zeros(x.len());
+        for i in (0..x.len()).step_by(2) {
+            let a = x[i + 1] - x[i].powi(2);
+            let b = 1.0 - x[i];
+            f += 100.0 * a.powi(2) + b.powi(2);
+            g[i] = -400.0 * x[i] * a - 2.0 * b;
+            g[i + 1] = 200.0 * a;
+        }
+        FuncEvalMultivariate::new(f, g)
+    };
+
+    // Setting up the line search (strong wolfe conditions with a small curvature parameter, as usual for conjugate gradient methods)
+    let mut ls = StrongWolfe::default().with_c2(0.1);
+
+    // Setting up the solver with the Polak-Ribière+ formula
+    let tol = 1e-6;
+    let x0 = DVector::from_fn(n, |i, _| if i % 2 == 0 { -1.2 } else { 1.0 }); // Starting point
+    let mut solver = NonlinearCG::new(tol, x0).with_beta(CGBeta::PolakRibierePlus);
+
+    // Running the solver
+    let max_iter_solver = 10000;
+    let max_iter_line_search = 50;
+
+    println!("=== Nonlinear Conjugate Gradient Example ===");
+    println!("Objective: extended Rosenbrock function");
+    println!("Dimension: {}", n);
+    println!("Beta formula: {:?}", solver.beta());
+    println!("Tolerance: {}", tol);
+    println!();
+
+    match solver.minimize(
+        &mut ls,
+        f_and_g,
+        max_iter_solver,
+        max_iter_line_search,
+        None,
+    ) {
+        Ok(report) => {
+            println!("✅ Optimization completed successfully!");
+            println!("Function value: {:.8}", report.f());
+            println!("Gradient norm: {:.8}", report.gradient_norm());
+            println!("Iterations: {}", report.iterations());
+            println!("Function evaluations: {}", report.evaluations().f());
+            println!("Termination reason: {}", report.termination_reason());
+        }
+        Err(e) => {
+            println!("❌ Optimization failed: {:?}", e);
+        }
+    }
+}
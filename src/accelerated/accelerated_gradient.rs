@@ -0,0 +1,149 @@
+use super::*;
+
+// Nesterov accelerated gradient method for unconstrained problems (see NesterovMomentum).
+#[derive(derive_getters::Getters)]
+pub struct AcceleratedGradientDescent {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    momentum: NesterovMomentum,
+}
+
+impl AcceleratedGradientDescent {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        Self {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            momentum: NesterovMomentum::default(),
+        }
+    }
+    pub fn with_momentum(mut self, momentum: NesterovMomentum) -> Self {
+        self.momentum = momentum;
+        self
+    }
+}
+
+impl LineSearchFreeSolver for AcceleratedGradientDescent {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().infinity_norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn setup(&mut self) {
+        self.momentum.reset();
+    }
+
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let (next_iterate, eval_next_iterate) =
+            self.momentum.step(&self.x, eval_x_k, oracle, &NoProx);
+        debug!(target: "accelerated_gradient", "ITERATE: {}, lipschitz estimate: {}", next_iterate, self.momentum.lipschitz());
+        self.x = next_iterate;
+        Ok(eval_next_iterate)
+    }
+}
+
+#[cfg(test)]
+mod accelerated_gradient_test {
+    use super::*;
+
+    #[test]
+    pub fn accelerated_gradient_ill_conditioned() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // ill conditioned quadratic (condition number 10^3)
+        let n = 20;
+        let diag = DVector::from_fn(n, |i, _| {
+            (10.0 as Floating).powf(3. * i as Floating / (n - 1) as Floating)
+        });
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+        let x_0 = DVector::from_element(n, 1.0);
+        let tol = 1e-6;
+
+        let mut iterations = vec![];
+        for restart in [
+            AdaptiveRestart::Never,
+            AdaptiveRestart::Function,
+            AdaptiveRestart::Gradient,
+        ] {
+            let mut solver = AcceleratedGradientDescent::new(tol, x_0.clone())
+                .with_momentum(NesterovMomentum::default().with_restart(restart));
+            let report = solver.minimize(f_and_g, 100000, None).unwrap();
+            println!("{:?}: {:?}", restart, report);
+            assert!(report.x().amax() < 1e-6);
+            // the backtracking estimate never exceeds twice the largest eigenvalue of the hessian
+            assert!(*solver.momentum().lipschitz() <= 2e3);
+            iterations.push(*report.iterations());
+        }
+        // the adaptive restart recovers the linear convergence on strongly convex functions
+        assert!(iterations[1] < iterations[0]);
+        assert!(iterations[2] < iterations[0]);
+
+        // gradient descent with a backtracking line search needs many more iterations
+        let mut solver = GradientDescent::new(tol, x_0);
+        let report = solver.minimize(
+            &mut BackTracking::new(1e-4, 0.5),
+            f_and_g,
+            100000,
+            100,
+            None,
+        );
+        let gd_iterations = report.map(|r| *r.iterations()).unwrap_or(100000);
+        println!("Gradient descent: {}", gd_iterations);
+        assert!(iterations[2] < gd_iterations);
+    }
+
+    #[test]
+    pub fn accelerated_gradient_rosenbrock() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = AcceleratedGradientDescent::new(1e-6, x_0);
+        let report = solver.minimize(f_and_g, 100000, None).unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+
+        // the backtracking cap and the stopping criterion are set on the solver
+        let mut solver = AcceleratedGradientDescent::new(1e-6, DVector::from(vec![-1.2, 1.0]))
+            .with_momentum(NesterovMomentum::default().with_max_iter_backtracking(20))
+            .with_stopping_criterion(TargetObjective::new(1e-2));
+        let report = solver.minimize(f_and_g, 100000, None).unwrap();
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::TargetObjective
+        );
+        assert!(*report.f() <= 1e-2);
+    }
+}
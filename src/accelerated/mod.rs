@@ -0,0 +1,137 @@
+use super::*;
+
+pub mod accelerated_gradient;
+pub use accelerated_gradient::*;
+pub mod projected_accelerated_gradient;
+pub use projected_accelerated_gradient::*;
+
+// Accelerated first order methods (Nesterov momentum). For L-smooth convex functions the steepest descent family converges as O(1/k), while the accelerated methods converge as O(1/k^2), which is optimal among first order methods. The iterates are
+//      y_k     = x_k + beta_k (x_k - x_{k-1})
+//      x_{k+1} = P(y_k - g(y_k) / L)
+// where P is the identity in the unconstrained case, the projection onto the feasible set with bounds and in general the proximal operator of the nonsmooth term of a composite objective (FISTA, [Beck, A., & Teboulle, M. (2009). A fast iterative shrinkage-thresholding algorithm for linear inverse problems. SIAM journal on imaging sciences]).
+// The Lipschitz constant L of the gradient is estimated with backtracking: it's increased until the quadratic upper bound f(x_{k+1}) <= f(y_k) + g(y_k)^T (x_{k+1} - y_k) + L/2 ||x_{k+1} - y_k||^2 holds.
+// The momentum makes the methods non monotone and, when the function is strongly convex, slower than they could be (the optimal momentum depends on the unknown strong convexity parameter). The adaptive restart of [O'Donoghue, B., & Candès, E. (2015). Adaptive restart for accelerated gradient schemes. Foundations of computational mathematics] resets the momentum when
+// - Function: the function value increases, F(x_{k+1}) > F(x_k) (F = f + g for composite objectives, see ProximalOperator);
+// - Gradient: the momentum points in an ascent direction of the gradient mapping, (y_k - x_{k+1})^T (x_{k+1} - x_k) > 0 (equivalent to g(y_k)^T (x_{k+1} - x_k) > 0 in the unconstrained case).
+// Since the step is computed internally with the backtracking on L, the solvers don't take a line search (see LineSearchFreeSolver).
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveRestart {
+    Never,
+    Function,
+    Gradient,
+}
+
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct NesterovMomentum {
+    lipschitz: Floating, // current estimate of the Lipschitz constant of the gradient
+    backtracking_factor: Floating, // the estimate is multiplied by this factor until the quadratic upper bound holds
+    restart: AdaptiveRestart,
+    max_iter_backtracking: usize, // maximum number of increases of the estimate at each iteration
+    t: Floating, // momentum sequence t_{k+1} = (1 + sqrt(1 + 4 t_k^2)) / 2, so that beta_k = (t_k - 1) / t_{k+1}
+    restarts: usize,
+    #[getter(skip)]
+    x_previous: Option<DVector<Floating>>,
+}
+
+impl NesterovMomentum {
+    pub fn new(lipschitz: Floating) -> Self {
+        assert!(lipschitz > 0.0, "lipschitz constant must be positive");
+        NesterovMomentum {
+            lipschitz,
+            backtracking_factor: 2.0,
+            max_iter_backtracking: 100,
+            restart: AdaptiveRestart::Gradient,
+            t: 1.0,
+            restarts: 0,
+            x_previous: None,
+        }
+    }
+    pub fn with_backtracking_factor(mut self, backtracking_factor: Floating) -> Self {
+        assert!(
+            backtracking_factor > 1.0,
+            "backtracking factor must be greater than 1"
+        );
+        self.backtracking_factor = backtracking_factor;
+        self
+    }
+    pub fn with_max_iter_backtracking(mut self, max_iter_backtracking: usize) -> Self {
+        self.max_iter_backtracking = max_iter_backtracking;
+        self
+    }
+    pub fn with_restart(mut self, restart: AdaptiveRestart) -> Self {
+        self.restart = restart;
+        self
+    }
+    pub fn reset(&mut self) {
+        self.t = 1.0;
+        self.restarts = 0;
+        self.x_previous = None;
+    }
+
+    // computes x_{k+1} (and its evaluation) from x_k with a proximal gradient step from the extrapolated point (NoProx in the unconstrained case, BoxIndicator for bounds)
+    pub fn step(
+        &mut self,
+        x_k: &DVector<Floating>,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        prox: &impl ProximalOperator,
+    ) -> (DVector<Floating>, FuncEvalMultivariate) {
+        let t_next = 0.5 * (1.0 + (1.0 + 4.0 * self.t * self.t).sqrt());
+        let beta = (self.t - 1.0) / t_next;
+
+        let extrapolated = match &self.x_previous {
+            Some(x_previous) if beta > 0.0 => {
+                let y = x_k + beta * (x_k - x_previous);
+                let eval_y = oracle.value_and_gradient(&y);
+                if eval_y.f().is_finite() {
+                    Some((y, eval_y))
+                } else {
+                    // the extrapolated point is out of domain: we restart from x_k
+                    debug!(target: "nesterov", "Extrapolated point out of domain: restarting");
+                    self.restarts += 1;
+                    None
+                }
+            }
+            _ => None,
+        };
+        let (y, eval_y) = match &extrapolated {
+            Some((y, eval_y)) => (y, eval_y),
+            None => (x_k, eval_x_k),
+        };
+
+        let (x_next, eval_next) = proximal_gradient_step(
+            y,
+            eval_y,
+            oracle,
+            prox,
+            &mut self.lipschitz,
+            self.backtracking_factor,
+            self.max_iter_backtracking,
+        );
+
+        let restart = match self.restart {
+            AdaptiveRestart::Never => false,
+            AdaptiveRestart::Function => {
+                eval_next.f() + prox.value(&x_next) > eval_x_k.f() + prox.value(x_k)
+            }
+            AdaptiveRestart::Gradient => (y - &x_next).dot(&(&x_next - x_k)) > 0.0,
+        };
+        if restart {
+            debug!(target: "nesterov", "Adaptive restart at x_k = {:?}", x_k);
+            self.t = 1.0;
+            self.restarts += 1;
+        } else {
+            self.t = t_next;
+        }
+        self.x_previous = Some(x_k.clone());
+
+        (x_next, eval_next)
+    }
+}
+
+impl Default for NesterovMomentum {
+    fn default() -> Self {
+        NesterovMomentum::new(1.0)
+    }
+}
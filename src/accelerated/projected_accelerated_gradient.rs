@@ -0,0 +1,143 @@
+use super::*;
+
+// Projected version of AcceleratedGradientDescent for problems with simple bounds (FISTA where the proximal operator is the projection onto the box). The extrapolated point y_k can be infeasible, but the iterates x_k are always feasible.
+#[derive(derive_getters::Getters)]
+pub struct ProjectedAcceleratedGradientDescent {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    momentum: NesterovMomentum,
+    bounds: BoxIndicator,
+}
+
+impl ProjectedAcceleratedGradientDescent {
+    pub fn new(
+        grad_tol: Floating,
+        x0: DVector<Floating>,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        let x0 = x0.box_projection(&lower_bound, &upper_bound);
+        Self {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            momentum: NesterovMomentum::default(),
+            bounds: BoxIndicator::new(lower_bound, upper_bound),
+        }
+    }
+    pub fn with_momentum(mut self, momentum: NesterovMomentum) -> Self {
+        self.momentum = momentum;
+        self
+    }
+    // gradient with the components pushing x_k out of the box set to zero (see HasProjectedGradient)
+    fn projected_gradient(&self, eval: &FuncEvalMultivariate) -> DVector<Floating> {
+        let mut proj_grad = eval.g().clone();
+        for (i, x) in self.x.iter().enumerate() {
+            if (x == &self.lower_bound()[i] && proj_grad[i] > 0.0)
+                || (x == &self.upper_bound()[i] && proj_grad[i] < 0.0)
+            {
+                proj_grad[i] = 0.0;
+            }
+        }
+        proj_grad
+    }
+}
+
+impl HasBounds for ProjectedAcceleratedGradientDescent {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        self.bounds.lower_bound()
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        self.bounds.upper_bound()
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.bounds.set_lower_bound(lower_bound);
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.bounds.set_upper_bound(upper_bound);
+    }
+}
+
+impl LineSearchFreeSolver for ProjectedAcceleratedGradientDescent {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (self.projected_gradient(eval).infinity_norm() < self.grad_tol)
+            .then_some(TerminationReason::GradientTolerance)
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
+    fn setup(&mut self) {
+        self.momentum.reset();
+    }
+
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let (next_iterate, eval_next_iterate) =
+            self.momentum.step(&self.x, eval_x_k, oracle, &self.bounds);
+        debug!(target: "projected_accelerated_gradient", "ITERATE: {}, lipschitz estimate: {}", next_iterate, self.momentum.lipschitz());
+        self.x = next_iterate;
+        Ok(eval_next_iterate)
+    }
+}
+
+#[cfg(test)]
+mod projected_accelerated_gradient_test {
+    use super::*;
+
+    #[test]
+    pub fn projected_accelerated_gradient_active_bounds() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // unconstrained minimizer at (1, 2, ..., n): the upper bound at n / 2 is active for half of the components
+        let n = 20;
+        let diag = DVector::from_fn(n, |i, _| 1. + 99. * (i as Floating) / (n as Floating));
+        let center = DVector::from_fn(n, |i, _| (i + 1) as Floating);
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = (x - &center).component_mul(&diag);
+            let f = 0.5 * (x - &center).dot(&g);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_element(n, 0.0);
+        let upper_bound = DVector::from_element(n, (n / 2) as Floating);
+        let x_0 = DVector::from_element(n, -5.0);
+
+        let mut solver = ProjectedAcceleratedGradientDescent::new(
+            1e-8,
+            x_0,
+            lower_bound.clone(),
+            upper_bound.clone(),
+        );
+        let report = solver.minimize(f_and_g, 10000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        let solution = center.box_projection(&lower_bound, &upper_bound);
+        assert!((report.x() - solution).amax() < 1e-8);
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::GradientTolerance
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use super::*;
+
+// Nonlinear conjugate gradient methods (chapter 5.2 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.] and the survey [Hager, W. W., & Zhang, H. (2006). A survey of nonlinear conjugate gradient methods.]).
+// The direction is d_k = -g_k + beta_k * d_{k-1}, where the scalar beta_k is chosen so that on a strongly convex quadratic with exact line search the directions are conjugate with respect to the hessian. Only the previous gradient and direction are stored, so memory is O(n) like the gradient descent.
+// The method is restarted (beta_k = 0) every `restart_every` iterations (n by default) and when consecutive gradients are far from orthogonal (Powell restart: |g_k^T g_{k-1}| >= nu * ||g_k||^2). If the direction is not a descent direction, we fall back to the steepest descent direction.
+// Fletcher-Reeves and Dai-Yuan need a strong wolfe line search (with c2 < 0.5 for Fletcher-Reeves) to generate descent directions; the Hager-Zhang formula generates descent directions independently of the line search and it's meant to be paired with the HagerZhang line search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CGBeta {
+    // g_k^T g_k / g_{k-1}^T g_{k-1}
+    FletcherReeves,
+    // max(0, g_k^T y_k / g_{k-1}^T g_{k-1})
+    PolakRibierePlus,
+    // g_k^T y_k / d_{k-1}^T y_k
+    HestenesStiefel,
+    // g_k^T g_k / d_{k-1}^T y_k
+    DaiYuan,
+    // (y_k - 2 d_{k-1} ||y_k||^2 / d_{k-1}^T y_k)^T g_k / d_{k-1}^T y_k, truncated from below as in CG_DESCENT
+    HagerZhang,
+}
+
+#[derive(derive_getters::Getters)]
+pub struct NonlinearCG {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    beta: CGBeta,
+    restart_every: usize, // number of iterations after which the method is restarted
+    powell_nu: Option<Floating>, // threshold of the Powell restart (None disables it)
+    g_previous: Option<DVector<Floating>>,
+    d_previous: Option<DVector<Floating>>,
+    iterations_since_restart: usize,
+}
+
+impl NonlinearCG {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        let restart_every = x0.len().max(1);
+        Self {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            beta: CGBeta::PolakRibierePlus,
+            restart_every,
+            powell_nu: Some(0.1),
+            g_previous: None,
+            d_previous: None,
+            iterations_since_restart: 0,
+        }
+    }
+    pub fn with_beta(mut self, beta: CGBeta) -> Self {
+        self.beta = beta;
+        self
+    }
+    pub fn with_restart_every(mut self, restart_every: usize) -> Self {
+        assert!(restart_every > 0, "restart period must be positive");
+        self.restart_every = restart_every;
+        self
+    }
+    pub fn with_powell_restart(mut self, powell_nu: Option<Floating>) -> Self {
+        if let Some(nu) = powell_nu {
+            assert!(nu > 0.0, "Powell restart threshold must be positive");
+        }
+        self.powell_nu = powell_nu;
+        self
+    }
+
+    // None means that the method has to be restarted
+    fn compute_beta(
+        &self,
+        g: &DVector<Floating>,
+        g_previous: &DVector<Floating>,
+        d_previous: &DVector<Floating>,
+    ) -> Option<Floating> {
+        let y = g - g_previous;
+        let gg_previous = g_previous.norm_squared();
+        let dy = d_previous.dot(&y);
+        let beta = match self.beta {
+            CGBeta::FletcherReeves => g.norm_squared() / gg_previous,
+            CGBeta::PolakRibierePlus => (g.dot(&y) / gg_previous).max(0.0),
+            CGBeta::HestenesStiefel => g.dot(&y) / dy,
+            CGBeta::DaiYuan => g.norm_squared() / dy,
+            CGBeta::HagerZhang => {
+                let beta = (&y - d_previous * (2.0 * y.norm_squared() / dy)).dot(g) / dy;
+                // lower bound eta_k = -1 / (||d_{k-1}|| * min(eta, ||g_{k-1}||)) with eta = 0.01
+                let eta = -1.0 / (d_previous.norm() * gg_previous.sqrt().min(0.01));
+                beta.max(eta)
+            }
+        };
+        beta.is_finite().then_some(beta)
+    }
+
+    fn powell_restart(&self, g: &DVector<Floating>, g_previous: &DVector<Floating>) -> bool {
+        match self.powell_nu {
+            Some(nu) => g.dot(g_previous).abs() >= nu * g.norm_squared(),
+            None => false,
+        }
+    }
+}
+
+impl ComputeDirection for NonlinearCG {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let g = eval.g();
+        let beta = match (&self.g_previous, &self.d_previous) {
+            (Some(g_previous), Some(d_previous)) => {
+                if self.iterations_since_restart >= self.restart_every {
+                    debug!(target: "nonlinear_cg", "Periodic restart after {} iterations", self.iterations_since_restart);
+                    None
+                } else if self.powell_restart(g, g_previous) {
+                    debug!(target: "nonlinear_cg", "Powell restart: consecutive gradients are far from orthogonal");
+                    None
+                } else {
+                    self.compute_beta(g, g_previous, d_previous)
+                }
+            }
+            _ => None,
+        };
+
+        let direction = match (beta, &self.d_previous) {
+            (Some(beta), Some(d_previous)) => {
+                let direction = -g + beta * d_previous;
+                if direction.dot(g) < 0.0 {
+                    self.iterations_since_restart += 1;
+                    direction
+                } else {
+                    debug!(target: "nonlinear_cg", "Not a descent direction (beta = {}): falling back to steepest descent", beta);
+                    self.iterations_since_restart = 1;
+                    -g
+                }
+            }
+            _ => {
+                self.iterations_since_restart = 1;
+                -g
+            }
+        };
+
+        self.g_previous = Some(g.clone());
+        self.d_previous = Some(direction.clone());
+        Ok(direction)
+    }
+}
+
+impl LineSearchSolver for NonlinearCG {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        // we verify that the infinity norm of the gradient is below the tolerance
+        (eval.g().amax() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+
+    fn setup(&mut self) {
+        // a new minimization starts with a steepest descent step
+        self.g_previous = None;
+        self.d_previous = None;
+        self.iterations_since_restart = 0;
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        debug!(target: "nonlinear_cg", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
+
+        let next_iterate = self.xk() + step * direction;
+
+        *self.xk_mut() = next_iterate;
+
+        Ok(eval_next_iterate)
+    }
+}
+
+#[cfg(test)]
+mod nonlinear_cg_test {
+    use super::*;
+
+    fn rosenbrock(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+        let g = DVector::from(vec![
+            -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+            200. * (x[1] - x[0].powi(2)),
+        ]);
+        (f, g).into()
+    }
+
+    #[test]
+    pub fn nonlinear_cg_rosenbrock_strong_wolfe() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        for beta in [
+            CGBeta::FletcherReeves,
+            CGBeta::PolakRibierePlus,
+            CGBeta::HestenesStiefel,
+            CGBeta::DaiYuan,
+            CGBeta::HagerZhang,
+        ] {
+            let mut ls = StrongWolfe::default().with_c2(0.1);
+            let x_0 = DVector::from(vec![-1.2, 1.0]);
+            let mut solver = NonlinearCG::new(1e-6, x_0).with_beta(beta);
+
+            let report = solver
+                .minimize(&mut ls, rosenbrock, 10000, 100, None)
+                .unwrap();
+
+            println!("{:?}: {:?}", beta, report);
+            assert!((report.x()[0] - 1.).abs() < 1e-4, "{:?}", beta);
+            assert!((report.x()[1] - 1.).abs() < 1e-4, "{:?}", beta);
+        }
+    }
+
+    #[test]
+    pub fn nonlinear_cg_hager_zhang_large_scale() {
+        // ill-conditioned separable quadratic with 10^4 variables
+        let n = 10_000;
+        let diag = DVector::from_fn(n, |i, _| 1. + 999. * (i as Floating) / (n as Floating));
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+        let mut ls = HagerZhang::default();
+        let x_0 = DVector::from_element(n, 1.0);
+        let mut solver = NonlinearCG::new(1e-6, x_0).with_beta(CGBeta::HagerZhang);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Iterations: {:?}", report.iterations());
+        assert!(report.x().amax() < 1e-6);
+    }
+
+    #[test]
+    pub fn nonlinear_cg_restarts() {
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = NonlinearCG::new(1e-6, x_0)
+            .with_beta(CGBeta::FletcherReeves)
+            .with_restart_every(3)
+            .with_powell_restart(None);
+        let g_0 = DVector::from(vec![1.0, 0.0]);
+        let g_1 = DVector::from(vec![0.0, 1.0]);
+
+        // the first direction is the steepest descent
+        let d = solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_0.clone()))
+            .unwrap();
+        assert_eq!(d, -&g_0);
+        // beta = ||g_1||^2 / ||g_0||^2 = 1
+        let d = solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_1.clone()))
+            .unwrap();
+        assert_eq!(d, DVector::from(vec![-1.0, -1.0]));
+        let d = solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_0.clone()))
+            .unwrap();
+        assert_eq!(d, DVector::from(vec![-2.0, -1.0]));
+        // periodic restart
+        let d = solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_1.clone()))
+            .unwrap();
+        assert_eq!(d, -&g_1);
+
+        // Powell restart: the gradients are not orthogonal
+        let mut solver = solver.with_powell_restart(Some(0.1));
+        solver.setup();
+        solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_0.clone()))
+            .unwrap();
+        let d = solver
+            .compute_direction(&FuncEvalMultivariate::new(0.0, g_0.clone()))
+            .unwrap();
+        assert_eq!(d, -&g_0);
+    }
+}
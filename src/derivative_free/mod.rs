@@ -0,0 +1,116 @@
+use super::*;
+
+pub mod nelder_mead;
+pub use nelder_mead::*;
+pub mod pattern_search;
+pub use pattern_search::*;
+pub mod powell;
+pub use powell::*;
+
+// Derivative-free (direct search) methods, for black-box objectives whose gradient is not available ([Conn, A. R., Scheinberg, K., & Vicente, L. N. (2009). Introduction to derivative-free optimization. SIAM] and [Kolda, T. G., Lewis, R. M., & Torczon, V. (2003). Optimization by direct search: New perspectives on some classical and modern methods. SIAM review]).
+// The oracle provides only the image (see ValueObjective): non finite values are treated as +infinity, so that the methods simply move away from the points out of domain.
+// They need many more evaluations than the gradient based methods and scale poorly with the dimension, so they are meant for small problems (tens of variables) or for objectives that are not differentiable.
+
+// Value-only oracle. Any closure FnMut(&DVector) -> Floating is a ValueObjective (see the blanket implementation below); an Objective can be used through a closure calling Objective::value.
+pub trait ValueObjective {
+    fn value(&mut self, x: &DVector<Floating>) -> Floating;
+}
+
+impl<F> ValueObjective for F
+where
+    F: FnMut(&DVector<Floating>) -> Floating,
+{
+    fn value(&mut self, x: &DVector<Floating>) -> Floating {
+        self(x)
+    }
+}
+
+// Wrapper counting the evaluations of the image (see CountedObjective)
+pub struct CountedValueObjective<O> {
+    objective: O,
+    counts: EvaluationCounts,
+}
+
+impl<O: ValueObjective> CountedValueObjective<O> {
+    pub fn new(objective: O) -> Self {
+        CountedValueObjective {
+            objective,
+            counts: EvaluationCounts::default(),
+        }
+    }
+    pub fn counts(&self) -> EvaluationCounts {
+        self.counts
+    }
+}
+
+impl<O: ValueObjective> ValueObjective for CountedValueObjective<O> {
+    fn value(&mut self, x: &DVector<Floating>) -> Floating {
+        self.counts.record_value();
+        let f = self.objective.value(x);
+        if f.is_nan() {
+            Floating::INFINITY
+        } else {
+            f
+        }
+    }
+}
+
+//Template pattern for derivative-free solvers (analogous to LineSearchSolver)
+pub trait DerivativeFreeSolver {
+    fn xk(&self) -> &DVector<Floating>;
+    // image at x_k
+    fn fk(&self) -> Floating;
+    fn k(&self) -> &usize;
+    fn k_mut(&mut self) -> &mut usize;
+    fn has_converged(&self) -> Option<TerminationReason>;
+    // initializes the state of the method at x_k (e.g. the initial simplex), so that restarting the minimization starts from scratch
+    fn setup(&mut self, oracle: &mut impl ValueObjective);
+    // one iteration of the method, which updates x_k
+    fn iterate(&mut self, oracle: &mut impl ValueObjective) -> Result<(), SolverError>;
+
+    fn minimize(
+        &mut self,
+        oracle: impl ValueObjective,
+        max_iter_solver: usize,
+        mut callback: Option<&mut dyn FnMut(&Self)>,
+    ) -> Result<MinimizationReport, SolverError> {
+        let stopwatch = Stopwatch::start();
+        let mut oracle = CountedValueObjective::new(oracle);
+
+        *self.k_mut() = 0;
+        self.setup(&mut oracle);
+        if !self.fk().is_finite() {
+            error!(target: "solver","Minimization completed: initial point is out of domain");
+            return Err(SolverError::OutOfDomain);
+        }
+
+        while &max_iter_solver > self.k() {
+            if let Some(termination_reason) = self.has_converged() {
+                info!(
+                    target: "solver",
+                    "Minimization completed: {} in {} iterations",
+                    termination_reason,
+                    self.k()
+                );
+                return Ok(MinimizationReport::without_gradient(
+                    self.xk().clone(),
+                    self.fk(),
+                    *self.k(),
+                    oracle.counts(),
+                    stopwatch.elapsed(),
+                    termination_reason,
+                ));
+            }
+
+            self.iterate(&mut oracle)?;
+            debug!(target: "solver","Iterate: {:?}, Function eval: {:?}", self.xk(), self.fk());
+
+            *self.k_mut() += 1;
+            if let Some(callback) = callback.as_mut() {
+                callback(self);
+            }
+        }
+        warn!(target: "solver","Minimization completed: max iter reached during minimization");
+        Err(SolverError::MaxIterReached)
+    }
+}
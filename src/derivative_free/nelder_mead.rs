@@ -0,0 +1,339 @@
+use super::*;
+
+// Nelder-Mead simplex method ([Nelder, J. A., & Mead, R. (1965). A simplex method for function minimization. The computer journal], with the tie-breaking rules and the acceptance conditions of [Lagarias, J. C., Reeds, J. A., Wright, M. H., & Wright, P. E. (1998). Convergence properties of the Nelder-Mead simplex method in low dimensions. SIAM Journal on optimization]).
+// At each iteration the worst vertex of the simplex is replaced by a point on the line through it and the centroid of the others (reflection, expansion or contraction); if none of them is good enough, the simplex is shrunk towards the best vertex.
+// By default the parameters depend on the dimension as in [Gao, F., & Han, L. (2012). Implementing the Nelder-Mead simplex algorithm with adaptive parameters. Computational Optimization and Applications]: reflection 1, expansion 1 + 2/n, contraction 3/4 - 1/(2n), shrink 1 - 1/n, which reduce to the standard ones (1, 2, 1/2, 1/2) for n = 2 and prevent the expansions from distorting the simplex in higher dimensions.
+// Bounds are handled by projecting every trial point onto the box.
+#[derive(derive_getters::Getters)]
+pub struct NelderMead {
+    tol: Floating, // tolerance on both the diameter of the simplex and the spread of the images at the vertices
+    x: DVector<Floating>, // best vertex
+    f: Floating,
+    k: usize,
+    reflection: Floating,
+    expansion: Floating,
+    contraction: Floating,
+    shrink: Floating,
+    initial_step: Option<Floating>, // edge length of the initial simplex (relative to |x0_i| when None)
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+    simplex: Vec<(DVector<Floating>, Floating)>, // vertices sorted by image
+    shrinks: usize,
+}
+
+impl NelderMead {
+    pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
+        let n = x0.len();
+        let dimension = n.max(2) as Floating;
+        NelderMead {
+            tol,
+            x: x0,
+            f: Floating::INFINITY,
+            k: 0,
+            reflection: 1.0,
+            expansion: 1.0 + 2.0 / dimension,
+            contraction: 0.75 - 1.0 / (2.0 * dimension),
+            shrink: 1.0 - 1.0 / dimension,
+            initial_step: None,
+            lower_bound: DVector::from_element(n, Floating::NEG_INFINITY),
+            upper_bound: DVector::from_element(n, Floating::INFINITY),
+            simplex: vec![],
+            shrinks: 0,
+        }
+    }
+    // the standard parameters are (1, 2, 0.5, 0.5)
+    pub fn with_parameters(
+        mut self,
+        reflection: Floating,
+        expansion: Floating,
+        contraction: Floating,
+        shrink: Floating,
+    ) -> Self {
+        assert!(reflection > 0.0, "reflection must be positive");
+        assert!(
+            expansion > 1.0 && expansion > reflection,
+            "expansion must be greater than 1 and than the reflection"
+        );
+        assert!(
+            contraction > 0.0 && contraction < 1.0,
+            "contraction must be in (0, 1)"
+        );
+        assert!(shrink > 0.0 && shrink < 1.0, "shrink must be in (0, 1)");
+        self.reflection = reflection;
+        self.expansion = expansion;
+        self.contraction = contraction;
+        self.shrink = shrink;
+        self
+    }
+    pub fn with_initial_step(mut self, initial_step: Floating) -> Self {
+        assert!(initial_step > 0.0, "initial step must be positive");
+        self.initial_step = Some(initial_step);
+        self
+    }
+    pub fn with_bounds(
+        mut self,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        assert!(
+            lower_bound.len() == self.x.len() && upper_bound.len() == self.x.len(),
+            "bounds must have dimension n"
+        );
+        // a zero width box would make the initial simplex flat (fixed variables should be removed from the problem instead)
+        assert!(
+            lower_bound
+                .iter()
+                .zip(upper_bound.iter())
+                .all(|(l, u)| l < u),
+            "lower bound must be strictly below the upper bound"
+        );
+        self.x = self.x.box_projection(&lower_bound, &upper_bound);
+        self.lower_bound = lower_bound;
+        self.upper_bound = upper_bound;
+        self
+    }
+
+    fn evaluate(
+        &self,
+        oracle: &mut impl ValueObjective,
+        x: DVector<Floating>,
+    ) -> (DVector<Floating>, Floating) {
+        let x = x.box_projection(&self.lower_bound, &self.upper_bound);
+        let f = oracle.value(&x);
+        (x, f)
+    }
+
+    fn sort_simplex(&mut self) {
+        // stable sort: among vertices with the same image the older ones come first (Lagarias et al.)
+        self.simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        self.x = self.simplex[0].0.clone();
+        self.f = self.simplex[0].1;
+    }
+
+    // point on the line through the worst vertex and the centroid of the others: c + coefficient * (c - x_worst)
+    fn along_worst(
+        &self,
+        centroid: &DVector<Floating>,
+        coefficient: Floating,
+    ) -> DVector<Floating> {
+        let worst = &self.simplex[self.simplex.len() - 1].0;
+        centroid + coefficient * (centroid - worst)
+    }
+}
+
+impl HasBounds for NelderMead {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
+    }
+}
+
+impl DerivativeFreeSolver for NelderMead {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn fk(&self) -> Floating {
+        self.f
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn has_converged(&self) -> Option<TerminationReason> {
+        let (best, f_best) = &self.simplex[0];
+        let diameter = self
+            .simplex
+            .iter()
+            .map(|(x, _)| (x - best).amax())
+            .fold(0.0, Floating::max);
+        let spread = self
+            .simplex
+            .iter()
+            .map(|(_, f)| (f - f_best).abs())
+            .fold(0.0, Floating::max);
+        (diameter <= self.tol && spread <= self.tol).then_some(TerminationReason::SimplexTolerance)
+    }
+
+    fn setup(&mut self, oracle: &mut impl ValueObjective) {
+        // right angled simplex at x0 (as in scipy: edges of 5% of |x0_i|, or 0.00025 for the null components)
+        let x0 = self.x.clone();
+        self.simplex = vec![self.evaluate(oracle, x0.clone())];
+        for i in 0..x0.len() {
+            let step = self.initial_step.unwrap_or(if x0[i] != 0.0 {
+                0.05 * x0[i].abs()
+            } else {
+                0.00025
+            });
+            let mut vertex = x0.clone();
+            // towards the interior of the box if x0 is close to the upper bound, and shortened if the box is narrower than the step on both sides (so that the projection doesn't flatten the simplex)
+            let room_up = self.upper_bound[i] - x0[i];
+            let room_down = x0[i] - self.lower_bound[i];
+            vertex[i] += if step <= room_up {
+                step
+            } else if step <= room_down {
+                -step
+            } else if room_up >= room_down {
+                room_up
+            } else {
+                -room_down
+            };
+            let vertex = self.evaluate(oracle, vertex);
+            self.simplex.push(vertex);
+        }
+        self.shrinks = 0;
+        self.sort_simplex();
+    }
+
+    fn iterate(&mut self, oracle: &mut impl ValueObjective) -> Result<(), SolverError> {
+        let n = self.simplex.len() - 1;
+        let f_best = self.simplex[0].1;
+        let f_second_worst = self.simplex[n - 1].1;
+        let f_worst = self.simplex[n].1;
+        let centroid = self.simplex[..n]
+            .iter()
+            .fold(DVector::zeros(self.x.len()), |acc, (x, _)| acc + x)
+            / n as Floating;
+
+        let (x_r, f_r) = self.evaluate(oracle, self.along_worst(&centroid, self.reflection));
+        let accepted = if f_r < f_best {
+            let (x_e, f_e) = self.evaluate(
+                oracle,
+                self.along_worst(&centroid, self.reflection * self.expansion),
+            );
+            trace!(target: "nelder_mead", "Expansion: f_e = {}, f_r = {}", f_e, f_r);
+            if f_e < f_r {
+                Some((x_e, f_e))
+            } else {
+                Some((x_r, f_r))
+            }
+        } else if f_r < f_second_worst {
+            trace!(target: "nelder_mead", "Reflection: f_r = {}", f_r);
+            Some((x_r, f_r))
+        } else if f_r < f_worst {
+            let (x_c, f_c) = self.evaluate(
+                oracle,
+                self.along_worst(&centroid, self.reflection * self.contraction),
+            );
+            trace!(target: "nelder_mead", "Outside contraction: f_c = {}, f_r = {}", f_c, f_r);
+            (f_c <= f_r).then_some((x_c, f_c))
+        } else {
+            let (x_c, f_c) = self.evaluate(oracle, self.along_worst(&centroid, -self.contraction));
+            trace!(target: "nelder_mead", "Inside contraction: f_c = {}, f_worst = {}", f_c, f_worst);
+            (f_c < f_worst).then_some((x_c, f_c))
+        };
+
+        match accepted {
+            Some(vertex) => self.simplex[n] = vertex,
+            None => {
+                debug!(target: "nelder_mead", "Shrinking the simplex towards the best vertex");
+                self.shrinks += 1;
+                let best = self.simplex[0].0.clone();
+                for i in 1..=n {
+                    let vertex = &best + self.shrink * (&self.simplex[i].0 - &best);
+                    self.simplex[i] = self.evaluate(oracle, vertex);
+                }
+            }
+        }
+        self.sort_simplex();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod nelder_mead_test {
+    use super::*;
+
+    fn rosenbrock(x: &DVector<Floating>) -> Floating {
+        100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2)
+    }
+
+    #[test]
+    pub fn nelder_mead_rosenbrock() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = NelderMead::new(1e-10, x_0);
+        let report = solver.minimize(rosenbrock, 10000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::SimplexTolerance
+        );
+        assert_eq!(report.evaluations().g(), &0);
+        assert!(report.gradient_norm().is_nan());
+    }
+
+    #[test]
+    pub fn nelder_mead_adaptive_parameters() {
+        // in higher dimensions the standard parameters need many more evaluations than the adaptive ones (the gap grows with n)
+        let n = 16;
+        let diag = DVector::from_fn(n, |i, _| 1.0 + i as Floating);
+        let f = |x: &DVector<Floating>| -> Floating {
+            let shifted = x.add_scalar(-1.0);
+            0.5 * shifted.dot(&shifted.component_mul(&diag)) + 0.1 * shifted.norm_squared().powi(2)
+        };
+        let x_0 = DVector::zeros(n);
+        let mut solver = NelderMead::new(1e-8, x_0.clone()).with_initial_step(0.5);
+        let report = solver.minimize(f, 100000, None).unwrap();
+        println!("Adaptive: {:?}", report);
+        assert!((report.x().add_scalar(-1.0)).amax() < 1e-3);
+
+        let mut standard = NelderMead::new(1e-8, x_0)
+            .with_initial_step(0.5)
+            .with_parameters(1.0, 2.0, 0.5, 0.5);
+        let standard_evaluations = standard
+            .minimize(f, 100000, None)
+            .map(|r| *r.evaluations().f())
+            .unwrap_or(usize::MAX);
+        println!("Standard: {} evaluations", standard_evaluations);
+        assert!(report.evaluations().f() < &standard_evaluations);
+    }
+
+    #[test]
+    pub fn nelder_mead_bounds() {
+        // unconstrained minimizer at (2, -3, 0.5): the bounds are active on the first two components
+        let f = |x: &DVector<Floating>| -> Floating {
+            (x[0] - 2.).powi(2) + 2. * (x[1] + 3.).powi(2) + 3. * (x[2] - 0.5).powi(2)
+        };
+        let lower_bound = DVector::from_element(3, -1.0);
+        let upper_bound = DVector::from_element(3, 1.0);
+        let x_0 = DVector::from(vec![0.9, 0.0, 0.0]);
+        let mut solver = NelderMead::new(1e-10, x_0)
+            .with_initial_step(0.1)
+            .with_bounds(lower_bound, upper_bound);
+        let report = solver.minimize(f, 10000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x() - DVector::from(vec![1.0, -1.0, 0.5])).amax() < 1e-4);
+
+        // box narrower than the initial step: the simplex is shrunk to fit instead of being flattened by the projection
+        let f =
+            |x: &DVector<Floating>| -> Floating { (x[0] - 0.3).powi(2) + (x[1] - 0.01).powi(2) };
+        let mut solver = NelderMead::new(1e-10, DVector::from(vec![0.0, 0.0]))
+            .with_initial_step(1.0)
+            .with_bounds(
+                DVector::from(vec![-1.0, -0.02]),
+                DVector::from(vec![1.0, 0.02]),
+            );
+        let report = solver.minimize(f, 10000, None).unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x() - DVector::from(vec![0.3, 0.01])).amax() < 1e-4);
+    }
+}
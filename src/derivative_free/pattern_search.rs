@@ -0,0 +1,193 @@
+use super::*;
+
+// Compass search, the simplest pattern search method (section 3 in [Kolda, T. G., Lewis, R. M., & Torczon, V. (2003). Optimization by direct search: New perspectives on some classical and modern methods. SIAM review]).
+// At each iteration the points x_k +- step * e_i are polled: the first one (or the best one, with a complete poll) improving the image becomes the next iterate and the step is expanded, otherwise the step is contracted. Since the polling directions positively span the space, when the step goes to zero the iterates approach a stationary point (for continuously differentiable objectives).
+// With bounds the polled points are projected onto the box (the coordinate directions conform to the box, so the projection never misses a feasible descent direction).
+#[derive(derive_getters::Getters)]
+pub struct PatternSearch {
+    tol: Floating, // minimum step
+    x: DVector<Floating>,
+    f: Floating,
+    k: usize,
+    initial_step: Floating,
+    step: Floating,
+    expansion: Floating, // the step is multiplied by this factor after a successful poll
+    contraction: Floating, // the step is multiplied by this factor after an unsuccessful poll
+    complete_poll: bool, // poll all the directions and move to the best point, instead of the first improving one
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+}
+
+impl PatternSearch {
+    pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
+        let n = x0.len();
+        PatternSearch {
+            tol,
+            x: x0,
+            f: Floating::INFINITY,
+            k: 0,
+            initial_step: 1.0,
+            step: 1.0,
+            expansion: 1.0,
+            contraction: 0.5,
+            complete_poll: false,
+            lower_bound: DVector::from_element(n, Floating::NEG_INFINITY),
+            upper_bound: DVector::from_element(n, Floating::INFINITY),
+        }
+    }
+    pub fn with_initial_step(mut self, initial_step: Floating) -> Self {
+        assert!(initial_step > 0.0, "initial step must be positive");
+        self.initial_step = initial_step;
+        self
+    }
+    pub fn with_factors(mut self, expansion: Floating, contraction: Floating) -> Self {
+        assert!(expansion >= 1.0, "expansion must be at least 1");
+        assert!(
+            contraction > 0.0 && contraction < 1.0,
+            "contraction must be in (0, 1)"
+        );
+        self.expansion = expansion;
+        self.contraction = contraction;
+        self
+    }
+    pub fn with_complete_poll(mut self, complete_poll: bool) -> Self {
+        self.complete_poll = complete_poll;
+        self
+    }
+    pub fn with_bounds(
+        mut self,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        assert!(
+            lower_bound.len() == self.x.len() && upper_bound.len() == self.x.len(),
+            "bounds must have dimension n"
+        );
+        self.x = self.x.box_projection(&lower_bound, &upper_bound);
+        self.lower_bound = lower_bound;
+        self.upper_bound = upper_bound;
+        self
+    }
+}
+
+impl HasBounds for PatternSearch {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
+    }
+}
+
+impl DerivativeFreeSolver for PatternSearch {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn fk(&self) -> Floating {
+        self.f
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn has_converged(&self) -> Option<TerminationReason> {
+        (self.step < self.tol).then_some(TerminationReason::StepSizeTolerance)
+    }
+
+    fn setup(&mut self, oracle: &mut impl ValueObjective) {
+        self.step = self.initial_step;
+        self.f = oracle.value(&self.x);
+    }
+
+    fn iterate(&mut self, oracle: &mut impl ValueObjective) -> Result<(), SolverError> {
+        let mut best: Option<(DVector<Floating>, Floating)> = None;
+        'poll: for i in 0..self.x.len() {
+            for sign in [1.0, -1.0] {
+                let mut trial = self.x.clone();
+                trial[i] += sign * self.step;
+                let trial = trial.box_projection(&self.lower_bound, &self.upper_bound);
+                if trial == self.x {
+                    // the direction points out of the box
+                    continue;
+                }
+                let f_trial = oracle.value(&trial);
+                if f_trial < best.as_ref().map_or(self.f, |(_, f)| *f) {
+                    best = Some((trial, f_trial));
+                    if !self.complete_poll {
+                        break 'poll;
+                    }
+                }
+            }
+        }
+
+        match best {
+            Some((x, f)) => {
+                trace!(target: "pattern_search", "Successful poll: f = {}, step = {}", f, self.step);
+                self.x = x;
+                self.f = f;
+                self.step *= self.expansion;
+            }
+            None => {
+                trace!(target: "pattern_search", "Unsuccessful poll: contracting the step to {}", self.step * self.contraction);
+                self.step *= self.contraction;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod pattern_search_test {
+    use super::*;
+
+    #[test]
+    pub fn pattern_search_quadratic() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let f = |x: &DVector<Floating>| -> Floating {
+            (x[0] - 1.).powi(2) + 5. * (x[1] + 2.).powi(2) + (x[2] - 0.3).powi(2) + x[0] * x[2]
+        };
+        // stationary point of the quadratic: the gradient (2 (x0 - 1) + x2, 10 (x1 + 2), 2 (x2 - 0.3) + x0) vanishes
+        let solution = DVector::from(vec![1.7 / 1.5, -2.0, 0.3 - 0.85 / 1.5]);
+        let x_0 = DVector::zeros(3);
+
+        for complete_poll in [false, true] {
+            let mut solver = PatternSearch::new(1e-8, x_0.clone())
+                .with_factors(2.0, 0.5)
+                .with_complete_poll(complete_poll);
+            let report = solver.minimize(f, 100000, None).unwrap();
+            println!("Report: {:?}", report);
+            assert!((report.x() - &solution).amax() < 1e-6);
+            assert_eq!(
+                report.termination_reason(),
+                &TerminationReason::StepSizeTolerance
+            );
+        }
+    }
+
+    #[test]
+    pub fn pattern_search_bounds_nonsmooth() {
+        // nonsmooth objective with the minimizer (1.5, -2) outside of the box
+        let f = |x: &DVector<Floating>| -> Floating { (x[0] - 1.5).abs() + 2. * (x[1] + 2.).abs() };
+        let lower_bound = DVector::from_element(2, -1.0);
+        let upper_bound = DVector::from_element(2, 1.0);
+        let mut solver = PatternSearch::new(1e-10, DVector::from(vec![0.3, 0.7]))
+            .with_bounds(lower_bound, upper_bound);
+        let report = solver.minimize(f, 100000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x() - DVector::from(vec![1.0, -1.0])).amax() < 1e-8);
+    }
+}
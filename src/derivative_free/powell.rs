@@ -0,0 +1,215 @@
+use super::*;
+
+// Powell's conjugate direction method ([Powell, M. J. D. (1964). An efficient method for finding the minimum of a function of several variables without calculating derivatives. The computer journal], in the version of section 10.7 of [Press, W. H., Teukolsky, S. A., Vetterling, W. T., & Flannery, B. P. (2007). Numerical recipes]).
+// Each iteration minimizes the function along each of n directions in turn (starting from the coordinate directions); then the overall displacement of the iteration becomes a new direction, replacing the one along which the function decreased the most. On a quadratic the directions become mutually conjugate, so the method terminates in about n iterations, as the conjugate gradient does with the gradient.
+// The direction is not replaced when that would make the set nearly linearly dependent (heuristic of Numerical Recipes), and the line minimizations are derivative-free (golden section search after bracketing the minimum).
+// The method stops when an iteration decreases the function by a relative amount below the tolerance.
+#[derive(derive_getters::Getters)]
+pub struct Powell {
+    tol: Floating, // tolerance on the relative decrease of the function over an iteration
+    x: DVector<Floating>,
+    f: Floating,
+    k: usize,
+    line_tol: Floating, // relative tolerance of the line minimizations
+    directions: Vec<DVector<Floating>>,
+    f_previous: Option<Floating>, // image at the beginning of the last iteration
+}
+
+const GOLDEN_RATIO: Floating = 1.618033988749895;
+
+impl Powell {
+    pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
+        Powell {
+            tol,
+            x: x0,
+            f: Floating::INFINITY,
+            k: 0,
+            line_tol: 1e-8,
+            directions: vec![],
+            f_previous: None,
+        }
+    }
+    pub fn with_line_tol(mut self, line_tol: Floating) -> Self {
+        assert!(line_tol > 0.0, "line tolerance must be positive");
+        self.line_tol = line_tol;
+        self
+    }
+
+    // Minimizes t -> f(x + t d), where f(x) = f_x. Returns the minimizer and its image (t = 0 if no decrease was found).
+    fn line_minimization(
+        &self,
+        oracle: &mut impl ValueObjective,
+        x: &DVector<Floating>,
+        d: &DVector<Floating>,
+        f_x: Floating,
+    ) -> (Floating, Floating) {
+        let mut phi = |t: Floating| oracle.value(&(x + t * d));
+
+        // bracketing: a, b, c with f(b) <= f(a) and f(b) <= f(c)
+        let (mut a, mut f_a) = (0.0, f_x);
+        let (mut b, mut f_b) = (1.0, phi(1.0));
+        if f_b > f_a {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut f_a, &mut f_b);
+        }
+        let mut c = b + GOLDEN_RATIO * (b - a);
+        let mut f_c = phi(c);
+        let mut i = 0;
+        while f_c < f_b && i < 100 {
+            (a, f_a) = (b, f_b);
+            (b, f_b) = (c, f_c);
+            c = b + GOLDEN_RATIO * (b - a);
+            f_c = phi(c);
+            i += 1;
+        }
+        trace!(target: "powell", "Bracket: [{}, {}, {}], images [{}, {}, {}]", a, b, c, f_a, f_b, f_c);
+
+        // golden section search on [a, c], keeping the best point found
+        let (mut best, mut f_best) = if f_b < f_x { (b, f_b) } else { (0.0, f_x) };
+        let (mut lo, mut hi) = if a < c { (a, c) } else { (c, a) };
+        let r = GOLDEN_RATIO - 1.0;
+        let mut t1 = hi - r * (hi - lo);
+        let mut t2 = lo + r * (hi - lo);
+        let mut f1 = phi(t1);
+        let mut f2 = phi(t2);
+        for _ in 0..200 {
+            if hi - lo <= self.line_tol * (t1.abs() + t2.abs()) + Floating::EPSILON {
+                break;
+            }
+            if f1 < f2 {
+                hi = t2;
+                (t2, f2) = (t1, f1);
+                t1 = hi - r * (hi - lo);
+                f1 = phi(t1);
+            } else {
+                lo = t1;
+                (t1, f1) = (t2, f2);
+                t2 = lo + r * (hi - lo);
+                f2 = phi(t2);
+            }
+        }
+        for (t, f) in [(t1, f1), (t2, f2)] {
+            if f < f_best {
+                (best, f_best) = (t, f);
+            }
+        }
+        (best, f_best)
+    }
+}
+
+impl DerivativeFreeSolver for Powell {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn fk(&self) -> Floating {
+        self.f
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn has_converged(&self) -> Option<TerminationReason> {
+        let f_previous = self.f_previous?;
+        (2.0 * (f_previous - self.f)
+            <= self.tol * (f_previous.abs() + self.f.abs()) + Floating::MIN_POSITIVE)
+            .then_some(TerminationReason::RelativeFunctionChange)
+    }
+
+    fn setup(&mut self, oracle: &mut impl ValueObjective) {
+        let n = self.x.len();
+        self.directions = (0..n)
+            .map(|i| DVector::from_fn(n, |j, _| if i == j { 1.0 } else { 0.0 }))
+            .collect();
+        self.f = oracle.value(&self.x);
+        self.f_previous = None;
+    }
+
+    fn iterate(&mut self, oracle: &mut impl ValueObjective) -> Result<(), SolverError> {
+        let x_start = self.x.clone();
+        let f_start = self.f;
+        let (mut largest_decrease, mut i_largest) = (0.0, 0);
+
+        for i in 0..self.directions.len() {
+            let f_before = self.f;
+            let (t, f) = self.line_minimization(oracle, &self.x, &self.directions[i], self.f);
+            self.x.axpy(t, &self.directions[i], 1.0);
+            self.f = f;
+            if f_before - f > largest_decrease {
+                largest_decrease = f_before - f;
+                i_largest = i;
+            }
+        }
+        self.f_previous = Some(f_start);
+
+        // the displacement of the iteration replaces the direction of largest decrease, unless the extrapolated point shows that it's not worth it
+        let displacement = &self.x - &x_start;
+        let f_extrapolated = oracle.value(&(&self.x + &displacement));
+        if f_extrapolated < f_start {
+            let t = 2.0
+                * (f_start - 2.0 * self.f + f_extrapolated)
+                * (f_start - self.f - largest_decrease).powi(2)
+                - largest_decrease * (f_start - f_extrapolated).powi(2);
+            if t < 0.0 {
+                let (t, f) = self.line_minimization(oracle, &self.x, &displacement, self.f);
+                self.x.axpy(t, &displacement, 1.0);
+                self.f = f;
+                debug!(target: "powell", "Replacing direction {} with the displacement of the iteration", i_largest);
+                let last = self.directions.len() - 1;
+                self.directions[i_largest] = self.directions[last].clone();
+                self.directions[last] = displacement;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod powell_test {
+    use super::*;
+
+    #[test]
+    pub fn powell_rosenbrock() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let rosenbrock = |x: &DVector<Floating>| -> Floating {
+            100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2)
+        };
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = Powell::new(1e-12, x_0);
+        let report = solver.minimize(rosenbrock, 1000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+        assert_eq!(report.evaluations().g(), &0);
+    }
+
+    #[test]
+    pub fn powell_quadratic() {
+        // non separable quadratic: the conjugate directions are built in a few iterations
+        let n = 5;
+        let a = DMatrix::from_fn(n, n, |i, j| {
+            if i == j {
+                4.0 + i as Floating
+            } else {
+                1.0 / (1.0 + (i as Floating - j as Floating).abs())
+            }
+        });
+        let b = DVector::from_fn(n, |i, _| i as Floating - 2.0);
+        let f = |x: &DVector<Floating>| -> Floating { 0.5 * x.dot(&(&a * x)) - b.dot(x) };
+        let solution = a.clone().lu().solve(&b).unwrap();
+
+        let mut solver = Powell::new(1e-14, DVector::zeros(n));
+        let report = solver.minimize(f, 1000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x() - solution).amax() < 1e-6);
+        assert!(report.iterations() < &(4 * n));
+    }
+}
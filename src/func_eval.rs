@@ -16,6 +16,9 @@ impl<T, H> FuncEval<T, H> {
             hessian: None,
         }
     }
+    pub fn into_parts(self) -> (Floating, T, Option<H>) {
+        (self.f, self.g, self.hessian)
+    }
 }
 
 pub type FuncEvalUnivariate = FuncEval<Floating, Floating>;
@@ -3,8 +3,6 @@ use lbfgsb_sys::lbfgsb as ffi;
 #[cfg(feature = "lbfgsb")]
 use lbfgsb_sys::string as ffi_string;
 use nalgebra::{DMatrix, DVector};
-#[cfg(feature = "lbfgsb")]
-use std::ffi::CStr;
 
 use tracing::{debug, error, info, trace, warn};
 use tracing_appender::non_blocking::WorkerGuard;
@@ -34,9 +32,21 @@ pub use tracer::*;
 pub mod ls_solver;
 pub use ls_solver::*;
 
+pub mod ls_free_solver;
+pub use ls_free_solver::*;
+
+pub mod report;
+pub use report::*;
+
+pub mod stopping_criterion;
+pub use stopping_criterion::*;
+
 pub mod func_eval;
 pub use func_eval::*;
 
+pub mod objective;
+pub use objective::*;
+
 pub mod line_search;
 pub use line_search::*;
 
@@ -45,10 +55,16 @@ pub use number::*;
 
 pub mod quasi_newton {
     use super::*;
+    pub mod safeguards;
+    pub use safeguards::*;
+    pub mod updates;
+    pub use updates::*;
     pub mod bfgs;
     pub use bfgs::*;
     pub mod bfgs_b;
     pub use bfgs_b::*;
+    pub mod broyden_class;
+    pub use broyden_class::*;
     pub mod broyden;
     pub use broyden::*;
     pub mod broyden_b;
@@ -57,6 +73,14 @@ pub mod quasi_newton {
     pub use dfp::*;
     pub mod dfp_b;
     pub use dfp_b::*;
+    pub mod lbfgs;
+    pub use lbfgs::*;
+    pub mod lbfgs_b;
+    pub use lbfgs_b::*;
+    pub mod owlqn;
+    pub use owlqn::*;
+    pub mod sr1;
+    pub use sr1::*;
     pub mod sr1_b;
     pub use sr1_b::*;
 
@@ -78,18 +102,43 @@ pub mod steepest_descent {
     pub mod pnorm_descent;
     pub use pnorm_descent::*;
 
+    pub mod osgm;
+    pub use osgm::*;
+
     pub mod spg;
     pub use spg::*;
 
+    pub mod barzilai_borwein;
+    pub use barzilai_borwein::*;
+
     pub mod projected_gradient_descent;
     pub use projected_gradient_descent::*;
 }
 
 pub use steepest_descent::*;
 
+pub mod conjugate_gradient {
+    use super::*;
+    pub mod nonlinear_cg;
+    pub use nonlinear_cg::*;
+}
+pub use conjugate_gradient::*;
+
+pub mod accelerated;
+pub use accelerated::*;
+
+pub mod proximal;
+pub use proximal::*;
+
 pub mod newton;
 pub use newton::*;
 
+pub mod trust_region;
+pub use trust_region::*;
+
+pub mod derivative_free;
+pub use derivative_free::*;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub mod plotter_3d;
 #[cfg(not(target_arch = "wasm32"))]
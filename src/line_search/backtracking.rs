@@ -22,28 +22,29 @@ impl LineSearch for BackTracking {
         x_k: &DVector<Floating>,
         eval_x_k: &FuncEvalMultivariate,
         direction_k: &DVector<Floating>,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         max_iter: usize,
-    ) -> Floating {
+    ) -> LineSearchStep {
         let mut t = 1.0;
         let mut i = 0;
 
         while max_iter > i {
             let x_kp1 = x_k + t * direction_k;
 
-            let eval_kp1 = oracle(&x_kp1);
+            // the armijo condition requires only the image at the trial point
+            let f_kp1 = oracle.value(&x_kp1);
 
             // we check if we are out of domain
-            if eval_kp1.f().is_nan() || eval_kp1.f().is_infinite() {
+            if f_kp1.is_nan() || f_kp1.is_infinite() {
                 trace!(target: "backtracking line search", "Step size too big: next iterate is out of domain. Decreasing step by beta ({:?})", x_kp1);
                 t *= self.beta;
                 continue;
             }
 
             // armijo condition
-            if self.sufficient_decrease(eval_x_k.f(), eval_kp1.f(), eval_x_k.g(), &t, direction_k) {
+            if self.sufficient_decrease(eval_x_k.f(), &f_kp1, eval_x_k.g(), &t, direction_k) {
                 trace!(target: "backtracking line search", "Sufficient decrease condition met. Exiting with step size: {:?}", t);
-                return t;
+                return LineSearchStep::new(t, None);
             }
 
             //if we are here, it means that the we still didn't meet the exit condition, so we decrease the step size accordingly
@@ -51,7 +52,7 @@ impl LineSearch for BackTracking {
             i += 1;
         }
         trace!(target: "backtracking line search", "Max iter reached. Early stopping.");
-        t
+        LineSearchStep::new(t, None)
         // worst case scenario: t=0 (or t>0 but t<1 because of early stopping).
         // if t=0 we are not updating the iterate
         // if early stop triggered, we benefit from some image reduction but it is not enough to be considered satisfactory
@@ -94,14 +95,15 @@ mod backtracking_tests {
                 break;
             }
             let direction = -eval.g();
-            let t = <BackTracking as LineSearch>::compute_step_len(
+            let t = *<BackTracking as LineSearch>::compute_step_len(
                 &mut backtracking,
                 &iterate,
                 &eval,
                 &direction,
                 &mut f_and_g,
                 max_iter,
-            );
+            )
+            .t();
             //we perform the update
             iterate += t * direction;
             k += 1;
@@ -55,9 +55,9 @@ impl LineSearch for BackTrackingB {
         x_k: &DVector<Floating>,
         eval_x_k: &FuncEvalMultivariate,
         direction_k: &DVector<Floating>,
-        oracle: & mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         max_iter: usize,
-    ) -> Floating {
+    ) -> LineSearchStep {
         let mut t = 1.0;
         let mut i = 0;
 
@@ -65,16 +65,16 @@ impl LineSearch for BackTrackingB {
             let x_kp1 = x_k + t * direction_k;
             // we project the next iterate onto the feasible set
             let x_kp1 = x_kp1.box_projection(&self.lower_bound, &self.upper_bound);
-            let eval_kp1 = oracle(&x_kp1);
+            let f_kp1 = oracle.value(&x_kp1);
             // we check if we are out of domain
-            if eval_kp1.f().is_nan() || eval_kp1.f().is_infinite() {
+            if f_kp1.is_nan() || f_kp1.is_infinite() {
                 trace!(target: "backtracking_b line search", "Step size too big: next iterate is out of domain. Decreasing step by beta ({:?})", x_kp1);
                 t *= self.beta;
                 continue;
             }
-            if self.sufficient_decrease_with_bounds(x_k, &x_kp1, eval_x_k.f(), eval_kp1.f(), &t) {
+            if self.sufficient_decrease_with_bounds(x_k, &x_kp1, eval_x_k.f(), &f_kp1, &t) {
                 trace!(target: "backtracking_b line search", "Modified Armijo rule met. Exiting with step size: {:?} at iteration {:?}", t, i);
-                return t;
+                return LineSearchStep::new(t, None);
             }
 
             //if we are here, it means that the we still didn't meet the exit condition, so we decrease the step size accordingly
@@ -82,7 +82,7 @@ impl LineSearch for BackTrackingB {
             i += 1;
         }
         trace!(target: "backtracking_b line search", "Max iter reached. Early stopping.");
-        t
+        LineSearchStep::new(t, None)
         // worst case scenario: t=0 (or t>0 but t<1 because of early stopping).
         // if t=0 we are not updating the iterate
         // if early stop triggered, we benefit from some image reduction but it is not enough to be considered satisfactory
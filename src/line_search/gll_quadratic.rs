@@ -55,9 +55,9 @@ impl LineSearch for GLLQuadratic {
         x_k: &DVector<Floating>,         // current iterate
         eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
         direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
-        oracle: & mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate, // oracle
+        oracle: &mut impl Objective,     // oracle
         max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
-    ) -> Floating {
+    ) -> LineSearchStep {
         // we append the function eval to the previous function evals
         self.append_new_f(*eval_x_k.f());
         let mut t = 1.0;
@@ -67,12 +67,12 @@ impl LineSearch for GLLQuadratic {
         while max_iter > i {
             let x_kp1 = x_k + t * direction_k;
 
-            let eval_kp1 = oracle(&x_kp1);
+            let f_kp1 = oracle.value(&x_kp1);
 
             // armijo condition
-            if self.sufficient_decrease(&f_max, eval_kp1.f(), eval_x_k.g(), &t, direction_k) {
+            if self.sufficient_decrease(&f_max, &f_kp1, eval_x_k.g(), &t, direction_k) {
                 trace!(target: "gll quadratic line search", "Sufficient decrease condition met. Exiting with step size: {:?}", t);
-                return t;
+                return LineSearchStep::new(t, None);
             }
 
             if t <= 0.1 {
@@ -81,7 +81,7 @@ impl LineSearch for GLLQuadratic {
             } else {
                 // here step size is sufficiently large to perform a quadratic interpolation
                 let t_tmp = -0.5 * t * t * eval_x_k.g().dot(direction_k)
-                    / (eval_kp1.f() - eval_x_k.f() - t * eval_x_k.g().dot(direction_k));
+                    / (f_kp1 - eval_x_k.f() - t * eval_x_k.g().dot(direction_k));
                 if t_tmp > self.sigma1 && t_tmp < self.sigma2 * t {
                     trace!(target: "gll quadratic line search", "Safeguarded step size: {}", t_tmp);
                     t = t_tmp;
@@ -95,6 +95,6 @@ impl LineSearch for GLLQuadratic {
             i += 1;
         }
         trace!(target: "gll quadratic line search", "Max iter reached. Early stopping.");
-        t
+        LineSearchStep::new(t, None)
     }
 }
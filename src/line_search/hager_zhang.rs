@@ -0,0 +1,411 @@
+use super::*;
+use std::ops::ControlFlow;
+
+// Line search from [Hager, W. W., & Zhang, H. (2005). A new conjugate gradient method with guaranteed descent and an efficient line search.] and [Hager, W. W., & Zhang, H. (2006). Algorithm 851: CG_DESCENT].
+// A step is accepted if it satisfies either the Wolfe conditions (T1) or the approximate Wolfe conditions (T2):
+//      (2 * delta - 1) * phi'(0) >= phi'(t) >= sigma * phi'(0)   and   phi(t) <= phi(0) + epsilon_k
+// where epsilon_k = epsilon * |f(x_k)|. The approximate conditions don't involve the difference phi(t) - phi(0), so they remain meaningful near the solution, where the armijo test is dominated by the rounding errors of the function values.
+// The interval containing acceptable steps is bracketed by expansion and then shrunk by double secant steps (secant^2), with bisection when the interval doesn't shrink enough.
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct HagerZhang {
+    delta: Floating,   // armijo sensitivity (c1), in (0, 0.5)
+    sigma: Floating,   // curvature sensitivity (c2), in [delta, 1)
+    epsilon: Floating, // relative tolerance of the approximate wolfe conditions on the function value
+    theta: Floating,   // position of the bisection point in the update step
+    gamma: Floating,   // minimum shrinkage of the interval required after a secant^2 step
+    rho: Floating,     // expansion factor of the trial step in the bracketing phase
+    t_init: Floating,  // initial trial step
+}
+
+impl Default for HagerZhang {
+    fn default() -> Self {
+        HagerZhang {
+            delta: 0.1,
+            sigma: 0.9,
+            epsilon: 1e-6,
+            theta: 0.5,
+            gamma: 0.66,
+            rho: 5.0,
+            t_init: 1.0,
+        }
+    }
+}
+
+impl HagerZhang {
+    pub fn with_delta(mut self, delta: Floating) -> Self {
+        assert!(delta > 0.0, "delta must be positive");
+        assert!(delta < 0.5, "delta must be less than 0.5");
+        assert!(delta <= self.sigma, "delta must be less than sigma");
+        self.delta = delta;
+        self
+    }
+    pub fn with_sigma(mut self, sigma: Floating) -> Self {
+        assert!(sigma < 1.0, "sigma must be less than 1");
+        assert!(sigma >= self.delta, "sigma must be greater than delta");
+        self.sigma = sigma;
+        self
+    }
+    pub fn with_epsilon(mut self, epsilon: Floating) -> Self {
+        assert!(epsilon >= 0.0, "epsilon must be non negative");
+        self.epsilon = epsilon;
+        self
+    }
+    pub fn with_theta(mut self, theta: Floating) -> Self {
+        assert!(theta > 0.0 && theta < 1.0, "theta must be in (0, 1)");
+        self.theta = theta;
+        self
+    }
+    pub fn with_gamma(mut self, gamma: Floating) -> Self {
+        assert!(gamma > 0.0 && gamma < 1.0, "gamma must be in (0, 1)");
+        self.gamma = gamma;
+        self
+    }
+    pub fn with_rho(mut self, rho: Floating) -> Self {
+        assert!(rho > 1.0, "rho must be greater than 1");
+        self.rho = rho;
+        self
+    }
+    pub fn with_t_init(mut self, t_init: Floating) -> Self {
+        assert!(t_init > 0.0, "initial step must be positive");
+        self.t_init = t_init;
+        self
+    }
+
+    // condition T2 (approximate wolfe conditions). f_bound is phi(0) + epsilon_k
+    pub fn approximate_wolfe_conditions(
+        &self,
+        f_bound: &Floating,
+        f_kp1: &Floating,
+        grad_k: &DVector<Floating>,
+        grad_kp1: &DVector<Floating>,
+        direction_k: &DVector<Floating>,
+    ) -> bool {
+        let dphi_0 = grad_k.dot(direction_k);
+        let dphi_t = grad_kp1.dot(direction_k);
+        (2.0 * self.delta - 1.0) * dphi_0 >= dphi_t
+            && self.curvature_condition(grad_k, grad_kp1, direction_k)
+            && f_kp1 <= f_bound
+    }
+}
+
+impl SufficientDecreaseCondition for HagerZhang {
+    fn c1(&self) -> Floating {
+        self.delta
+    }
+}
+
+impl CurvatureCondition for HagerZhang {
+    fn c2(&self) -> Floating {
+        self.sigma
+    }
+}
+
+// image and directional derivative at a trial step (both infinite if the trial point is out of domain)
+#[derive(Debug, Clone, Copy)]
+struct Trial {
+    t: Floating,
+    f: Floating,
+    df: Floating,
+}
+
+// State of a single line search. Every method returns ControlFlow::Break with the outcome of the line search as soon as an acceptable step is found or the budget of evaluations is exhausted
+struct HagerZhangSearch<'a, O: Objective> {
+    ls: &'a HagerZhang,
+    x_k: &'a DVector<Floating>,
+    eval_x_k: &'a FuncEvalMultivariate,
+    direction_k: &'a DVector<Floating>,
+    oracle: &'a mut O,
+    f_bound: Floating, // phi(0) + epsilon_k
+    max_iter: usize,
+    i: usize,
+    // lowest trial satisfying phi(t) <= phi(0) + epsilon_k and phi'(t) < 0, returned if the budget is exhausted
+    best: Trial,
+    eval_best: Option<FuncEvalMultivariate>,
+}
+
+impl<O: Objective> HagerZhangSearch<'_, O> {
+    fn give_up(&mut self) -> LineSearchStep {
+        trace!(target: "hager zhang line search", "Max iter reached. Early stopping with step {}", self.best.t);
+        LineSearchStep::new(self.best.t, self.eval_best.take())
+    }
+
+    fn evaluate(&mut self, t: Floating) -> ControlFlow<LineSearchStep, Trial> {
+        if self.i >= self.max_iter {
+            return ControlFlow::Break(self.give_up());
+        }
+        self.i += 1;
+        let eval = self
+            .oracle
+            .value_and_gradient(&(self.x_k + t * self.direction_k));
+        if !eval.f().is_finite() || eval.g().iter().any(|g| !g.is_finite()) {
+            trace!(target: "hager zhang line search", "Trial step {} is out of domain", t);
+            return ControlFlow::Continue(Trial {
+                t,
+                f: Floating::INFINITY,
+                df: Floating::INFINITY,
+            });
+        }
+        let trial = Trial {
+            t,
+            f: *eval.f(),
+            df: eval.g().dot(self.direction_k),
+        };
+
+        if self.ls.wolfe_conditions_with_directional_derivative(
+            self.eval_x_k.f(),
+            eval.f(),
+            self.eval_x_k.g(),
+            eval.g(),
+            &t,
+            self.direction_k,
+        ) || self.ls.approximate_wolfe_conditions(
+            &self.f_bound,
+            eval.f(),
+            self.eval_x_k.g(),
+            eval.g(),
+            self.direction_k,
+        ) {
+            trace!(target: "hager zhang line search", "Wolfe or approximate Wolfe conditions satisfied at iteration {} with step {}", self.i, t);
+            return ControlFlow::Break(LineSearchStep::new(t, Some(eval)));
+        }
+
+        if trial.f <= self.f_bound && trial.df < 0.0 && trial.f < self.best.f {
+            self.best = trial;
+            self.eval_best = Some(eval);
+        }
+        ControlFlow::Continue(trial)
+    }
+
+    // Interval update U0-U3 given the new trial c. On exit a satisfies phi(a) <= phi(0) + epsilon_k, phi'(a) < 0 and b satisfies phi'(b) >= 0
+    fn update(
+        &mut self,
+        a: Trial,
+        b: Trial,
+        c: Trial,
+    ) -> ControlFlow<LineSearchStep, (Trial, Trial)> {
+        if c.t <= a.t || c.t >= b.t {
+            return ControlFlow::Continue((a, b));
+        }
+        if c.df >= 0.0 {
+            return ControlFlow::Continue((a, c));
+        }
+        if c.f <= self.f_bound {
+            return ControlFlow::Continue((c, b));
+        }
+        self.bisect(a, c)
+    }
+
+    // step U3: phi'(b) < 0 but phi(b) > phi(0) + epsilon_k, so there is a local maximum in between that we skip by bisection
+    fn bisect(
+        &mut self,
+        mut a: Trial,
+        mut b: Trial,
+    ) -> ControlFlow<LineSearchStep, (Trial, Trial)> {
+        loop {
+            if b.t - a.t <= Floating::EPSILON * b.t {
+                return ControlFlow::Break(self.give_up());
+            }
+            let d = self.evaluate((1.0 - self.ls.theta) * a.t + self.ls.theta * b.t)?;
+            if d.df >= 0.0 {
+                return ControlFlow::Continue((a, d));
+            }
+            if d.f <= self.f_bound {
+                a = d;
+            } else {
+                b = d;
+            }
+        }
+    }
+
+    fn secant(a: &Trial, b: &Trial) -> Floating {
+        (a.t * b.df - b.t * a.df) / (b.df - a.df)
+    }
+
+    // steps S1-S4
+    fn secant2(&mut self, a: Trial, b: Trial) -> ControlFlow<LineSearchStep, (Trial, Trial)> {
+        let c_t = Self::secant(&a, &b);
+        if !c_t.is_finite() || c_t <= a.t || c_t >= b.t {
+            return ControlFlow::Continue((a, b));
+        }
+        let c = self.evaluate(c_t)?;
+        let (a_new, b_new) = self.update(a, b, c)?;
+        let c_bar = if c.t == b_new.t {
+            Self::secant(&b, &b_new)
+        } else if c.t == a_new.t {
+            Self::secant(&a, &a_new)
+        } else {
+            return ControlFlow::Continue((a_new, b_new));
+        };
+        if !c_bar.is_finite() || c_bar <= a_new.t || c_bar >= b_new.t {
+            return ControlFlow::Continue((a_new, b_new));
+        }
+        let c_bar = self.evaluate(c_bar)?;
+        self.update(a_new, b_new, c_bar)
+    }
+
+    // steps B0-B3: the trial step is expanded until phi' becomes non negative or phi exceeds phi(0) + epsilon_k
+    fn bracket(
+        &mut self,
+        phi_0: Trial,
+        mut c: Trial,
+    ) -> ControlFlow<LineSearchStep, (Trial, Trial)> {
+        let mut last_admissible = phi_0;
+        loop {
+            if c.df >= 0.0 {
+                return ControlFlow::Continue((last_admissible, c));
+            }
+            if c.f > self.f_bound {
+                return self.bisect(last_admissible, c);
+            }
+            last_admissible = c;
+            c = self.evaluate(self.ls.rho * c.t)?;
+        }
+    }
+
+    fn run(&mut self, phi_0: Trial) -> ControlFlow<LineSearchStep, ()> {
+        let c = self.evaluate(self.ls.t_init)?;
+        let (mut a, mut b) = self.bracket(phi_0, c)?;
+        loop {
+            let (a_new, b_new) = self.secant2(a, b)?;
+            let (a_new, b_new) = if b_new.t - a_new.t > self.ls.gamma * (b.t - a.t) {
+                let c = self.evaluate(0.5 * (a_new.t + b_new.t))?;
+                self.update(a_new, b_new, c)?
+            } else {
+                (a_new, b_new)
+            };
+            a = a_new;
+            b = b_new;
+            trace!(target: "hager zhang line search", "Interval: [{}, {}]", a.t, b.t);
+            if b.t - a.t <= Floating::EPSILON * b.t {
+                return ControlFlow::Continue(());
+            }
+        }
+    }
+}
+
+impl LineSearch for HagerZhang {
+    fn compute_step_len(
+        &mut self,
+        x_k: &DVector<Floating>,         // current iterate
+        eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
+        direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
+        oracle: &mut impl Objective,     // oracle
+        max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
+    ) -> LineSearchStep {
+        let phi_0 = Trial {
+            t: 0.0,
+            f: *eval_x_k.f(),
+            df: eval_x_k.g().dot(direction_k),
+        };
+        if phi_0.df >= 0.0 {
+            warn!(target: "hager zhang line search", "Not a descent direction (directional derivative: {}). Exiting with null step", phi_0.df);
+            return LineSearchStep::new(0.0, None);
+        }
+
+        let mut search = HagerZhangSearch {
+            ls: self,
+            x_k,
+            eval_x_k,
+            direction_k,
+            oracle,
+            f_bound: phi_0.f + self.epsilon * phi_0.f.abs(),
+            max_iter,
+            i: 0,
+            best: phi_0,
+            eval_best: None,
+        };
+        match search.run(phi_0) {
+            ControlFlow::Break(step) => step,
+            ControlFlow::Continue(()) => search.give_up(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod hager_zhang_test {
+    use super::*;
+
+    #[test]
+    pub fn test_hager_zhang() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        let gamma = 90.0;
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0].powi(2) + gamma * x[1].powi(2));
+            let g = DVector::from(vec![x[0], gamma * x[1]]);
+            (f, g).into()
+        };
+        //here we define a rough gradient descent method that uses hager zhang line search
+        let mut k = 1;
+        let mut iterate = DVector::from(vec![180.0, 152.0]);
+        let mut ls = HagerZhang::default();
+        let gradient_tol = 1e-12;
+
+        while 10000 > k {
+            let eval = f_and_g(&iterate);
+            if eval.g().dot(eval.g()) < gradient_tol {
+                break;
+            }
+            let direction = -eval.g();
+            let t = *<HagerZhang as LineSearch>::compute_step_len(
+                &mut ls,
+                &iterate,
+                &eval,
+                &direction,
+                &mut f_and_g,
+                100,
+            )
+            .t();
+            iterate += t * direction;
+            k += 1;
+        }
+        println!("Iterate: {:?}", iterate);
+        assert!((iterate[0] - 0.0).abs() < 1e-6);
+        assert!((iterate[1] - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn hager_zhang_high_accuracy() {
+        // the large offset makes f_kp1 - f_k meaningless once the iterates are close to the solution (|f_kp1 - f_k| is below the rounding error of f)
+        let offset = 1e8;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = offset + 0.5 * (x[0].powi(2) + 10. * x[1].powi(2));
+            let g = DVector::from(vec![x[0], 10. * x[1]]);
+            (f, g).into()
+        };
+        let x_0 = DVector::from(vec![1.0, 1.0]);
+        let mut ls = HagerZhang::default();
+        let mut solver = LBFGS::new(1e-12, x_0);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        // the gradient is driven well below the accuracy that the function values alone could certify (sqrt(eps * offset) ~ 1e-4)
+        assert!(report.gradient_norm() < &1e-10);
+    }
+
+    #[test]
+    pub fn hager_zhang_out_of_domain() {
+        // f(x) = x - ln(x) is defined only for x > 0 and has its minimum at x = 1
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = x[0] - x[0].ln();
+            let g = DVector::from(vec![1. - 1. / x[0]]);
+            (f, g).into()
+        };
+        let x_k = DVector::from(vec![5.0]);
+        let eval_x_k = f_and_g(&x_k);
+        let direction = DVector::from(vec![-8.0]);
+        let mut ls = HagerZhang::default();
+
+        let (t, eval_t) = ls
+            .compute_step_len(&x_k, &eval_x_k, &direction, &mut f_and_g, 100)
+            .dissolve();
+
+        let x_kp1 = &x_k + t * &direction;
+        assert!(x_kp1[0] > 0.0);
+        assert!(eval_t.unwrap().f() < eval_x_k.f());
+    }
+}
@@ -7,19 +7,40 @@ pub mod morethuente_b;
 pub use morethuente_b::*;
 pub mod backtracking_b;
 pub use backtracking_b::*;
+pub mod orthant_wise_backtracking;
+pub use orthant_wise_backtracking::*;
 pub mod gll_quadratic;
 pub use gll_quadratic::*;
 pub mod nosearch;
 pub use nosearch::*;
+pub mod strong_wolfe;
+pub use strong_wolfe::*;
+pub mod hager_zhang;
+pub use hager_zhang::*;
+pub mod zhang_hager;
+pub use zhang_hager::*;
+// Outcome of the line search: the accepted step length and, when the search computed it, the evaluation of the oracle at x_k + t * direction_k (so that solvers don't query the oracle again at the same point)
+#[derive(derive_getters::Getters, derive_getters::Dissolve, Debug)]
+pub struct LineSearchStep {
+    t: Floating,
+    eval: Option<FuncEvalMultivariate>,
+}
+
+impl LineSearchStep {
+    pub fn new(t: Floating, eval: Option<FuncEvalMultivariate>) -> Self {
+        LineSearchStep { t, eval }
+    }
+}
+
 pub trait LineSearch {
     fn compute_step_len(
         &mut self,
         x_k: &DVector<Floating>,         // current iterate
         eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
         direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate, // oracle
+        oracle: &mut impl Objective,     // oracle
         max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
-    ) -> Floating; //returns the scalar step size
+    ) -> LineSearchStep; //returns the scalar step size, together with the evaluation at the accepted point (if available)
 }
 
 pub trait SufficientDecreaseCondition {
@@ -167,9 +167,9 @@ impl LineSearch for MoreThuente {
         x_k: &DVector<Floating>,         // current iterate
         eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
         direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate, // oracle
+        oracle: &mut impl Objective,     // oracle
         max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
-    ) -> Floating {
+    ) -> LineSearchStep {
         let mut use_modified_updating = false;
         let mut interval_converged = false;
 
@@ -179,7 +179,7 @@ impl LineSearch for MoreThuente {
         let eval_0 = eval_x_k;
 
         for i in 0..max_iter {
-            let eval_t = oracle(&(x_k + t * direction_k));
+            let eval_t = oracle.value_and_gradient(&(x_k + t * direction_k));
             // Check for convergence
             if self.strong_wolfe_conditions_with_directional_derivative(
                 eval_0.f(),
@@ -190,18 +190,18 @@ impl LineSearch for MoreThuente {
                 direction_k,
             ) {
                 trace!("Strong Wolfe conditions satisfied at iteration {}", i);
-                return t;
+                return LineSearchStep::new(t, Some(eval_t));
             } else if interval_converged {
                 trace!("Interval converged at iteration {}", i);
-                return t;
+                return LineSearchStep::new(t, Some(eval_t));
             // } else if t == self.t_min {
             } else if t == tl {
                 trace!("t is at the minimum value at iteration {}", i);
-                return t;
+                return LineSearchStep::new(t, Some(eval_t));
             // } else if t == self.t_max {
             } else if t == tu {
                 trace!("t is at the maximum value at iteration {}", i);
-                return t;
+                return LineSearchStep::new(t, Some(eval_t));
             }
 
             let phi_t = Self::phi(&eval_t, direction_k);
@@ -214,7 +214,7 @@ impl LineSearch for MoreThuente {
                 use_modified_updating = true;
             }
 
-            let eval_tl = oracle(&(x_k + tl * direction_k));
+            let eval_tl = oracle.value_and_gradient(&(x_k + tl * direction_k));
             let phi_tl = Self::phi(&eval_tl, direction_k);
 
             // using auxiliary or modified evaluation according to the flag
@@ -273,7 +273,7 @@ impl LineSearch for MoreThuente {
             // case 4 (here f_t <= &f_tl, g_t * g_tl >= 0., g_t.abs() > g_tl.abs())
             else {
                 let (f_tu, g_tu) = {
-                    let eval_tu = oracle(&(x_k + tu * direction_k));
+                    let eval_tu = oracle.value_and_gradient(&(x_k + tu * direction_k));
                     let phi_tu = Self::phi(&eval_tu, direction_k);
                     if use_modified_updating {
                         (*phi_tu.f(), *phi_tu.g())
@@ -293,7 +293,8 @@ impl LineSearch for MoreThuente {
             interval_converged = Self::update_interval(&f_tl, f_t, g_t, &mut tl, t, &mut tu)
         }
         trace!("Line search did not converge in {} iterations", max_iter);
-        t
+        // the last trial step has not been evaluated
+        LineSearchStep::new(t, None)
     }
 }
 
@@ -333,14 +334,15 @@ mod morethuente_test {
                 break;
             }
             let direction = -eval.g();
-            let t = <MoreThuente as LineSearch>::compute_step_len(
+            let t = *<MoreThuente as LineSearch>::compute_step_len(
                 &mut ls,
                 &iterate,
                 &eval,
                 &direction,
                 &mut f_and_g,
                 max_iter,
-            );
+            )
+            .t();
             //we perform the update
             iterate += t * direction;
             k += 1;
@@ -7,9 +7,9 @@ impl LineSearch for NoSearch {
         _: &DVector<Floating>,    // current iterate
         _: &FuncEvalMultivariate, // function evaluation at x_k
         _: &DVector<Floating>,    // direction of the ray along which we are going to search
-        _: & mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate, // oracle
+        _: &mut impl Objective,   // oracle
         _: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
-    ) -> Floating {
-        1.0
+    ) -> LineSearchStep {
+        LineSearchStep::new(1.0, None)
     }
 }
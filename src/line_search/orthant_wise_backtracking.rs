@@ -0,0 +1,58 @@
+// Backtracking line search for OWLQN (section 3.2 in [Andrew, G., & Gao, J. (2007). Scalable training of L1-regularized log-linear models. ICML]): the trial points are projected onto the orthant of the current iterate, in the same way BackTrackingB projects them onto the box, and the sufficient decrease condition is checked on F(x) = f(x) + lambda ||x||_1 using the pseudo-gradient
+//      F(x_kp1) <= F(x_k) + c1 * pseudo_gradient_k^T (x_kp1 - x_k)
+use super::*;
+pub struct OrthantWiseBackTracking {
+    c1: Floating,     // recommended: [0.01, 0.3]
+    beta: Floating,   // recommended: [0.1, 0.8]
+    lambda: Floating, // weight of the L1 regularization (taken from the solver)
+}
+impl OrthantWiseBackTracking {
+    // the weight of the L1 term is the one of the solver, so that the line search checks the decrease of the same objective
+    pub fn new(c1: Floating, beta: Floating, solver: &OWLQN) -> Self {
+        OrthantWiseBackTracking {
+            c1,
+            beta,
+            lambda: *solver.lambda(),
+        }
+    }
+}
+
+impl LineSearch for OrthantWiseBackTracking {
+    fn compute_step_len(
+        &mut self,
+        x_k: &DVector<Floating>,
+        eval_x_k: &FuncEvalMultivariate,
+        direction_k: &DVector<Floating>,
+        oracle: &mut impl Objective,
+        max_iter: usize,
+    ) -> LineSearchStep {
+        let pseudo_gradient = OWLQN::pseudo_gradient(x_k, eval_x_k.g(), self.lambda);
+        let orthant = OWLQN::orthant(x_k, &pseudo_gradient);
+        let f_k = eval_x_k.f() + self.lambda * x_k.lp_norm(1);
+        let mut t = 1.0;
+        let mut i = 0;
+
+        while max_iter > i {
+            // we project the next iterate onto the orthant of x_k
+            let x_kp1 = OWLQN::orthant_projection(&(x_k + t * direction_k), &orthant);
+            let f_kp1 = oracle.value(&x_kp1);
+            // we check if we are out of domain
+            if f_kp1.is_nan() || f_kp1.is_infinite() {
+                trace!(target: "orthant_wise_backtracking line search", "Step size too big: next iterate is out of domain. Decreasing step by beta ({:?})", x_kp1);
+                t *= self.beta;
+                continue;
+            }
+            let f_kp1 = f_kp1 + self.lambda * x_kp1.lp_norm(1);
+            if f_kp1 - f_k <= self.c1 * pseudo_gradient.dot(&(&x_kp1 - x_k)) {
+                trace!(target: "orthant_wise_backtracking line search", "Sufficient decrease condition met. Exiting with step size: {:?} at iteration {:?}", t, i);
+                return LineSearchStep::new(t, None);
+            }
+
+            //if we are here, it means that the we still didn't meet the exit condition, so we decrease the step size accordingly
+            t *= self.beta;
+            i += 1;
+        }
+        trace!(target: "orthant_wise_backtracking line search", "Max iter reached. Early stopping.");
+        LineSearchStep::new(t, None)
+    }
+}
@@ -0,0 +1,361 @@
+use super::*;
+
+// Line search for the strong Wolfe conditions, Algorithms 3.5 (bracketing) and 3.6 (zoom) in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]
+// The trial step is extrapolated until an interval containing strong Wolfe points is bracketed, then the interval is shrunk by cubic interpolation (with bisection as safeguard).
+// Trial points out of the domain of the function (NaN or infinite image) are handled by moving back towards the last admissible step.
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct StrongWolfe {
+    c1: Floating,            // armijo sensitivity
+    c2: Floating,            // curvature sensitivity
+    t_init: Floating,        // initial trial step
+    t_max: Floating,         // maximum step length
+    extrapolation: Floating, // expansion factor of the trial step in the bracketing phase
+}
+
+impl Default for StrongWolfe {
+    fn default() -> Self {
+        StrongWolfe {
+            c1: 1e-4,
+            c2: 0.9,
+            t_init: 1.0,
+            t_max: 1e10,
+            extrapolation: 2.0,
+        }
+    }
+}
+
+impl StrongWolfe {
+    pub fn with_c1(mut self, c1: Floating) -> Self {
+        assert!(c1 > 0.0, "c1 must be positive");
+        assert!(c1 < self.c2, "c1 must be less than c2");
+        self.c1 = c1;
+        self
+    }
+    pub fn with_c2(mut self, c2: Floating) -> Self {
+        assert!(c2 > 0.0, "c2 must be positive");
+        assert!(c2 < 1.0, "c2 must be less than 1");
+        assert!(c2 > self.c1, "c2 must be greater than c1");
+        self.c2 = c2;
+        self
+    }
+    pub fn with_t_init(mut self, t_init: Floating) -> Self {
+        assert!(t_init > 0.0, "initial step must be positive");
+        self.t_init = t_init;
+        self
+    }
+    pub fn with_t_max(mut self, t_max: Floating) -> Self {
+        assert!(t_max > 0.0, "max step must be positive");
+        self.t_max = t_max;
+        self
+    }
+    pub fn with_extrapolation(mut self, extrapolation: Floating) -> Self {
+        assert!(
+            extrapolation > 1.0,
+            "extrapolation factor must be greater than 1"
+        );
+        self.extrapolation = extrapolation;
+        self
+    }
+
+    fn out_of_domain(eval: &FuncEvalMultivariate) -> bool {
+        !eval.f().is_finite() || eval.g().iter().any(|g| !g.is_finite())
+    }
+
+    // Algorithm 3.6: t_lo is the step with the lowest image among the ones satisfying the sufficient decrease condition, and t_hi is chosen so that phi'(t_lo) * (t_hi - t_lo) < 0
+    #[allow(clippy::too_many_arguments)]
+    fn zoom(
+        &self,
+        x_k: &DVector<Floating>,
+        eval_x_k: &FuncEvalMultivariate,
+        direction_k: &DVector<Floating>,
+        oracle: &mut impl Objective,
+        max_iter: usize,
+        lo: (Floating, FuncEvalUnivariate, Option<FuncEvalMultivariate>),
+        hi: (Floating, FuncEvalUnivariate),
+    ) -> LineSearchStep {
+        let (mut t_lo, mut phi_lo, mut eval_lo) = lo;
+        let (mut t_hi, mut phi_hi) = hi;
+        let phi_0 = MoreThuente::phi(eval_x_k, direction_k);
+
+        for i in 0..max_iter {
+            // cubic interpolation, safeguarded by bisection when the minimizer is not well inside the interval
+            let (a, b) = (t_lo.min(t_hi), t_lo.max(t_hi));
+            let margin = 0.1 * (b - a);
+            let t_cubic = MoreThuente::cubic_minimizer(
+                &t_lo,
+                &t_hi,
+                phi_lo.f(),
+                phi_hi.f(),
+                phi_lo.g(),
+                phi_hi.g(),
+            );
+            let t = if t_cubic.is_finite() && t_cubic > a + margin && t_cubic < b - margin {
+                t_cubic
+            } else {
+                0.5 * (a + b)
+            };
+            trace!(target: "strong wolfe line search", "Zoom iteration {}: t_lo: {}, t_hi: {}, t: {}", i, t_lo, t_hi, t);
+
+            let eval_t = oracle.value_and_gradient(&(x_k + t * direction_k));
+            if Self::out_of_domain(&eval_t) {
+                trace!(target: "strong wolfe line search", "Trial point out of domain: shrinking the interval");
+                t_hi = t;
+                phi_hi = FuncEvalUnivariate::new(Floating::INFINITY, Floating::NAN);
+                continue;
+            }
+            let phi_t = MoreThuente::phi(&eval_t, direction_k);
+
+            if !self.sufficient_decrease(eval_x_k.f(), eval_t.f(), eval_x_k.g(), &t, direction_k)
+                || phi_t.f() >= phi_lo.f()
+            {
+                t_hi = t;
+                phi_hi = phi_t;
+            } else {
+                if self.strong_curvature_condition(eval_x_k.g(), eval_t.g(), direction_k) {
+                    trace!(target: "strong wolfe line search", "Strong Wolfe conditions satisfied in zoom at iteration {}", i);
+                    return LineSearchStep::new(t, Some(eval_t));
+                }
+                if phi_t.g() * (t_hi - t_lo) >= 0.0 {
+                    t_hi = t_lo;
+                    phi_hi = phi_lo;
+                }
+                t_lo = t;
+                phi_lo = phi_t;
+                eval_lo = Some(eval_t);
+            }
+
+            if (t_hi - t_lo).abs() <= Floating::EPSILON * t_lo.abs().max(1.0)
+                || phi_0.g().abs() * (t_hi - t_lo).abs() <= Floating::EPSILON * phi_0.f().abs()
+            {
+                trace!(target: "strong wolfe line search", "Interval too small: exiting with t_lo: {}", t_lo);
+                break;
+            }
+        }
+        // the best point found so far satisfies the sufficient decrease condition
+        LineSearchStep::new(t_lo, eval_lo)
+    }
+}
+
+impl SufficientDecreaseCondition for StrongWolfe {
+    fn c1(&self) -> Floating {
+        self.c1
+    }
+}
+
+impl CurvatureCondition for StrongWolfe {
+    fn c2(&self) -> Floating {
+        self.c2
+    }
+}
+
+impl LineSearch for StrongWolfe {
+    fn compute_step_len(
+        &mut self,
+        x_k: &DVector<Floating>,         // current iterate
+        eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
+        direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
+        oracle: &mut impl Objective,     // oracle
+        max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
+    ) -> LineSearchStep {
+        let phi_0 = MoreThuente::phi(eval_x_k, direction_k);
+        if phi_0.g() >= &0.0 {
+            warn!(target: "strong wolfe line search", "Not a descent direction (directional derivative: {}). Exiting with null step", phi_0.g());
+            return LineSearchStep::new(0.0, None);
+        }
+
+        // last admissible trial step (initially the current iterate)
+        let mut t_prev = 0.0;
+        let mut phi_prev = MoreThuente::phi(eval_x_k, direction_k);
+        let mut eval_prev = None;
+        let mut t = self.t_init.min(self.t_max);
+
+        for i in 0..max_iter {
+            let eval_t = oracle.value_and_gradient(&(x_k + t * direction_k));
+
+            // we check if we are out of domain
+            if Self::out_of_domain(&eval_t) {
+                trace!(target: "strong wolfe line search", "Step size too big: next iterate is out of domain. Moving back towards {}", t_prev);
+                t = 0.5 * (t_prev + t);
+                continue;
+            }
+
+            let phi_t = MoreThuente::phi(&eval_t, direction_k);
+
+            if !self.sufficient_decrease(eval_x_k.f(), eval_t.f(), eval_x_k.g(), &t, direction_k)
+                || (i > 0 && phi_t.f() >= phi_prev.f())
+            {
+                trace!(target: "strong wolfe line search", "Bracketed interval [{}, {}] at iteration {}", t_prev, t, i);
+                return self.zoom(
+                    x_k,
+                    eval_x_k,
+                    direction_k,
+                    oracle,
+                    max_iter - i,
+                    (t_prev, phi_prev, eval_prev),
+                    (t, phi_t),
+                );
+            }
+
+            if self.strong_wolfe_conditions_with_directional_derivative(
+                eval_x_k.f(),
+                eval_t.f(),
+                eval_x_k.g(),
+                eval_t.g(),
+                &t,
+                direction_k,
+            ) {
+                trace!(target: "strong wolfe line search", "Strong Wolfe conditions satisfied at iteration {}", i);
+                return LineSearchStep::new(t, Some(eval_t));
+            }
+
+            if phi_t.g() >= &0.0 {
+                trace!(target: "strong wolfe line search", "Bracketed interval [{}, {}] at iteration {}", t, t_prev, i);
+                return self.zoom(
+                    x_k,
+                    eval_x_k,
+                    direction_k,
+                    oracle,
+                    max_iter - i,
+                    (t, phi_t, Some(eval_t)),
+                    (t_prev, phi_prev),
+                );
+            }
+
+            if t >= self.t_max {
+                trace!(target: "strong wolfe line search", "Max step length reached at iteration {}", i);
+                return LineSearchStep::new(t, Some(eval_t));
+            }
+
+            // the step satisfies the sufficient decrease condition but the function is still decreasing: we extrapolate
+            t_prev = t;
+            phi_prev = phi_t;
+            eval_prev = Some(eval_t);
+            t = (self.extrapolation * t).min(self.t_max);
+        }
+        trace!(target: "strong wolfe line search", "Max iter reached. Early stopping.");
+        LineSearchStep::new(t_prev, eval_prev)
+    }
+}
+
+#[cfg(test)]
+mod strong_wolfe_test {
+    use super::*;
+
+    #[test]
+    pub fn test_strong_wolfe() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        let gamma = 90.0;
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0].powi(2) + gamma * x[1].powi(2));
+            let g = DVector::from(vec![x[0], gamma * x[1]]);
+            (f, g).into()
+        };
+        let max_iter = 10000;
+        //here we define a rough gradient descent method that uses strong wolfe line search
+        let mut k = 1;
+        let mut iterate = DVector::from(vec![180.0, 152.0]);
+        let mut ls = StrongWolfe::default();
+        let gradient_tol = 1e-12;
+
+        while max_iter > k {
+            let eval = f_and_g(&iterate);
+            if eval.g().dot(eval.g()) < gradient_tol {
+                break;
+            }
+            let direction = -eval.g();
+            let (t, eval_t) = <StrongWolfe as LineSearch>::compute_step_len(
+                &mut ls,
+                &iterate,
+                &eval,
+                &direction,
+                &mut f_and_g,
+                100,
+            )
+            .dissolve();
+            // every accepted step satisfies the strong wolfe conditions
+            let eval_t = eval_t.unwrap();
+            assert!(ls.strong_wolfe_conditions_with_directional_derivative(
+                eval.f(),
+                eval_t.f(),
+                eval.g(),
+                eval_t.g(),
+                &t,
+                &direction
+            ));
+            iterate += t * direction;
+            k += 1;
+        }
+        println!("Iterate: {:?}", iterate);
+        assert!((iterate[0] - 0.0).abs() < 1e-6);
+        assert!((iterate[1] - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn strong_wolfe_out_of_domain() {
+        // f(x) = x - ln(x) is defined only for x > 0 and has its minimum at x = 1
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = x[0] - x[0].ln();
+            let g = DVector::from(vec![1. - 1. / x[0]]);
+            (f, g).into()
+        };
+        let x_k = DVector::from(vec![5.0]);
+        let eval_x_k = f_and_g(&x_k);
+        // the unit step would bring the trial point to x = -3
+        let direction = DVector::from(vec![-8.0]);
+        let mut ls = StrongWolfe::default().with_c2(0.1);
+
+        let (t, eval_t) = ls
+            .compute_step_len(&x_k, &eval_x_k, &direction, &mut f_and_g, 100)
+            .dissolve();
+
+        let x_kp1 = &x_k + t * &direction;
+        assert!(x_kp1[0] > 0.0);
+        assert!(eval_t.unwrap().f() < eval_x_k.f());
+        assert!((x_kp1[0] - 1.0).abs() < 0.2);
+    }
+
+    #[test]
+    pub fn strong_wolfe_extrapolation() {
+        // quadratic along the search direction with minimizer at t = 10: the initial unit step must be expanded
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0] - 10.).powi(2);
+            let g = DVector::from(vec![x[0] - 10.]);
+            (f, g).into()
+        };
+        let x_k = DVector::from(vec![0.0]);
+        let eval_x_k = f_and_g(&x_k);
+        let direction = DVector::from(vec![1.0]);
+        let mut ls = StrongWolfe::default().with_c2(0.1).with_extrapolation(3.0);
+
+        let t = *ls
+            .compute_step_len(&x_k, &eval_x_k, &direction, &mut f_and_g, 100)
+            .t();
+
+        // |phi'(t)| = |t - 10| <= 0.1 * 10
+        assert!((t - 10.).abs() <= 1.0);
+    }
+
+    #[test]
+    pub fn lbfgs_strong_wolfe_rosenbrock() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        let mut ls = StrongWolfe::default();
+        let mut solver = LBFGS::new(1e-10, DVector::from(vec![-1.2, 1.0]));
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,176 @@
+use super::*;
+
+// Nonmonotone line search from [Zhang, H., & Hager, W. W. (2004). A nonmonotone line search technique and its application to unconstrained optimization.]
+// Instead of the maximum of the last m function values (as in GLLQuadratic), the armijo condition is tested against the weighted average C_k of all the previous function values:
+//      Q_{k+1} = eta * Q_k + 1,    C_{k+1} = (eta * Q_k * C_k + f(x_{k+1})) / Q_{k+1},    with Q_0 = 1 and C_0 = f(x_0)
+// eta = 0 gives the monotone armijo line search, while eta = 1 makes C_k the average of all the function values met so far.
+// As for GLLQuadratic, the reference value is updated at every call, so a new instance is needed for every minimization.
+#[derive(Debug, Clone)]
+pub enum ZhangHagerStepReduction {
+    // the step is multiplied by the contraction factor beta in (0, 1)
+    Backtracking(Floating),
+    // the step is the minimizer of the quadratic interpolating phi(0), phi'(0) and phi(t), safeguarded in [sigma1 * t, sigma2 * t]
+    Quadratic { sigma1: Floating, sigma2: Floating },
+}
+
+#[derive(derive_getters::Getters)]
+pub struct ZhangHager {
+    c1: Floating,
+    eta: Floating,
+    step_reduction: ZhangHagerStepReduction,
+    c: Option<Floating>, // reference value C_k
+    q: Floating,         // Q_k
+}
+
+impl ZhangHager {
+    pub fn new(c1: Floating, eta: Floating) -> Self {
+        assert!((0.0..=1.0).contains(&eta), "eta must be in [0, 1]");
+        Self {
+            c1,
+            eta,
+            step_reduction: ZhangHagerStepReduction::Quadratic {
+                sigma1: 0.1,
+                sigma2: 0.9,
+            },
+            c: None,
+            q: 1.0,
+        }
+    }
+    pub fn with_step_reduction(mut self, step_reduction: ZhangHagerStepReduction) -> Self {
+        match step_reduction {
+            ZhangHagerStepReduction::Backtracking(beta) => {
+                assert!(beta > 0.0 && beta < 1.0, "beta must be in (0, 1)")
+            }
+            ZhangHagerStepReduction::Quadratic { sigma1, sigma2 } => assert!(
+                sigma1 > 0.0 && sigma1 <= sigma2 && sigma2 < 1.0,
+                "sigmas must satisfy 0 < sigma1 <= sigma2 < 1"
+            ),
+        }
+        self.step_reduction = step_reduction;
+        self
+    }
+
+    // at the first call the reference value is initialized with f(x_0), then it's updated with the function value at the point accepted in the previous call
+    fn update_reference_value(&mut self, f: Floating) -> Floating {
+        let c = match self.c {
+            None => f,
+            Some(c) => {
+                let q = self.eta * self.q + 1.0;
+                let c = (self.eta * self.q * c + f) / q;
+                self.q = q;
+                c
+            }
+        };
+        self.c = Some(c);
+        c
+    }
+
+    fn reduce_step(
+        &self,
+        t: Floating,
+        f_k: Floating,
+        f_kp1: Floating,
+        dphi_0: Floating,
+    ) -> Floating {
+        match self.step_reduction {
+            ZhangHagerStepReduction::Backtracking(beta) => beta * t,
+            ZhangHagerStepReduction::Quadratic { sigma1, sigma2 } => {
+                if !f_kp1.is_finite() {
+                    trace!(target: "zhang hager line search", "Trial point out of domain; bisecting.");
+                    return 0.5 * t;
+                }
+                let t_tmp = -0.5 * t * t * dphi_0 / (f_kp1 - f_k - t * dphi_0);
+                let t_tmp = t_tmp.max(sigma1 * t).min(sigma2 * t);
+                trace!(target: "zhang hager line search", "Safeguarded step size: {}", t_tmp);
+                t_tmp
+            }
+        }
+    }
+}
+
+impl SufficientDecreaseCondition for ZhangHager {
+    fn c1(&self) -> Floating {
+        self.c1
+    }
+}
+
+impl LineSearch for ZhangHager {
+    fn compute_step_len(
+        &mut self,
+        x_k: &DVector<Floating>,         // current iterate
+        eval_x_k: &FuncEvalMultivariate, // function evaluation at x_k
+        direction_k: &DVector<Floating>, // direction of the ray along which we are going to search
+        oracle: &mut impl Objective,     // oracle
+        max_iter: usize, // maximum number of iterations during line search (if direction update is costly, set this high to perform more exact line search)
+    ) -> LineSearchStep {
+        let c_k = self.update_reference_value(*eval_x_k.f());
+        let dphi_0 = eval_x_k.g().dot(direction_k);
+        let mut t = 1.0;
+        let mut i = 0;
+
+        while max_iter > i {
+            let x_kp1 = x_k + t * direction_k;
+
+            let f_kp1 = oracle.value(&x_kp1);
+
+            // nonmonotone armijo condition
+            if self.sufficient_decrease(&c_k, &f_kp1, eval_x_k.g(), &t, direction_k) {
+                trace!(target: "zhang hager line search", "Sufficient decrease condition met. Exiting with step size: {:?}", t);
+                return LineSearchStep::new(t, None);
+            }
+
+            t = self.reduce_step(t, *eval_x_k.f(), f_kp1, dphi_0);
+            i += 1;
+        }
+        trace!(target: "zhang hager line search", "Max iter reached. Early stopping.");
+        LineSearchStep::new(t, None)
+    }
+}
+
+#[cfg(test)]
+mod zhang_hager_test {
+    use super::*;
+
+    #[test]
+    pub fn reference_value_average() {
+        let mut ls = ZhangHager::new(1e-4, 0.5);
+        assert_eq!(ls.update_reference_value(4.0), 4.0);
+        // Q_1 = 1.5, C_1 = (0.5 * 4 + 1) / 1.5
+        assert!((ls.update_reference_value(1.0) - 2.0).abs() < 1e-12);
+        // Q_2 = 1.75, C_2 = (0.75 * 2 + 0.25) / 1.75
+        assert!((ls.update_reference_value(0.25) - 1.0).abs() < 1e-12);
+
+        // eta = 0 is the monotone line search
+        let mut ls = ZhangHager::new(1e-4, 0.0);
+        ls.update_reference_value(4.0);
+        assert_eq!(ls.update_reference_value(1.0), 1.0);
+    }
+
+    #[test]
+    pub fn zhang_hager_gradient_descent() {
+        let gamma = 90.0;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0].powi(2) + gamma * x[1].powi(2));
+            let g = DVector::from(vec![x[0], gamma * x[1]]);
+            (f, g).into()
+        };
+        let tol = 1e-10;
+        for step_reduction in [
+            ZhangHagerStepReduction::Backtracking(0.5),
+            ZhangHagerStepReduction::Quadratic {
+                sigma1: 0.1,
+                sigma2: 0.9,
+            },
+        ] {
+            let mut ls = ZhangHager::new(1e-4, 0.85).with_step_reduction(step_reduction);
+            let x_0 = DVector::from(vec![180.0, 152.0]);
+            let mut solver = GradientDescent::new(tol, x_0);
+
+            let report = solver.minimize(&mut ls, f_and_g, 10000, 100, None).unwrap();
+
+            println!("Report: {:?}", report);
+            assert!(report.x()[0].abs() < 1e-6);
+            assert!(report.x()[1].abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+
+//Template pattern for solvers computing the step internally (e.g. with a backtracking on the Lipschitz constant or with a learned preconditioner), which therefore don't take a line search (analogous to LineSearchSolver and DerivativeFreeSolver)
+pub trait LineSearchFreeSolver {
+    fn xk(&self) -> &DVector<Floating>;
+    fn k(&self) -> &usize;
+    fn k_mut(&mut self) -> &mut usize;
+    fn has_converged(&self, eval_x_k: &FuncEvalMultivariate) -> Option<TerminationReason>;
+    // slot of the stopping criterion that, when present, replaces the built-in convergence test of the solver
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>>;
+
+    fn with_stopping_criterion(mut self, criterion: impl StoppingCriterion + 'static) -> Self
+    where
+        Self: Sized,
+    {
+        *self.stopping_criterion_mut() = Some(Box::new(criterion));
+        self
+    }
+
+    fn setup(&mut self) {}
+
+    // Solvers for composite objectives F = f + g override this with the value of the nonsmooth term g at x_k, so that the report and the stopping criterion see F instead of the image of the oracle f
+    fn nonsmooth_value(&self) -> Floating {
+        0.0
+    }
+
+    // Solvers with bounds override this so that the report contains the first order optimality measure of the constrained problem
+    fn projected_gradient_norm(&self, _eval_x_k: &FuncEvalMultivariate) -> Option<Floating> {
+        None
+    }
+
+    // one iteration of the method, which updates x_k and returns the evaluation of the oracle at the new iterate
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError>;
+
+    fn minimize(
+        &mut self,
+        oracle: impl Objective,
+        max_iter_solver: usize,
+        mut callback: Option<&mut dyn FnMut(&Self)>,
+    ) -> Result<MinimizationReport, SolverError> {
+        let stopwatch = Stopwatch::start();
+        let mut oracle = CountedObjective::new(oracle);
+
+        *self.k_mut() = 0;
+        let mut f_previous = None;
+        let mut step_norm = None;
+        if let Some(criterion) = self.stopping_criterion_mut() {
+            criterion.reset();
+        }
+
+        self.setup();
+        let mut eval_x_k = oracle.value_and_gradient(self.xk());
+
+        while &max_iter_solver > self.k() {
+            if !eval_x_k.f().is_finite() {
+                error!(target: "solver","Minimization completed: next iterate is out of domain");
+                return Err(SolverError::OutOfDomain);
+            }
+            // image of the whole objective, with the gradient of the smooth part
+            let eval_objective: FuncEvalMultivariate =
+                (eval_x_k.f() + self.nonsmooth_value(), eval_x_k.g().clone()).into();
+
+            let termination_reason = match self.stopping_criterion_mut().take() {
+                Some(mut criterion) => {
+                    let state = SolverState::new(
+                        *self.k(),
+                        self.xk(),
+                        &eval_objective,
+                        f_previous,
+                        step_norm,
+                        self.projected_gradient_norm(&eval_x_k),
+                        oracle.counts(),
+                        stopwatch.elapsed(),
+                    );
+                    let termination_reason = criterion.check(&state);
+                    *self.stopping_criterion_mut() = Some(criterion);
+                    termination_reason
+                }
+                None => self.has_converged(&eval_x_k),
+            };
+
+            if let Some(termination_reason) = termination_reason {
+                info!(
+                    target: "solver",
+                    "Minimization completed: {} in {} iterations",
+                    termination_reason,
+                    self.k()
+                );
+                return Ok(MinimizationReport::new(
+                    self.xk().clone(),
+                    &eval_objective,
+                    self.projected_gradient_norm(&eval_x_k),
+                    *self.k(),
+                    oracle.counts(),
+                    stopwatch.elapsed(),
+                    termination_reason,
+                ));
+            }
+
+            let x_previous = self.xk().clone();
+            eval_x_k = self.iterate(&eval_x_k, &mut oracle)?;
+            debug!(target: "solver","Iterate: {:?}, Function eval: {:?}", self.xk(), eval_x_k);
+
+            f_previous = Some(*eval_objective.f());
+            step_norm = Some((self.xk() - x_previous).norm());
+
+            *self.k_mut() += 1;
+            if let Some(callback) = callback.as_mut() {
+                callback(self);
+            }
+        }
+        warn!(target: "solver","Minimization completed: max iter reached during minimization");
+        Err(SolverError::MaxIterReached)
+    }
+}
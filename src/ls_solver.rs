@@ -13,10 +13,12 @@ pub enum SolverError {
     MaxIterReached,
     #[error("Out of domain")]
     OutOfDomain,
-    #[error("Error in input parameters")]
-    ErrorInputParams,
-    #[error("Abnormal termination")]
-    AbnormalTermination,
+    #[error("Error in input parameters: {0}")]
+    ErrorInputParams(String),
+    #[error("Abnormal termination: {0}")]
+    AbnormalTermination(String),
+    #[error("Hessian not available in the oracle")]
+    MissingHessian,
 }
 
 //Template pattern for solvers. Methods that are already implemented can be freely overriden.
@@ -25,19 +27,69 @@ pub trait LineSearchSolver: ComputeDirection {
     fn xk_mut(&mut self) -> &mut DVector<Floating>;
     fn k(&self) -> &usize;
     fn k_mut(&mut self) -> &mut usize;
-    fn has_converged(&self, eval_x_k: &FuncEvalMultivariate) -> bool;
+    fn has_converged(&self, eval_x_k: &FuncEvalMultivariate) -> Option<TerminationReason>;
+    // slot of the stopping criterion that, when present, replaces the built-in convergence test of the solver
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>>;
+
+    fn with_stopping_criterion(mut self, criterion: impl StoppingCriterion + 'static) -> Self
+    where
+        Self: Sized,
+    {
+        *self.stopping_criterion_mut() = Some(Box::new(criterion));
+        self
+    }
 
     fn setup(&mut self) {}
 
+    // Second order solvers override this so that the hessian is requested to the oracle when evaluating the iterate
+    fn requires_hessian(&self) -> bool {
+        false
+    }
+
+    // Hessian-free solvers override this to query the oracle (e.g. for hessian-vector products at x_k) while computing the direction
+    fn compute_direction_with_oracle(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        _oracle: &mut impl Objective,
+    ) -> Result<DVector<Floating>, SolverError> {
+        self.compute_direction(eval_x_k)
+    }
+
+    // Solvers with bounds override this so that the report contains the first order optimality measure of the constrained problem
+    fn projected_gradient_norm(&self, _eval_x_k: &FuncEvalMultivariate) -> Option<Floating> {
+        None
+    }
+
+    // Solvers modifying the hessian override this so that the report contains the shift used at each iteration
+    fn regularization_shifts(&self) -> Option<Vec<Floating>> {
+        None
+    }
+
+    // Solvers inducing sparsity override this so that the report contains the number of exact zeros of the solution
+    fn zeros(&self) -> Option<usize> {
+        None
+    }
+
+    // eval_x_k is the evaluation at x_k already computed by the line search (if any), so that the oracle is not queried twice at the same point
     fn evaluate_x_k(
         &mut self,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        eval_x_k: Option<FuncEvalMultivariate>,
     ) -> Result<FuncEvalMultivariate, SolverError> {
-        let eval_x_k = oracle(self.xk());
+        let mut eval_x_k = match eval_x_k {
+            Some(eval_x_k) => eval_x_k,
+            None => oracle.value_and_gradient(self.xk()),
+        };
         if eval_x_k.f().is_nan() || eval_x_k.f().is_infinite() {
             error!(target: "solver","Minimization completed: next iterate is out of domain");
             return Err(SolverError::OutOfDomain);
         }
+        if self.requires_hessian() && eval_x_k.hessian().is_none() {
+            let hessian = oracle
+                .hessian(self.xk())
+                .ok_or(SolverError::MissingHessian)?;
+            eval_x_k = eval_x_k.with_hessian(hessian);
+        }
         Ok(eval_x_k)
     }
 
@@ -45,52 +97,89 @@ pub trait LineSearchSolver: ComputeDirection {
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval_x_k: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let next_iterate = self.xk() + step * direction;
         *self.xk_mut() = next_iterate;
 
-        Ok(())
+        Ok(eval_next_iterate)
     }
 
     fn minimize<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
-        mut oracle: impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: impl Objective,
         max_iter_solver: usize,
         max_iter_line_search: usize,
         mut callback: Option<&mut dyn FnMut(&Self)>,
-    ) -> Result<(), SolverError> {
+    ) -> Result<MinimizationReport, SolverError> {
+        let stopwatch = Stopwatch::start();
+        // we wrap the oracle so that every evaluation (also the ones performed by the line search) is counted
+        let mut oracle = CountedObjective::new(oracle);
+
         *self.k_mut() = 0;
+        let mut f_previous = None;
+        let mut step_norm = None;
+        let mut eval_next_iterate = None;
+        if let Some(criterion) = self.stopping_criterion_mut() {
+            criterion.reset();
+        }
 
         self.setup();
 
         while &max_iter_solver > self.k() {
-            let eval_x_k = self.evaluate_x_k(&mut oracle)?;
-
-            if self.has_converged(&eval_x_k) {
+            let eval_x_k = self.evaluate_x_k(&mut oracle, eval_next_iterate.take())?;
+
+            let termination_reason = match self.stopping_criterion_mut().take() {
+                Some(mut criterion) => {
+                    let state = SolverState::new(
+                        *self.k(),
+                        self.xk(),
+                        &eval_x_k,
+                        f_previous,
+                        step_norm,
+                        self.projected_gradient_norm(&eval_x_k),
+                        oracle.counts(),
+                        stopwatch.elapsed(),
+                    );
+                    let termination_reason = criterion.check(&state);
+                    *self.stopping_criterion_mut() = Some(criterion);
+                    termination_reason
+                }
+                None => self.has_converged(&eval_x_k),
+            };
+
+            if let Some(termination_reason) = termination_reason {
                 info!(
                     target: "solver",
-                    "Minimization completed: convergence in {} iterations",
+                    "Minimization completed: {} in {} iterations",
+                    termination_reason,
                     self.k()
                 );
-                return Ok(());
+                return Ok(MinimizationReport::new(
+                    self.xk().clone(),
+                    &eval_x_k,
+                    self.projected_gradient_norm(&eval_x_k),
+                    *self.k(),
+                    oracle.counts(),
+                    stopwatch.elapsed(),
+                    termination_reason,
+                )
+                .with_regularization_shifts(self.regularization_shifts())
+                .with_zeros(self.zeros()));
             }
 
-            let direction = self.compute_direction(&eval_x_k)?;
+            let direction = self.compute_direction_with_oracle(&eval_x_k, &mut oracle)?;
 
             debug!(target: "solver","Gradient: {:?}, Direction: {:?}", eval_x_k.g(), direction);
-            self.update_next_iterate(
+            let x_previous = self.xk().clone();
+            eval_next_iterate = self.update_next_iterate(
                 line_search,
                 &eval_x_k,
                 &mut oracle,
@@ -101,6 +190,9 @@ pub trait LineSearchSolver: ComputeDirection {
             debug!(target: "solver","Iterate: {:?}", self.xk());
             debug!(target: "solver","Function eval: {:?}", eval_x_k);
 
+            f_previous = Some(*eval_x_k.f());
+            step_norm = Some((self.xk() - x_previous).norm());
+
             *self.k_mut() += 1;
             if let Some(callback) = callback.as_mut() {
                 callback(self);
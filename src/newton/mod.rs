@@ -4,12 +4,98 @@ pub mod projected_newton;
 pub use projected_newton::*;
 pub mod spn;
 pub use spn::*;
+pub mod newton_cg;
+pub use newton_cg::*;
+// Strategies to make the hessian positive definite when it's not, so that the newton direction is a descent direction that still exploits the second order information (section 3.4 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// In both cases the shift is the euclidean norm of the modification, i.e. the largest amount added to the eigenvalues of the hessian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HessianModification {
+    // Algorithm 3.3: the hessian is replaced with H + tau * I, where tau is the first value of the sequence tau_0, max(2 * tau_0, beta), ... for which the cholesky factorization succeeds
+    CholeskyAddedIdentity { beta: Floating },
+    // the eigenvalues of the hessian below delta are replaced with delta (minimum Frobenius norm modification, equation 3.42)
+    EigenvalueModification { delta: Floating },
+}
+
+impl HessianModification {
+    // maximum number of times the shift of CholeskyAddedIdentity is doubled before giving up
+    const MAX_SHIFT_DOUBLINGS: usize = 60;
+
+    // returns the solution of (H + E) d = -g and the norm of E
+    pub fn newton_direction(
+        &self,
+        hessian: DMatrix<Floating>,
+        g: &DVector<Floating>,
+    ) -> Result<(DVector<Floating>, Floating), SolverError> {
+        if !hessian.iter().all(|h| h.is_finite()) {
+            return Err(SolverError::AbnormalTermination(
+                "Hessian with non finite entries".to_string(),
+            ));
+        }
+        match *self {
+            HessianModification::CholeskyAddedIdentity { beta } => {
+                let min_diagonal = hessian.diagonal().min();
+                let mut tau = if min_diagonal > 0.0 {
+                    0.0
+                } else {
+                    -min_diagonal + beta
+                };
+                for _ in 0..Self::MAX_SHIFT_DOUBLINGS {
+                    let shifted = &hessian + DMatrix::identity(g.len(), g.len()) * tau;
+                    if let Some(cholesky) = shifted.cholesky() {
+                        return Ok((-cholesky.solve(g), tau));
+                    }
+                    tau = (2.0 * tau).max(beta);
+                }
+                Err(SolverError::AbnormalTermination(format!(
+                    "Cholesky factorization of the shifted hessian failed with shift {}",
+                    tau
+                )))
+            }
+            HessianModification::EigenvalueModification { delta } => {
+                let eigen = hessian.symmetric_eigen();
+                let shift = (delta - eigen.eigenvalues.min()).max(0.0);
+                let eigenvalues = eigen.eigenvalues.map(|lambda| lambda.max(delta));
+                let q = eigen.eigenvectors;
+                let direction = -&q * (q.tr_mul(g).component_div(&eigenvalues));
+                Ok((direction, shift))
+            }
+        }
+    }
+}
+
+// newton step -H^{-1} g used by the projected newton methods: without a modification the hessian must be positive definite, otherwise the step would not be a descent direction
+pub(crate) fn positive_definite_newton_step(
+    hessian: DMatrix<Floating>,
+    g: &DVector<Floating>,
+    modification: Option<HessianModification>,
+) -> Result<DVector<Floating>, SolverError> {
+    match modification {
+        Some(modification) => {
+            let (step, shift) = modification.newton_direction(hessian, g)?;
+            if shift > 0.0 {
+                debug!(target:"newton","Hessian modified with shift {}", shift);
+            }
+            Ok(step)
+        }
+        None => {
+            let cholesky = hessian.cholesky().ok_or_else(|| {
+                SolverError::AbnormalTermination("Hessian is not positive definite".to_string())
+            })?;
+            Ok(-cholesky.solve(g))
+        }
+    }
+}
+
 #[derive(derive_getters::Getters)]
 pub struct Newton {
     tol: Floating,
     decrement_squared: Option<Floating>,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    modification: Option<HessianModification>,
+    regularization_shifts: Vec<Floating>, // shift added to the hessian at each iteration
 }
 
 impl Newton {
@@ -19,8 +105,16 @@ impl Newton {
             decrement_squared: None,
             x: x0,
             k: 0,
+            stopping_criterion: None,
+            modification: None,
+            regularization_shifts: vec![],
         }
     }
+    // modified newton method: the hessian is made positive definite before solving the newton system
+    pub fn with_modification(mut self, modification: HessianModification) -> Self {
+        self.modification = Some(modification);
+        self
+    }
 }
 
 impl ComputeDirection for Newton {
@@ -28,26 +122,35 @@ impl ComputeDirection for Newton {
         &mut self,
         eval: &FuncEvalMultivariate,
     ) -> Result<DVector<Floating>, SolverError> {
-        let hessian = eval
-            .hessian()
-            .clone()
-            .expect("Hessian not available in the oracle");
+        let hessian = eval.hessian().clone().ok_or(SolverError::MissingHessian)?;
+        let g = eval.g();
         //[TODO]: Boyd recommends several alternatives to the solution of Newton system which take advantage of prior information about sparsity/banded bandwidth of the hessian.
-        match hessian.try_inverse() {
-            Some(hessian_inv) => {
-                let direction = -&hessian_inv * eval.g();
-                // we compute also the squared newton decrement
-                self.decrement_squared = Some((hessian_inv * &direction).dot(&direction));
-                Ok(direction)
-            }
-            None => {
-                warn!(target:"newton","Hessian is singular. Using gradient descent direction.");
-                Ok(-eval.g())
-            }
+        // the newton system is solved by factorization: cholesky if the hessian is positive definite, LU otherwise
+        let (direction, shift) = match self.modification {
+            Some(modification) => modification.newton_direction(hessian, g)?,
+            None => match hessian.clone().cholesky() {
+                Some(cholesky) => (-cholesky.solve(g), 0.0),
+                None => match hessian.lu().solve(g) {
+                    Some(direction) => (-direction, 0.0),
+                    None => {
+                        warn!(target:"newton","Hessian is singular. Using gradient descent direction.");
+                        // no second order information: the decrement is not available and the shift is undefined
+                        self.decrement_squared = None;
+                        self.regularization_shifts.push(Floating::NAN);
+                        return Ok(-g);
+                    }
+                },
+            },
+        };
+        // the squared newton decrement g^T H^{-1} g (with the modified hessian, if any); it's negative when the LU direction of an indefinite hessian is not a descent direction
+        self.decrement_squared = Some(-g.dot(&direction));
+        if shift > 0.0 {
+            debug!(target:"newton","Hessian modified with shift {}", shift);
         }
+        self.regularization_shifts.push(shift);
+        Ok(direction)
     }
 }
-
 impl LineSearchSolver for Newton {
     fn xk(&self) -> &DVector<Floating> {
         &self.x
@@ -61,10 +164,26 @@ impl LineSearchSolver for Newton {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, _: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn requires_hessian(&self) -> bool {
+        true
+    }
+    fn setup(&mut self) {
+        self.decrement_squared = None;
+        self.regularization_shifts.clear();
+    }
+    fn regularization_shifts(&self) -> Option<Vec<Floating>> {
+        Some(self.regularization_shifts.clone())
+    }
+    fn has_converged(&self, _: &FuncEvalMultivariate) -> Option<TerminationReason> {
         match self.decrement_squared {
-            Some(decrement_squared) => decrement_squared * 0.5 < self.tol,
-            None => false,
+            // the decrement is a stopping test only when the direction is not an ascent direction
+            Some(decrement_squared) => (decrement_squared >= 0.0
+                && decrement_squared * 0.5 < self.tol)
+                .then_some(TerminationReason::NewtonDecrement),
+            None => None,
         }
     }
 }
@@ -161,4 +280,86 @@ mod newton_test {
 
         assert!((eval.f() - 0.0).abs() < 1e-6);
     }
+
+    #[test]
+    pub fn hessian_modification() {
+        let hessian = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, -2.0]);
+        let g = DVector::from(vec![1.0, 1.0]);
+        for modification in [
+            HessianModification::CholeskyAddedIdentity { beta: 1e-3 },
+            HessianModification::EigenvalueModification { delta: 1e-3 },
+        ] {
+            let (direction, shift) = modification.newton_direction(hessian.clone(), &g).unwrap();
+            // the direction is a descent direction and the shift makes the hessian positive definite
+            assert!(direction.dot(&g) < 0.0, "{:?}", modification);
+            assert!(shift > 2.0, "{:?}", modification);
+        }
+
+        // positive definite hessians are not modified
+        let hessian = DMatrix::from_row_slice(2, 2, &[2.0, 1.0, 1.0, 2.0]);
+        let (direction, shift) = HessianModification::CholeskyAddedIdentity { beta: 1e-3 }
+            .newton_direction(hessian.clone(), &g)
+            .unwrap();
+        assert_eq!(shift, 0.0);
+        assert!((hessian * direction + &g).norm() < 1e-12);
+
+        // a hessian with non finite entries is an error instead of an endless increase of the shift
+        let hessian = DMatrix::from_row_slice(2, 2, &[Floating::NAN, 0.0, 0.0, 1.0]);
+        for modification in [
+            HessianModification::CholeskyAddedIdentity { beta: 1e-3 },
+            HessianModification::EigenvalueModification { delta: 1e-3 },
+        ] {
+            assert!(modification.newton_direction(hessian.clone(), &g).is_err());
+        }
+    }
+
+    #[test]
+    pub fn newton_indefinite_hessian_decrement() {
+        // the LU direction of an indefinite hessian is an ascent direction: its negative decrement must not stop the solver
+        let mut nt = Newton::new(1e-8, DVector::from(vec![1.0, 1.0]));
+        let eval = FuncEvalMultivariate::new(0.0, DVector::from(vec![1e-6, 1.0]))
+            .with_hessian(DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, -1.0]));
+        nt.compute_direction(&eval).unwrap();
+        assert!(nt.decrement_squared().unwrap() < 0.0);
+        assert_eq!(nt.has_converged(&eval), None);
+
+        // singular hessian: gradient direction, no decrement and a NaN shift recorded for the iteration
+        let eval = FuncEvalMultivariate::new(0.0, DVector::from(vec![1.0, 1.0]))
+            .with_hessian(DMatrix::zeros(2, 2));
+        assert_eq!(nt.compute_direction(&eval).unwrap(), -eval.g());
+        assert_eq!(nt.decrement_squared(), &None);
+        assert_eq!(nt.regularization_shifts().len(), 2);
+        assert!(nt.regularization_shifts()[1].is_nan());
+    }
+
+    #[test]
+    pub fn modified_newton_nonconvex() {
+        // f(x, y) = x^4 / 4 - x^2 / 2 + y^2 has an indefinite hessian for |x| < 1 / sqrt(3) and minima at (+-1, 0)
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.25 * x[0].powi(4) - 0.5 * x[0].powi(2) + x[1].powi(2);
+            let g = DVector::from(vec![x[0].powi(3) - x[0], 2. * x[1]]);
+            let hessian = DMatrix::from_row_slice(2, 2, &[3. * x[0].powi(2) - 1., 0., 0., 2.]);
+            FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+        };
+
+        for modification in [
+            HessianModification::CholeskyAddedIdentity { beta: 1e-3 },
+            HessianModification::EigenvalueModification { delta: 1e-3 },
+        ] {
+            let mut ls = MoreThuente::default();
+            let x_0 = DVector::from(vec![0.1, 1.0]);
+            let mut nt = Newton::new(1e-12, x_0).with_modification(modification);
+
+            let report = nt.minimize(&mut ls, oracle, 100, 100, None).unwrap();
+
+            println!("{:?}: {:?}", modification, report);
+            assert!((report.x()[0].abs() - 1.).abs() < 1e-6);
+            assert!(report.x()[1].abs() < 1e-6);
+            let shifts = report.regularization_shifts().as_ref().unwrap();
+            assert_eq!(shifts.len(), *report.iterations());
+            // the hessian is regularized at the first iteration and not close to the solution
+            assert!(shifts[0] > 0.0);
+            assert_eq!(shifts.last(), Some(&0.0));
+        }
+    }
 }
@@ -0,0 +1,343 @@
+use super::*;
+
+// Truncated Newton method (Newton-CG, Algorithm 7.1 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// The newton system H_k d = -g_k is solved inexactly with (preconditioned) conjugate gradient iterations, which only need products of the hessian with vectors: the dense hessian is never formed, so memory is O(n).
+// The products are requested to the oracle (Objective::hessian_vector_product) or approximated by finite differences of the gradient: H v ~ (g(x + h v) - g(x)) / h. If the oracle attaches the dense hessian to the evaluation anyway, the products are computed from it.
+// The inner iterations stop when the residual satisfies ||H_k d + g_k|| <= eta_k ||g_k||, where eta_k follows the second choice of [Eisenstat, S. C., & Walker, H. F. (1996). Choosing the forcing terms in an inexact Newton method.]:
+//      eta_k = gamma * (||g_k|| / ||g_{k-1}||)^alpha,  with gamma = 0.9 and alpha = 2, safeguarded so that it doesn't decrease too fast and never exceeds eta_max = 0.9
+// or when a direction of non positive curvature is found: the iterate computed so far is returned (the steepest descent direction if it's the first iteration), which is always a descent direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HessianVectorProduct {
+    // products provided by the oracle (if the oracle doesn't provide them, we switch to finite differences)
+    Oracle,
+    // forward finite differences of the gradient
+    FiniteDifferences,
+}
+
+#[derive(derive_getters::Getters)]
+pub struct NewtonCG {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    hessian_vector_product: HessianVectorProduct,
+    preconditioner: Option<DVector<Floating>>, // diagonal of the inverse of the preconditioner
+    max_cg_iter: Option<usize>, // maximum number of conjugate gradient iterations (dimension of the problem if None)
+    eta: Floating,              // current forcing term
+    g_norm_previous: Option<Floating>,
+}
+
+impl NewtonCG {
+    const ETA_MAX: Floating = 0.9;
+    const GAMMA: Floating = 0.9;
+    const ALPHA: Floating = 2.0;
+
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        NewtonCG {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            hessian_vector_product: HessianVectorProduct::Oracle,
+            preconditioner: None,
+            max_cg_iter: None,
+            eta: 0.5,
+            g_norm_previous: None,
+        }
+    }
+    pub fn with_hessian_vector_product(
+        mut self,
+        hessian_vector_product: HessianVectorProduct,
+    ) -> Self {
+        self.hessian_vector_product = hessian_vector_product;
+        self
+    }
+    // diagonal preconditioner M: the argument is the diagonal of M^{-1}, which must be positive
+    pub fn with_diagonal_preconditioner(mut self, inverse_diagonal: DVector<Floating>) -> Self {
+        assert_eq!(
+            inverse_diagonal.len(),
+            self.x.len(),
+            "preconditioner has wrong dimension"
+        );
+        assert!(
+            inverse_diagonal.iter().all(|d| d > &0.0),
+            "preconditioner must be positive definite"
+        );
+        self.preconditioner = Some(inverse_diagonal);
+        self
+    }
+    pub fn with_max_cg_iter(mut self, max_cg_iter: usize) -> Self {
+        self.max_cg_iter = Some(max_cg_iter);
+        self
+    }
+
+    fn update_forcing_term(&mut self, g_norm: Floating) {
+        if let Some(g_norm_previous) = self.g_norm_previous {
+            let eta = Self::GAMMA * (g_norm / g_norm_previous).powf(Self::ALPHA);
+            // safeguard: if the previous forcing term was large, don't let it drop too quickly
+            let safeguard = Self::GAMMA * self.eta.powf(Self::ALPHA);
+            let eta = if safeguard > 0.1 {
+                eta.max(safeguard)
+            } else {
+                eta
+            };
+            self.eta = eta.min(Self::ETA_MAX);
+        }
+        self.g_norm_previous = Some(g_norm);
+    }
+
+    fn precondition(&self, r: &DVector<Floating>) -> DVector<Floating> {
+        match &self.preconditioner {
+            Some(inverse_diagonal) => r.component_mul(inverse_diagonal),
+            None => r.clone(),
+        }
+    }
+
+    // preconditioned conjugate gradient on H d = -g with the inexact newton stopping rule
+    fn truncated_cg(
+        &self,
+        g: &DVector<Floating>,
+        mut hessian_vector_product: impl FnMut(&DVector<Floating>) -> DVector<Floating>,
+    ) -> DVector<Floating> {
+        let tol = self.eta * g.norm();
+        let mut z = DVector::zeros(g.len());
+        let mut r = g.clone();
+        let mut y = self.precondition(&r);
+        let mut p = -&y;
+        let mut ry = r.dot(&y);
+
+        for j in 0..self.max_cg_iter.unwrap_or(g.len()) {
+            let hp = hessian_vector_product(&p);
+            let curvature = p.dot(&hp);
+            if curvature <= Floating::EPSILON * p.norm_squared() {
+                debug!(target: "newton_cg", "Non positive curvature at inner iteration {}", j);
+                return if j == 0 { -g } else { z };
+            }
+            let alpha = ry / curvature;
+            z.axpy(alpha, &p, 1.0);
+            r.axpy(alpha, &hp, 1.0);
+            if r.norm() <= tol {
+                trace!(target: "newton_cg", "Inexact newton condition met at inner iteration {}", j);
+                return z;
+            }
+            y = self.precondition(&r);
+            let ry_next = r.dot(&y);
+            let beta = ry_next / ry;
+            ry = ry_next;
+            p = -&y + beta * p;
+        }
+        z
+    }
+
+    // forward difference of the gradient along v, with step sqrt(eps) * (1 + ||x||) / ||v||
+    fn finite_difference_product(
+        x: &DVector<Floating>,
+        g: &DVector<Floating>,
+        v: &DVector<Floating>,
+        oracle: &mut impl Objective,
+    ) -> DVector<Floating> {
+        let h = Floating::EPSILON.sqrt() * (1.0 + x.norm()) / v.norm();
+        (oracle.gradient(&(x + h * v)) - g) / h
+    }
+}
+
+impl ComputeDirection for NewtonCG {
+    // without access to the oracle, the products are computed from the dense hessian of the evaluation (if any)
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let hessian = eval.hessian().as_ref().ok_or(SolverError::MissingHessian)?;
+        self.update_forcing_term(eval.g().norm());
+        Ok(self.truncated_cg(eval.g(), |v| hessian * v))
+    }
+}
+
+impl LineSearchSolver for NewtonCG {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn setup(&mut self) {
+        self.eta = 0.5;
+        self.g_norm_previous = None;
+    }
+
+    fn compute_direction_with_oracle(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let g = eval_x_k.g();
+        if self.hessian_vector_product == HessianVectorProduct::Oracle {
+            if let Some(hessian) = eval_x_k.hessian() {
+                // the oracle already attached the dense hessian: asking it for the products would evaluate it again at each inner iteration
+                self.update_forcing_term(g.norm());
+                return Ok(self.truncated_cg(g, |v| hessian * v));
+            }
+        }
+        self.update_forcing_term(g.norm());
+        let x = self.x.clone();
+        let mut mode = self.hessian_vector_product;
+        let direction = self.truncated_cg(g, |v| {
+            if mode == HessianVectorProduct::Oracle {
+                match oracle.hessian_vector_product(&x, v) {
+                    Some(hv) => return hv,
+                    None => {
+                        debug!(target: "newton_cg", "Hessian-vector products not provided by the oracle: switching to finite differences");
+                        mode = HessianVectorProduct::FiniteDifferences;
+                    }
+                }
+            }
+            Self::finite_difference_product(&x, g, v, oracle)
+        });
+        // the oracle can't provide the products: we don't ask again in the next iterations
+        self.hessian_vector_product = mode;
+        Ok(direction)
+    }
+}
+
+#[cfg(test)]
+mod newton_cg_test {
+    use super::*;
+
+    // f(x) = sum_i d_i * x_i^2 / 2 + (x_i - 1)^4 / 4, exposing only hessian-vector products (the hessian is diagonal here, but it's never formed)
+    struct LargeObjective {
+        d: DVector<Floating>,
+    }
+
+    impl Objective for LargeObjective {
+        fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate {
+            let shifted = x.add_scalar(-1.0);
+            let f = 0.5 * x.component_mul(&self.d).dot(x) + 0.25 * shifted.map(|s| s.powi(4)).sum();
+            let g = x.component_mul(&self.d) + shifted.map(|s| s.powi(3));
+            FuncEvalMultivariate::new(f, g)
+        }
+        fn hessian_vector_product(
+            &mut self,
+            x: &DVector<Floating>,
+            v: &DVector<Floating>,
+        ) -> Option<DVector<Floating>> {
+            let diagonal = &self.d + x.add_scalar(-1.0).map(|s| 3.0 * s.powi(2));
+            Some(diagonal.component_mul(v))
+        }
+    }
+
+    #[test]
+    pub fn newton_cg_hessian_vector_products() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let n = 10_000;
+        let d = DVector::from_fn(n, |i, _| 1. + 999. * (i as Floating) / (n as Floating));
+        // close to the solution the decrease of f is below its rounding error, so we use the approximate wolfe conditions
+        let mut ls = HagerZhang::default();
+        let x_0 = DVector::zeros(n);
+        let mut solver = NewtonCG::new(1e-8, x_0).with_diagonal_preconditioner(d.map(|d| 1.0 / d));
+
+        let report = solver
+            .minimize(&mut ls, LargeObjective { d }, 100, 100, None)
+            .unwrap();
+
+        println!(
+            "Iterations: {:?}, evaluations: {:?}",
+            report.iterations(),
+            report.evaluations()
+        );
+        assert!(report.gradient_norm() < &1e-8);
+        assert_eq!(report.evaluations().hessian(), &0);
+        assert!(report.evaluations().hessian_vector_products() > &0);
+        // superlinear convergence
+        assert!(report.iterations() < &20);
+    }
+
+    #[test]
+    pub fn newton_cg_finite_differences_rosenbrock() {
+        // the closure doesn't provide second order information: the products are computed by finite differences
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        let mut ls = MoreThuente::default();
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = NewtonCG::new(1e-6, x_0)
+            .with_hessian_vector_product(HessianVectorProduct::FiniteDifferences);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-5);
+        assert!((report.x()[1] - 1.).abs() < 1e-5);
+        assert_eq!(report.evaluations().hessian_vector_products(), &0);
+    }
+
+    #[test]
+    pub fn newton_cg_attached_hessian() {
+        // the closure attaches the dense hessian: the products use it instead of evaluating the closure again at each inner iteration
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            let hessian = DMatrix::from_row_slice(
+                2,
+                2,
+                &[
+                    1200. * x[0].powi(2) - 400. * x[1] + 2.,
+                    -400. * x[0],
+                    -400. * x[0],
+                    200.,
+                ],
+            );
+            FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+        };
+        let mut ls = MoreThuente::default();
+        let mut solver = NewtonCG::new(1e-6, DVector::from(vec![-1.2, 1.0]));
+
+        let report = solver.minimize(&mut ls, oracle, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-5);
+        assert!((report.x()[1] - 1.).abs() < 1e-5);
+        assert_eq!(report.evaluations().hessian_vector_products(), &0);
+        // one evaluation per line search trial, no hidden evaluations for the products
+        assert!(report.evaluations().f() <= &(3 * report.iterations() + 3));
+    }
+
+    #[test]
+    pub fn newton_cg_negative_curvature() {
+        // indefinite hessian: the inner iterations stop at the first direction of negative curvature, still returning a descent direction
+        let solver = NewtonCG::new(1e-6, DVector::zeros(2));
+        let hessian = DMatrix::from_row_slice(2, 2, &[-1.0, 0.0, 0.0, 2.0]);
+        let g = DVector::from(vec![1.0, 0.0]);
+        let direction = solver.truncated_cg(&g, |v| &hessian * v);
+        assert_eq!(direction, -&g);
+
+        let g = DVector::from(vec![0.1, 1.0]);
+        let direction = solver.truncated_cg(&g, |v| &hessian * v);
+        assert!(direction.dot(&g) < 0.0);
+    }
+}
@@ -5,10 +5,13 @@ pub struct ProjectedNewton {
     grad_tol: Floating,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
     s_norm: Option<Floating>,
     y_norm: Option<Floating>,
+    modification: Option<HessianModification>,
 }
 
 impl ProjectedNewton {
@@ -37,13 +40,20 @@ impl ProjectedNewton {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
             lower_bound,
             upper_bound,
             s_norm: None,
             y_norm: None,
+            modification: None,
             // pg,
         }
     }
+    // indefinite hessians are made positive definite (see HessianModification) instead of stopping the minimization with an error
+    pub fn with_modification(mut self, modification: HessianModification) -> Self {
+        self.modification = Some(modification);
+        self
+    }
 }
 
 impl HasBounds for ProjectedNewton {
@@ -67,12 +77,10 @@ impl ComputeDirection for ProjectedNewton {
         eval: &FuncEvalMultivariate,
     ) -> Result<DVector<Floating>, SolverError> {
         // Ok(-eval.g())
-        let hessian = eval
-            .hessian()
-            .clone()
-            .expect("Hessian not available in the oracle");
+        let hessian = eval.hessian().clone().ok_or(SolverError::MissingHessian)?;
+        let newton_step = positive_definite_newton_step(hessian, eval.g(), self.modification)?;
         // let direction = &self.x - eval.g();
-        let direction = &self.x - &hessian.cholesky().unwrap().solve(eval.g());
+        let direction = &self.x + newton_step;
         let direction = direction.box_projection(&self.lower_bound, &self.upper_bound);
         let direction = direction - &self.x;
         Ok(direction)
@@ -92,38 +100,42 @@ impl LineSearchSolver for ProjectedNewton {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn requires_hessian(&self) -> bool {
+        true
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // we verify that the norm of the gradient is below the tolerance. If the projected gradient is available, then it means that we are in a constrained optimization setting and we verify if it is zero since this is equivalent to first order conditions of optimality in the setting of optimization with simple bounds (Theorem 12.3 from [Neculai Andrei, 2022])
 
         if self.next_iterate_too_close() {
-            warn!(target: "bfgs","Minimization completed: next iterate too close");
-            true
+            Some(TerminationReason::NextIterateTooClose)
         } else if self.gradient_next_iterate_too_close() {
-            warn!(target: "bfgs","Minimization completed: gradient next iterate too close");
-            true
+            Some(TerminationReason::GradientNextIterateTooClose)
         } else {
             let proj_grad = self.projected_gradient(eval);
             // warn!(target: "projected_newton", "Projected gradient: {:?}", proj_grad);
             // we compute the infinity norm of the projected gradient
-            proj_grad.infinity_norm() < self.grad_tol
+            (proj_grad.infinity_norm() < self.grad_tol)
+                .then_some(TerminationReason::GradientTolerance)
         }
     }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         debug!(target: "projected_newton", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
 
@@ -131,12 +143,15 @@ impl LineSearchSolver for ProjectedNewton {
 
         let s = &next_iterate - &self.x;
         self.s_norm = Some(s.norm());
-        let y = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
         self.y_norm = Some(y.norm());
 
         *self.xk_mut() = next_iterate;
 
-        Ok(())
+        Ok(Some(eval_next_iterate))
     }
 }
 
@@ -196,4 +211,52 @@ mod projected_newton_tests {
         let convergence = gd.has_converged(&eval);
         println!("Convergence: {:?}", convergence);
     }
+
+    #[test]
+    pub fn projected_newton_direction_errors() {
+        let bound = DVector::from_element(2, 10.0);
+        let mut solver = ProjectedNewton::new(1e-6, DVector::zeros(2), -&bound, bound);
+        let g = DVector::from(vec![1.0, 1.0]);
+
+        // missing and indefinite hessians are errors, not panics
+        let eval = FuncEvalMultivariate::new(0.0, g.clone());
+        assert!(matches!(
+            solver.compute_direction(&eval),
+            Err(SolverError::MissingHessian)
+        ));
+        let eval = FuncEvalMultivariate::new(0.0, g).with_hessian(DMatrix::from_row_slice(
+            2,
+            2,
+            &[1.0, 0.0, 0.0, -1.0],
+        ));
+        assert!(matches!(
+            solver.compute_direction(&eval),
+            Err(SolverError::AbnormalTermination(_))
+        ));
+    }
+
+    #[test]
+    pub fn projected_newton_modified_nonconvex() {
+        // f(x, y) = x^4 / 4 - x^2 / 2 + y^2 has an indefinite hessian for |x| < 1 / sqrt(3): with the modification the solver moves to the minimizer (1, 0) instead of failing
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.25 * x[0].powi(4) - 0.5 * x[0].powi(2) + x[1].powi(2);
+            let g = DVector::from(vec![x[0].powi(3) - x[0], 2. * x[1]]);
+            let hessian = DMatrix::from_row_slice(2, 2, &[3. * x[0].powi(2) - 1., 0., 0., 2.]);
+            FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+        };
+        let lower_bound = DVector::from(vec![0.0, -2.0]);
+        let upper_bound = DVector::from(vec![2.0, 2.0]);
+        let x_0 = DVector::from(vec![0.1, 1.0]);
+
+        let mut ls = BackTracking::new(1e-4, 0.5);
+        let mut solver =
+            ProjectedNewton::new(1e-8, x_0.clone(), lower_bound.clone(), upper_bound.clone());
+        assert!(solver.minimize(&mut ls, oracle, 100, 100, None).is_err());
+
+        let mut solver = ProjectedNewton::new(1e-8, x_0, lower_bound, upper_bound)
+            .with_modification(HessianModification::CholeskyAddedIdentity { beta: 1e-3 });
+        let report = solver.minimize(&mut ls, oracle, 100, 100, None).unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x() - DVector::from(vec![1.0, 0.0])).amax() < 1e-6);
+    }
 }
@@ -12,11 +12,14 @@ pub struct SpectralProjectedNewton {
     grad_tol: Floating,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
     lambda: Floating,
     lambda_min: Floating,
     lambda_max: Floating,
+    modification: Option<HessianModification>,
 }
 
 impl SpectralProjectedNewton {
@@ -25,10 +28,15 @@ impl SpectralProjectedNewton {
         self.lambda_max = lambda_max;
         self
     }
+    // indefinite hessians are made positive definite (see HessianModification) instead of stopping the minimization with an error
+    pub fn with_modification(mut self, modification: HessianModification) -> Self {
+        self.modification = Some(modification);
+        self
+    }
     pub fn new(
         grad_tol: Floating,
         x0: DVector<Floating>,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         lower_bound: DVector<Floating>,
         upper_bound: DVector<Floating>,
     ) -> Self {
@@ -37,8 +45,8 @@ impl SpectralProjectedNewton {
         let lambda_max = 1e3;
 
         // we initialize lambda0 as equation 8 from [Birgin, Martínez, Raydan, 2014]
-        let eval0 = oracle(&x0);
-        let direction0 = &x0 - eval0.g();
+        let g0 = oracle.gradient(&x0);
+        let direction0 = &x0 - g0;
         let direction0 = direction0.box_projection(&lower_bound, &upper_bound);
         let direction0 = direction0 - &x0;
         let lambda = (1. / direction0.infinity_norm())
@@ -49,11 +57,13 @@ impl SpectralProjectedNewton {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
             lower_bound,
             upper_bound,
             lambda,
             lambda_min,
             lambda_max,
+            modification: None,
         }
     }
 }
@@ -79,11 +89,9 @@ impl ComputeDirection for SpectralProjectedNewton {
         eval: &FuncEvalMultivariate,
     ) -> Result<DVector<Floating>, SolverError> {
         // let direction = &self.x - self.lambda * eval.g();
-        let hessian = eval
-            .hessian()
-            .clone()
-            .expect("Hessian not available in the oracle");
-        let direction = &self.x - self.lambda * &hessian.cholesky().unwrap().solve(eval.g());
+        let hessian = eval.hessian().clone().ok_or(SolverError::MissingHessian)?;
+        let newton_step = positive_definite_newton_step(hessian, eval.g(), self.modification)?;
+        let direction = &self.x + self.lambda * newton_step;
         let direction = direction.box_projection(&self.lower_bound, &self.upper_bound);
         let direction = direction - &self.x;
         Ok(direction)
@@ -91,9 +99,13 @@ impl ComputeDirection for SpectralProjectedNewton {
 }
 
 impl LineSearchSolver for SpectralProjectedNewton {
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         let projected_gradient = self.projected_gradient(eval);
-        projected_gradient.infinity_norm() < self.grad_tol
+        (projected_gradient.infinity_norm() < self.grad_tol)
+            .then_some(TerminationReason::GradientTolerance)
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
     }
     fn xk(&self) -> &DVector<Floating> {
         &self.x
@@ -107,22 +119,24 @@ impl LineSearchSolver for SpectralProjectedNewton {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn requires_hessian(&self) -> bool {
+        true
+    }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let xk = self.xk(); //immutable borrow
 
@@ -132,7 +146,10 @@ impl LineSearchSolver for SpectralProjectedNewton {
 
         // we compute the correction terms:
         let s_k = &next_iterate - xk;
-        let y_k = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y_k = eval_next_iterate.g() - eval_x_k.g();
 
         *self.xk_mut() = next_iterate;
 
@@ -141,11 +158,11 @@ impl LineSearchSolver for SpectralProjectedNewton {
         if skyk <= 0. {
             debug!(target: "spectral_projected_newton", "skyk = {} <= 0. Resetting lambda to lambda_max", skyk);
             self.lambda = self.lambda_max;
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
         let sksk = s_k.dot(&s_k);
         self.lambda = (sksk / skyk).min(self.lambda_max).max(self.lambda_min);
-        Ok(())
+        Ok(Some(eval_next_iterate))
     }
 }
 
@@ -0,0 +1,174 @@
+use super::*;
+
+// Oracle of the objective function, with separate entry points for the image, the gradient and the second order information. Solvers and line searches ask only for what they need (e.g. backtracking requires just the image at the trial points), which matters when the gradient is much more expensive than the function value.
+// Any closure FnMut(&DVector) -> FuncEvalMultivariate is an Objective (see the blanket implementation below), so existing oracles work unchanged.
+pub trait Objective {
+    fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate;
+
+    fn value(&mut self, x: &DVector<Floating>) -> Floating {
+        *self.value_and_gradient(x).f()
+    }
+
+    fn gradient(&mut self, x: &DVector<Floating>) -> DVector<Floating> {
+        let (_, g, _) = self.value_and_gradient(x).into_parts();
+        g
+    }
+
+    // Coordinate methods need only a few partial derivatives at a time. None if the objective doesn't provide them separately (extracting them from the full gradient would cost a gradient evaluation each)
+    fn partial_derivative(&mut self, _x: &DVector<Floating>, _i: usize) -> Option<Floating> {
+        None
+    }
+
+    // None if the objective doesn't provide second order information
+    fn hessian(&mut self, _x: &DVector<Floating>) -> Option<DMatrix<Floating>> {
+        None
+    }
+
+    // Hessian-free methods only need the action of the hessian on a vector. By default it's computed from the dense hessian (if available)
+    fn hessian_vector_product(
+        &mut self,
+        x: &DVector<Floating>,
+        v: &DVector<Floating>,
+    ) -> Option<DVector<Floating>> {
+        self.hessian(x).map(|hessian| hessian * v)
+    }
+}
+
+// Blanket implementation for the closures returning the function evaluation (with the hessian attached via FuncEval::with_hessian, if available)
+impl<F> Objective for F
+where
+    F: FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+{
+    fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate {
+        self(x)
+    }
+
+    fn hessian(&mut self, x: &DVector<Floating>) -> Option<DMatrix<Floating>> {
+        let (_, _, hessian) = self(x).into_parts();
+        hessian
+    }
+}
+
+// Wrapper counting the calls to each entry point of the objective. Used by the solvers to fill the evaluation counts of the report
+pub struct CountedObjective<O> {
+    objective: O,
+    counts: EvaluationCounts,
+}
+
+impl<O: Objective> CountedObjective<O> {
+    pub fn new(objective: O) -> Self {
+        CountedObjective {
+            objective,
+            counts: EvaluationCounts::default(),
+        }
+    }
+    pub fn counts(&self) -> EvaluationCounts {
+        self.counts
+    }
+}
+
+impl<O: Objective> Objective for CountedObjective<O> {
+    fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let eval = self.objective.value_and_gradient(x);
+        self.counts.record(&eval);
+        eval
+    }
+
+    fn value(&mut self, x: &DVector<Floating>) -> Floating {
+        self.counts.record_value();
+        self.objective.value(x)
+    }
+
+    fn gradient(&mut self, x: &DVector<Floating>) -> DVector<Floating> {
+        self.counts.record_gradient();
+        self.objective.gradient(x)
+    }
+
+    fn partial_derivative(&mut self, x: &DVector<Floating>, i: usize) -> Option<Floating> {
+        let partial_derivative = self.objective.partial_derivative(x, i);
+        if partial_derivative.is_some() {
+            self.counts.record_partial_derivative();
+        }
+        partial_derivative
+    }
+
+    fn hessian(&mut self, x: &DVector<Floating>) -> Option<DMatrix<Floating>> {
+        let hessian = self.objective.hessian(x);
+        if hessian.is_some() {
+            self.counts.record_hessian();
+        }
+        hessian
+    }
+
+    fn hessian_vector_product(
+        &mut self,
+        x: &DVector<Floating>,
+        v: &DVector<Floating>,
+    ) -> Option<DVector<Floating>> {
+        let hv = self.objective.hessian_vector_product(x, v);
+        if hv.is_some() {
+            self.counts.record_hessian_vector_product();
+        }
+        hv
+    }
+}
+
+#[cfg(test)]
+mod objective_test {
+    use super::*;
+
+    // quadratic exposing a value-only entry point, as an objective with an expensive gradient would do
+    struct ExpensiveQuadratic {
+        gamma: Floating,
+    }
+
+    impl Objective for ExpensiveQuadratic {
+        fn value(&mut self, x: &DVector<Floating>) -> Floating {
+            0.5 * (x[0].powi(2) + self.gamma * x[1].powi(2))
+        }
+        fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate {
+            let g = DVector::from(vec![x[0], self.gamma * x[1]]);
+            FuncEvalMultivariate::new(self.value(x), g)
+        }
+        fn hessian(&mut self, _x: &DVector<Floating>) -> Option<DMatrix<Floating>> {
+            Some(DMatrix::from_iterator(
+                2,
+                2,
+                vec![1.0, 0.0, 0.0, self.gamma],
+            ))
+        }
+    }
+
+    #[test]
+    pub fn backtracking_asks_for_value_only() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        let oracle = ExpensiveQuadratic { gamma: 90.0 };
+        let mut ls = BackTracking::new(1e-4, 0.5);
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+        let mut gd = GradientDescent::new(1e-6, x_0);
+
+        let report = gd.minimize(&mut ls, oracle, 10000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        // the gradient is computed only at the iterates, while the line search trials require only the image
+        assert!(report.evaluations().f() > report.evaluations().g());
+        assert_eq!(report.evaluations().g(), &(report.iterations() + 1));
+    }
+
+    #[test]
+    pub fn newton_with_hessian_entry_point() {
+        let oracle = ExpensiveQuadratic { gamma: 1222.0 };
+        let mut ls = MoreThuente::default();
+        let x_0 = DVector::from(vec![1.0, 1.0]);
+        let mut nt = Newton::new(1e-8, x_0);
+
+        let report = nt.minimize(&mut ls, oracle, 1000, 100, None).unwrap();
+
+        assert!(report.f().abs() < 1e-6);
+        assert_eq!(report.evaluations().hessian(), &(report.iterations() + 1));
+    }
+}
@@ -78,7 +78,7 @@ impl Plotter3d {
             // let input = DVector::from_vec(vec![*x, *y]);
             z[i] = *oracle(input).f();
         }
-        let (x, y) = points.iter().cloned().map(|v| (v[0], v[1])).unzip();
+        let (x, y) = points.iter().map(|v| (v[0], v[1])).unzip();
         let scatter = plotly::Scatter3D::new(x, y, z)
             .mode(Mode::Markers)
             .name(title)
@@ -0,0 +1,130 @@
+use super::*;
+
+// Accelerated proximal gradient method (FISTA, [Beck, A., & Teboulle, M. (2009). A fast iterative shrinkage-thresholding algorithm for linear inverse problems]) for composite objectives f(x) + g(x): the proximal gradient step is taken from the extrapolated point of the Nesterov momentum (see NesterovMomentum, which also handles the backtracking on the Lipschitz constant and the adaptive restart).
+// The convergence test is on the norm of the gradient mapping, while the report and the stopping criteria see the value of the composite objective f + g.
+#[derive(derive_getters::Getters)]
+pub struct AcceleratedProximalGradient<P: ProximalOperator> {
+    tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    prox: P,
+    momentum: NesterovMomentum,
+}
+
+impl<P: ProximalOperator> AcceleratedProximalGradient<P> {
+    pub fn new(tol: Floating, x0: DVector<Floating>, prox: P) -> Self {
+        Self {
+            tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            prox,
+            momentum: NesterovMomentum::default(),
+        }
+    }
+    pub fn with_momentum(mut self, momentum: NesterovMomentum) -> Self {
+        self.momentum = momentum;
+        self
+    }
+}
+
+impl<P: ProximalOperator> LineSearchFreeSolver for AcceleratedProximalGradient<P> {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (gradient_mapping_norm(&self.x, eval, &self.prox, *self.momentum.lipschitz()) < self.tol)
+            .then_some(TerminationReason::GradientTolerance)
+    }
+    fn nonsmooth_value(&self) -> Floating {
+        self.prox.value(&self.x)
+    }
+    fn setup(&mut self) {
+        self.momentum.reset();
+    }
+
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let (next_iterate, eval_next_iterate) =
+            self.momentum.step(&self.x, eval_x_k, oracle, &self.prox);
+        debug!(target: "accelerated_proximal_gradient", "ITERATE: {}, lipschitz estimate: {}", next_iterate, self.momentum.lipschitz());
+        self.x = next_iterate;
+        Ok(eval_next_iterate)
+    }
+}
+
+#[cfg(test)]
+mod accelerated_proximal_gradient_test {
+    use super::*;
+
+    #[test]
+    pub fn lasso_regression() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // sparse regression: 40 observations, 15 regressors and only 3 of them in the model
+        let (m, n) = (40, 15);
+        // pseudo random design matrix (linear congruential generator), entries in [-1, 1)
+        let mut state: u64 = 12345;
+        let a = DMatrix::from_fn(m, n, |_, _| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as Floating / (1u64 << 53) as Floating * 2.0 - 1.0
+        });
+        let mut beta = DVector::zeros(n);
+        beta[1] = 2.0;
+        beta[5] = -1.5;
+        beta[11] = 1.0;
+        let b = &a * &beta;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let r = &a * x - &b;
+            (0.5 * r.norm_squared(), a.tr_mul(&r)).into()
+        };
+        let lambda = 0.5;
+        let x_0 = DVector::zeros(n);
+
+        let mut fista = AcceleratedProximalGradient::new(1e-8, x_0.clone(), L1Norm::new(lambda));
+        let report_fista = fista.minimize(f_and_g, 100000, None).unwrap();
+        println!("FISTA: {:?}", report_fista);
+
+        let mut ista = ProximalGradient::new(1e-8, x_0, L1Norm::new(lambda));
+        let report_ista = ista.minimize(f_and_g, 100000, None).unwrap();
+        println!("ISTA: {:?}", report_ista);
+
+        assert!((report_fista.x() - report_ista.x()).amax() < 1e-6);
+        assert!(report_fista.iterations() < report_ista.iterations());
+
+        // the support of the regression is recovered
+        let x = report_fista.x();
+        for i in 0..n {
+            assert_eq!(x[i] != 0.0, beta[i] != 0.0, "component {}", i);
+        }
+        // optimality conditions: A^T (A x - b) + lambda * sign(x) = 0 on the support, |A^T (A x - b)| <= lambda outside
+        let g = f_and_g(x).g().clone();
+        for i in 0..n {
+            if x[i] != 0.0 {
+                assert!((g[i] + lambda * x[i].signum()).abs() < 1e-6);
+            } else {
+                assert!(g[i].abs() <= lambda + 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use super::*;
+
+pub mod accelerated_proximal_gradient;
+pub use accelerated_proximal_gradient::*;
+pub mod proximal_gradient;
+pub use proximal_gradient::*;
+
+// Proximal methods for composite objectives F(x) = f(x) + g(x), where f is smooth (provided by the oracle) and g is convex, possibly nonsmooth or extended valued (e.g. the indicator of a feasible set), but with a proximal operator that is cheap to evaluate
+//      prox_{t g}(v) = argmin_u g(u) + ||u - v||^2 / (2 t)
+// (chapter 10 in [Beck, A. (2017). First-order methods in optimization. SIAM]).
+// The prox of the indicator of a set is the projection onto it, so the projected gradient methods are a special case of the proximal ones.
+pub trait ProximalOperator {
+    // proximal operator of step * g at v
+    fn prox(&self, v: &DVector<Floating>, step: Floating) -> DVector<Floating>;
+    // value of g at x (infinity outside its domain)
+    fn value(&self, x: &DVector<Floating>) -> Floating;
+}
+
+// g = 0: the prox is the identity and the proximal gradient step is a gradient step
+#[derive(Debug, Clone, Default)]
+pub struct NoProx;
+
+impl ProximalOperator for NoProx {
+    fn prox(&self, v: &DVector<Floating>, _step: Floating) -> DVector<Floating> {
+        v.clone()
+    }
+    fn value(&self, _x: &DVector<Floating>) -> Floating {
+        0.0
+    }
+}
+
+// g(x) = lambda ||x||_1 (lasso): the prox is the soft-thresholding operator
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct L1Norm {
+    lambda: Floating,
+}
+
+impl L1Norm {
+    pub fn new(lambda: Floating) -> Self {
+        assert!(lambda >= 0.0, "lambda must be non negative");
+        L1Norm { lambda }
+    }
+    pub fn soft_thresholding(v: &DVector<Floating>, threshold: Floating) -> DVector<Floating> {
+        v.map(|v_i| v_i.signum() * (v_i.abs() - threshold).max(0.0))
+    }
+}
+
+impl ProximalOperator for L1Norm {
+    fn prox(&self, v: &DVector<Floating>, step: Floating) -> DVector<Floating> {
+        Self::soft_thresholding(v, step * self.lambda)
+    }
+    fn value(&self, x: &DVector<Floating>) -> Floating {
+        self.lambda * x.lp_norm(1)
+    }
+}
+
+// g(x) = l1 ||x||_1 + l2 / 2 ||x||^2: soft-thresholding followed by a shrinkage
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct ElasticNet {
+    l1: Floating,
+    l2: Floating,
+}
+
+impl ElasticNet {
+    pub fn new(l1: Floating, l2: Floating) -> Self {
+        assert!(l1 >= 0.0 && l2 >= 0.0, "penalties must be non negative");
+        ElasticNet { l1, l2 }
+    }
+}
+
+impl ProximalOperator for ElasticNet {
+    fn prox(&self, v: &DVector<Floating>, step: Floating) -> DVector<Floating> {
+        L1Norm::soft_thresholding(v, step * self.l1) / (1.0 + step * self.l2)
+    }
+    fn value(&self, x: &DVector<Floating>) -> Floating {
+        self.l1 * x.lp_norm(1) + 0.5 * self.l2 * x.norm_squared()
+    }
+}
+
+// g(x) = lambda * sum_G ||x_G||_2 over disjoint groups of indices (the components outside every group are not penalized): the prox shrinks each group towards zero as a block (block soft-thresholding), so that entire groups are set to zero
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct GroupLasso {
+    lambda: Floating,
+    groups: Vec<Vec<usize>>,
+}
+
+impl GroupLasso {
+    pub fn new(lambda: Floating, groups: Vec<Vec<usize>>) -> Self {
+        assert!(lambda >= 0.0, "lambda must be non negative");
+        let mut indices: Vec<usize> = groups.iter().flatten().copied().collect();
+        let n_indices = indices.len();
+        indices.sort_unstable();
+        indices.dedup();
+        assert!(indices.len() == n_indices, "groups must be disjoint");
+        GroupLasso { lambda, groups }
+    }
+    fn group_norm(x: &DVector<Floating>, group: &[usize]) -> Floating {
+        group.iter().map(|&i| x[i] * x[i]).sum::<Floating>().sqrt()
+    }
+}
+
+impl ProximalOperator for GroupLasso {
+    fn prox(&self, v: &DVector<Floating>, step: Floating) -> DVector<Floating> {
+        let mut u = v.clone();
+        for group in &self.groups {
+            let norm = Self::group_norm(v, group);
+            let shrinkage = if norm > 0.0 {
+                (1.0 - step * self.lambda / norm).max(0.0)
+            } else {
+                0.0
+            };
+            for &i in group {
+                u[i] *= shrinkage;
+            }
+        }
+        u
+    }
+    fn value(&self, x: &DVector<Floating>) -> Floating {
+        self.lambda
+            * self
+                .groups
+                .iter()
+                .map(|group| Self::group_norm(x, group))
+                .sum::<Floating>()
+    }
+}
+
+// indicator of the nonnegative orthant: the prox is max(v, 0)
+#[derive(Debug, Clone, Default)]
+pub struct NonNegative;
+
+impl ProximalOperator for NonNegative {
+    fn prox(&self, v: &DVector<Floating>, _step: Floating) -> DVector<Floating> {
+        v.map(|v_i| v_i.max(0.0))
+    }
+    fn value(&self, x: &DVector<Floating>) -> Floating {
+        if x.iter().all(|x_i| *x_i >= 0.0) {
+            0.0
+        } else {
+            Floating::INFINITY
+        }
+    }
+}
+
+// indicator of the box [lower_bound, upper_bound]: the prox is the projection onto the box
+#[derive(Debug, Clone)]
+pub struct BoxIndicator {
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+}
+
+impl BoxIndicator {
+    pub fn new(lower_bound: DVector<Floating>, upper_bound: DVector<Floating>) -> Self {
+        assert!(
+            lower_bound.len() == upper_bound.len(),
+            "bounds must have the same dimension"
+        );
+        BoxIndicator {
+            lower_bound,
+            upper_bound,
+        }
+    }
+}
+
+impl HasBounds for BoxIndicator {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
+    }
+}
+
+impl ProximalOperator for BoxIndicator {
+    fn prox(&self, v: &DVector<Floating>, _step: Floating) -> DVector<Floating> {
+        v.box_projection(&self.lower_bound, &self.upper_bound)
+    }
+    fn value(&self, x: &DVector<Floating>) -> Floating {
+        if x.box_projection(&self.lower_bound, &self.upper_bound) == *x {
+            0.0
+        } else {
+            Floating::INFINITY
+        }
+    }
+}
+
+// Proximal gradient step from y with backtracking on the estimate of the Lipschitz constant of the gradient of f: the estimate is increased until
+//      f(x+) <= f(y) + g(y)^T (x+ - y) + L/2 ||x+ - y||^2,      x+ = prox_{g / L}(y - g(y) / L)
+// (section 10.4.2 in Beck, 2017). Returns x+ and the evaluation of f at x+.
+pub fn proximal_gradient_step(
+    y: &DVector<Floating>,
+    eval_y: &FuncEvalMultivariate,
+    oracle: &mut impl Objective,
+    prox: &impl ProximalOperator,
+    lipschitz: &mut Floating,
+    backtracking_factor: Floating,
+    max_iter_backtracking: usize,
+) -> (DVector<Floating>, FuncEvalMultivariate) {
+    let mut x_next = prox.prox(&(y - eval_y.g() / *lipschitz), 1.0 / *lipschitz);
+    let mut eval_next = oracle.value_and_gradient(&x_next);
+    for _ in 0..max_iter_backtracking {
+        let d = &x_next - y;
+        let upper_bound = eval_y.f() + eval_y.g().dot(&d) + 0.5 * *lipschitz * d.norm_squared();
+        if eval_next.f().is_finite() && *eval_next.f() <= upper_bound {
+            break;
+        }
+        *lipschitz *= backtracking_factor;
+        trace!(target: "proximal", "Quadratic upper bound not satisfied: lipschitz estimate increased to {}", lipschitz);
+        x_next = prox.prox(&(y - eval_y.g() / *lipschitz), 1.0 / *lipschitz);
+        eval_next = oracle.value_and_gradient(&x_next);
+    }
+    (x_next, eval_next)
+}
+
+// Norm of the gradient mapping L (x - prox_{g / L}(x - g(x) / L)), which is zero if and only if x is a minimizer of the composite objective (it reduces to the gradient when g = 0)
+pub fn gradient_mapping_norm(
+    x: &DVector<Floating>,
+    eval: &FuncEvalMultivariate,
+    prox: &impl ProximalOperator,
+    lipschitz: Floating,
+) -> Floating {
+    let x_plus = prox.prox(&(x - eval.g() / lipschitz), 1.0 / lipschitz);
+    (lipschitz * (x - x_plus)).infinity_norm()
+}
+
+#[cfg(test)]
+mod proximal_operators_test {
+    use super::*;
+
+    #[test]
+    pub fn proximal_operators() {
+        let v = DVector::from(vec![3.0, -0.5, 1.5, -2.0]);
+
+        let u = L1Norm::new(1.0).prox(&v, 1.0);
+        assert_eq!(u, DVector::from(vec![2.0, 0.0, 0.5, -1.0]));
+
+        let u = ElasticNet::new(1.0, 1.0).prox(&v, 1.0);
+        assert_eq!(u, DVector::from(vec![1.0, 0.0, 0.25, -0.5]));
+
+        // the norm of the first group is 5, the second group is zeroed
+        let v_groups = DVector::from(vec![3.0, 4.0, 0.1, -0.1]);
+        let u = GroupLasso::new(1.0, vec![vec![0, 1], vec![2, 3]]).prox(&v_groups, 1.0);
+        assert!((u - DVector::from(vec![2.4, 3.2, 0.0, 0.0])).amax() < 1e-12);
+
+        let u = NonNegative.prox(&v, 1.0);
+        assert_eq!(u, DVector::from(vec![3.0, 0.0, 1.5, 0.0]));
+        assert_eq!(NonNegative.value(&v), Floating::INFINITY);
+
+        let box_indicator = BoxIndicator::new(
+            DVector::from_element(4, -1.0),
+            DVector::from_element(4, 1.0),
+        );
+        let u = box_indicator.prox(&v, 1.0);
+        assert_eq!(u, DVector::from(vec![1.0, -0.5, 1.0, -1.0]));
+        assert_eq!(box_indicator.value(&u), 0.0);
+    }
+
+    #[test]
+    pub fn prox_optimality() {
+        // u = prox_{t g}(v) minimizes g(u) + ||u - v||^2 / (2 t): no perturbation of u decreases the objective
+        let v = DVector::from(vec![0.7, -1.3, 0.05, 2.0]);
+        let step = 0.5;
+        let operators: Vec<Box<dyn ProximalOperator>> = vec![
+            Box::new(L1Norm::new(0.8)),
+            Box::new(ElasticNet::new(0.8, 0.5)),
+            Box::new(GroupLasso::new(0.8, vec![vec![0, 2], vec![1, 3]])),
+        ];
+        for operator in operators {
+            let u = operator.prox(&v, step);
+            let objective =
+                |u: &DVector<Floating>| operator.value(u) + (u - &v).norm_squared() / (2.0 * step);
+            for i in 0..v.len() {
+                for delta in [-1e-4, 1e-4] {
+                    let mut perturbed = u.clone();
+                    perturbed[i] += delta;
+                    assert!(objective(&perturbed) >= objective(&u) - 1e-12);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+
+// Proximal gradient method (ISTA when g is the L1 norm) for composite objectives f(x) + g(x): at every iteration x_{k+1} = prox_{g / L}(x_k - g(x_k) / L), with backtracking on the estimate L of the Lipschitz constant of the gradient of f (see proximal_gradient_step).
+// The convergence test is on the norm of the gradient mapping, while the report and the stopping criteria see the value of the composite objective f + g.
+#[derive(derive_getters::Getters)]
+pub struct ProximalGradient<P: ProximalOperator> {
+    tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    prox: P,
+    lipschitz: Floating, // current estimate of the Lipschitz constant of the gradient of f
+    backtracking_factor: Floating,
+    max_iter_backtracking: usize, // maximum number of increases of the estimate at each iteration
+}
+
+impl<P: ProximalOperator> ProximalGradient<P> {
+    pub fn new(tol: Floating, x0: DVector<Floating>, prox: P) -> Self {
+        Self {
+            tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            prox,
+            lipschitz: 1.0,
+            backtracking_factor: 2.0,
+            max_iter_backtracking: 100,
+        }
+    }
+    pub fn with_lipschitz(mut self, lipschitz: Floating) -> Self {
+        assert!(lipschitz > 0.0, "lipschitz constant must be positive");
+        self.lipschitz = lipschitz;
+        self
+    }
+    pub fn with_backtracking_factor(mut self, backtracking_factor: Floating) -> Self {
+        assert!(
+            backtracking_factor > 1.0,
+            "backtracking factor must be greater than 1"
+        );
+        self.backtracking_factor = backtracking_factor;
+        self
+    }
+    pub fn with_max_iter_backtracking(mut self, max_iter_backtracking: usize) -> Self {
+        self.max_iter_backtracking = max_iter_backtracking;
+        self
+    }
+}
+
+impl<P: ProximalOperator> LineSearchFreeSolver for ProximalGradient<P> {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (gradient_mapping_norm(&self.x, eval, &self.prox, self.lipschitz) < self.tol)
+            .then_some(TerminationReason::GradientTolerance)
+    }
+    fn nonsmooth_value(&self) -> Floating {
+        self.prox.value(&self.x)
+    }
+
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let (next_iterate, eval_next_iterate) = proximal_gradient_step(
+            &self.x,
+            eval_x_k,
+            oracle,
+            &self.prox,
+            &mut self.lipschitz,
+            self.backtracking_factor,
+            self.max_iter_backtracking,
+        );
+        debug!(target: "proximal_gradient", "ITERATE: {}, lipschitz estimate: {}", next_iterate, self.lipschitz);
+        self.x = next_iterate;
+        Ok(eval_next_iterate)
+    }
+}
+
+#[cfg(test)]
+mod proximal_gradient_test {
+    use super::*;
+
+    #[test]
+    pub fn proximal_gradient_nonnegative_least_squares() {
+        // min 0.5 ||A x - b||^2 subject to x >= 0, with solution (0, 1): the first constraint is active
+        let a = DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let b = DVector::from(vec![-1.0, 1.0, 0.0]);
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let r = &a * x - &b;
+            (0.5 * r.norm_squared(), a.tr_mul(&r)).into()
+        };
+        let x_0 = DVector::from(vec![1.0, 1.0]);
+        let mut solver = ProximalGradient::new(1e-8, x_0, NonNegative);
+        let report = solver.minimize(f_and_g, 10000, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!(report.x()[0].abs() < 1e-12);
+        assert!((report.x()[1] - 0.5).abs() < 1e-8);
+    }
+
+    #[test]
+    pub fn proximal_gradient_composite_value() {
+        // min 0.5 ||x - c||^2 + ||x||_1, with solution soft_thresholding(c, 1) = (1, 0, -0.5)
+        let c = DVector::from(vec![2.0, 0.5, -1.5]);
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            (0.5 * (x - &c).norm_squared(), x - &c).into()
+        };
+        let solution = DVector::from(vec![1.0, 0.0, -0.5]);
+        let optimal_value = 0.5 * (&solution - &c).norm_squared() + solution.lp_norm(1);
+
+        let mut solver = ProximalGradient::new(1e-10, DVector::zeros(3), L1Norm::new(1.0));
+        let report = solver.minimize(f_and_g, 10000, None).unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x() - &solution).amax() < 1e-10);
+        // the reported value includes the nonsmooth term
+        assert!((report.f() - optimal_value).abs() < 1e-10);
+
+        // the stopping criterion sees f + g, which never goes below its minimum (f alone drops below the target after the first step)
+        let mut solver = ProximalGradient::new(1e-10, DVector::zeros(3), L1Norm::new(1.0))
+            .with_stopping_criterion(TargetObjective::new(optimal_value - 1e-6));
+        assert!(matches!(
+            solver.minimize(f_and_g, 1000, None),
+            Err(SolverError::MaxIterReached)
+        ));
+    }
+}
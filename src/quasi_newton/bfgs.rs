@@ -5,10 +5,14 @@ pub struct BFGS {
     approx_inv_hessian: DMatrix<Floating>,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     tol: Floating,
     s_norm: Option<Floating>,
     y_norm: Option<Floating>,
-    identity: DMatrix<Floating>,
+    #[getter(skip)]
+    hy: DVector<Floating>, // work vector for the update of the inverse hessian
+    safeguards: UpdateSafeguards,
 }
 
 impl BFGS {
@@ -26,17 +30,22 @@ impl BFGS {
     }
     pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
         let n = x0.len();
-        let identity = DMatrix::identity(n, n);
         BFGS {
-            approx_inv_hessian: identity.clone(),
+            approx_inv_hessian: DMatrix::identity(n, n),
             x: x0,
             k: 0,
+            stopping_criterion: None,
             tol,
             s_norm: None,
             y_norm: None,
-            identity,
+            hy: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
         }
     }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
 }
 
 impl ComputeDirection for BFGS {
@@ -61,17 +70,20 @@ impl LineSearchSolver for BFGS {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // either the gradient is small or the difference between the iterates is small
         // eval.g().norm() < self.tol || self.next_iterate_too_close()
         if self.next_iterate_too_close() {
-            warn!(target: "bfgs","Minimization completed: next iterate too close");
-            true
+            Some(TerminationReason::NextIterateTooClose)
         } else if self.gradient_next_iterate_too_close() {
-            warn!(target: "bfgs","Minimization completed: gradient next iterate too close");
-            true
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if eval.g().norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
         } else {
-            eval.g().norm() < self.tol
+            None
         }
     }
 
@@ -79,23 +91,22 @@ impl LineSearchSolver for BFGS {
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let next_iterate = self.xk() + step * direction;
 
         let s = &next_iterate - &self.x;
         self.s_norm = Some(s.norm());
-        let y = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
         self.y_norm = Some(y.norm());
 
         //updating iterate here, and then we will update the inverse hessian (if corrections are not too small)
@@ -104,26 +115,24 @@ impl LineSearchSolver for BFGS {
         // We update the inverse hessian and the corrections in this hook which is triggered just after the calculation of the next iterate
 
         if self.next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
         if self.gradient_next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
+        let Some(s) = self
+            .safeguards
+            .prepare_update(&mut self.approx_inv_hessian, &s, &y)
+        else {
+            return Ok(Some(eval_next_iterate));
+        };
+
         // Equation 2.21 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]
-        let ys = &y.dot(&s);
-        let rho = 1.0 / ys;
-        let w_a = &s * &y.transpose();
-        // let w_b = &y * &s.transpose();
-        let w_b = w_a.transpose();
-        let innovation = &s * &s.transpose();
-        let left_term = self.identity() - (w_a * rho);
-        let right_term = self.identity() - (w_b * rho);
-        self.approx_inv_hessian =
-            (left_term * &self.approx_inv_hessian * right_term) + innovation * rho;
-
-        Ok(())
+        bfgs_inverse_update(&mut self.approx_inv_hessian, &s, &y, &mut self.hy);
+
+        Ok(Some(eval_next_iterate))
     }
 }
 
@@ -237,4 +246,89 @@ mod test_bfgs {
 
         assert!((eval.f() - 0.0).abs() < 1e-6);
     }
+
+    #[test]
+    pub fn bfgs_reuses_line_search_evaluation() {
+        let mut oracle_calls = 0;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            oracle_calls += 1;
+            let f = 0.5 * ((x[0] + 1.).powi(2) + (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., x[1] - 1.]);
+            (f, g).into()
+        };
+
+        let mut ls = MoreThuente::default();
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+        let mut gd = BFGS::new(1e-12, x_0);
+
+        let report = gd.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        // the unit step along the steepest descent direction lands on the minimizer: the oracle is queried only at x_0 and at the trial point accepted by the line search, which is reused both for the update and for the convergence test
+        assert_eq!(report.iterations(), &1);
+        assert_eq!(oracle_calls, 2);
+        assert_eq!(report.evaluations().f(), &2);
+        assert!(report.f().abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn bfgs_safeguards_armijo_rosenbrock() {
+        // with an armijo-only line search the curvature condition is not guaranteed
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        for curvature in [
+            CurvatureSafeguard::Skip { threshold: 1e-8 },
+            CurvatureSafeguard::PowellDamping { delta: 0.2 },
+        ] {
+            let mut ls = BackTracking::new(1e-4, 0.5);
+            let x_0 = DVector::from(vec![-1.2, 1.0]);
+            let safeguards = UpdateSafeguards::default()
+                .with_curvature_safeguard(curvature)
+                .with_initial_scaling(true);
+            let mut solver = BFGS::new(1e-8, x_0).with_safeguards(safeguards);
+
+            let mut positive_definite = true;
+            let mut callback = |solver: &BFGS| {
+                positive_definite &= solver.approx_inv_hessian().clone().cholesky().is_some();
+            };
+            let report = solver
+                .minimize(&mut ls, f_and_g, 10000, 100, Some(&mut callback))
+                .unwrap();
+
+            println!("{:?}: {:?}", curvature, report);
+            assert!(positive_definite, "{:?}", curvature);
+            assert!((report.x()[0] - 1.).abs() < 1e-4, "{:?}", curvature);
+            assert!((report.x()[1] - 1.).abs() < 1e-4, "{:?}", curvature);
+        }
+    }
+
+    #[test]
+    pub fn bfgs_large_scale() {
+        // the inverse hessian is updated in place with O(n^2) operations per iteration
+        let n = 500;
+        let diag = DVector::from_fn(n, |i, _| 1. + 99. * (i as Floating) / (n as Floating));
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+
+        let mut ls = MoreThuente::default();
+        let x_0 = DVector::from_element(n, 1.0);
+        let mut solver = BFGS::new(1e-6, x_0);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Iterations: {:?}", report.iterations());
+        assert!(report.f().abs() < 1e-8);
+        assert!(report.x().amax() < 1e-4);
+        // the approximation stays symmetric
+        let h = solver.approx_inv_hessian();
+        assert_eq!(h, &h.transpose());
+    }
 }
@@ -5,10 +5,14 @@ pub struct BFGSB {
     approx_inv_hessian: DMatrix<Floating>,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     tol: Floating,
     s_norm: Option<Floating>,
     y_norm: Option<Floating>,
-    identity: DMatrix<Floating>,
+    #[getter(skip)]
+    hy: DVector<Floating>, // work vector for the update of the inverse hessian
+    safeguards: UpdateSafeguards,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
 }
@@ -48,19 +52,24 @@ impl BFGSB {
     ) -> Self {
         let n = x0.len();
         let x0 = x0.box_projection(&lower_bound, &upper_bound);
-        let identity = DMatrix::identity(n, n);
         BFGSB {
-            approx_inv_hessian: identity.clone(),
+            approx_inv_hessian: DMatrix::identity(n, n),
             x: x0,
             k: 0,
+            stopping_criterion: None,
             tol,
             s_norm: None,
             y_norm: None,
-            identity,
+            hy: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
             lower_bound,
             upper_bound,
         }
     }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
 }
 
 impl ComputeDirection for BFGSB {
@@ -89,42 +98,47 @@ impl LineSearchSolver for BFGSB {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // either the gradient is small or the difference between the iterates is small
         // eval.g().norm() < self.tol || self.next_iterate_too_close()
         if self.next_iterate_too_close() {
-            warn!(target: "BFGSB","Minimization completed: next iterate too close");
-            true
+            Some(TerminationReason::NextIterateTooClose)
         } else if self.gradient_next_iterate_too_close() {
-            warn!(target: "BFGSB","Minimization completed: gradient next iterate too close");
-            true
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if eval.g().norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
         } else {
-            eval.g().norm() < self.tol
+            None
         }
     }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let next_iterate = self.xk() + step * direction;
         debug!(target: "BFGSB", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, next_iterate);
 
         let s = &next_iterate - &self.x;
         self.s_norm = Some(s.norm());
-        let y = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
         self.y_norm = Some(y.norm());
 
         //updating iterate here, and then we will update the inverse hessian (if corrections are not too small)
@@ -133,24 +147,23 @@ impl LineSearchSolver for BFGSB {
         // We update the inverse hessian and the corrections in this hook which is triggered just after the calculation of the next iterate
 
         if self.next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
         if self.gradient_next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
-        let ys = &y.dot(&s);
-        let rho = 1.0 / ys;
-        let w_a = &s * &y.transpose();
-        let w_b = &y * &s.transpose();
-        let innovation = &s * &s.transpose();
-        let left_term = self.identity() - (w_a * rho);
-        let right_term = self.identity() - (w_b * rho);
-        self.approx_inv_hessian =
-            (left_term * &self.approx_inv_hessian * right_term) + innovation * rho;
-
-        Ok(())
+        let Some(s) = self
+            .safeguards
+            .prepare_update(&mut self.approx_inv_hessian, &s, &y)
+        else {
+            return Ok(Some(eval_next_iterate));
+        };
+
+        bfgs_inverse_update(&mut self.approx_inv_hessian, &s, &y, &mut self.hy);
+
+        Ok(Some(eval_next_iterate))
     }
 }
 
@@ -0,0 +1,305 @@
+use super::*;
+
+// Parameter phi of the Broyden class (see broyden_class_inverse_update)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BroydenPhi {
+    // fixed value of phi: 0 is BFGS, 1 is DFP and the values in [0, 1] form the convex class (the approximation stays positive definite as long as s^T y > 0)
+    Fixed(Floating),
+    // phi = y^T H y / (y^T H y - s^T y), which changes at every iteration and gives the SR1 update (the approximation can be indefinite)
+    SymmetricRankOne,
+}
+
+// Quasi-newton solver with the update of the approximation of the inverse hessian chosen in the Broyden class (section 6.3 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// BFGS, DFP and SR1 are obtained with the presets BroydenClass::bfgs, BroydenClass::dfp and BroydenClass::sr1, while any other phi can be set with with_phi.
+// The solver is unconstrained by default; with with_bounds the direction is projected onto the box (as in BFGSB, DFPB and SR1B) and the convergence test uses the projected gradient.
+// The curvature condition is handled by the safeguards (skip or damping) for fixed phi, and by the SR1 skip rule for the SR1 value.
+#[derive(derive_getters::Getters)]
+pub struct BroydenClass {
+    approx_inv_hessian: DMatrix<Floating>,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    tol: Floating,
+    s_norm: Option<Floating>,
+    y_norm: Option<Floating>,
+    #[getter(skip)]
+    hy: DVector<Floating>, // work vector for the update of the inverse hessian
+    safeguards: UpdateSafeguards,
+    phi: BroydenPhi,
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+}
+
+impl HasBounds for BroydenClass {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+}
+
+impl BroydenClass {
+    pub fn next_iterate_too_close(&self) -> bool {
+        match self.s_norm() {
+            Some(s) => s < &self.tol,
+            None => false,
+        }
+    }
+    pub fn gradient_next_iterate_too_close(&self) -> bool {
+        match self.y_norm() {
+            Some(y) => y < &self.tol,
+            None => false,
+        }
+    }
+    pub fn new(tol: Floating, x0: DVector<Floating>, phi: BroydenPhi) -> Self {
+        let n = x0.len();
+        BroydenClass {
+            approx_inv_hessian: DMatrix::identity(n, n),
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            tol,
+            s_norm: None,
+            y_norm: None,
+            hy: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
+            phi,
+            lower_bound: DVector::from_element(n, Floating::NEG_INFINITY),
+            upper_bound: DVector::from_element(n, Floating::INFINITY),
+        }
+    }
+    pub fn bfgs(tol: Floating, x0: DVector<Floating>) -> Self {
+        Self::new(tol, x0, BroydenPhi::Fixed(0.0))
+    }
+    pub fn dfp(tol: Floating, x0: DVector<Floating>) -> Self {
+        Self::new(tol, x0, BroydenPhi::Fixed(1.0))
+    }
+    pub fn sr1(tol: Floating, x0: DVector<Floating>) -> Self {
+        Self::new(tol, x0, BroydenPhi::SymmetricRankOne)
+    }
+    pub fn with_phi(mut self, phi: BroydenPhi) -> Self {
+        if let BroydenPhi::Fixed(phi) = phi {
+            assert!(phi.is_finite(), "phi must be finite");
+        }
+        self.phi = phi;
+        self
+    }
+    pub fn with_bounds(
+        mut self,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        assert!(
+            lower_bound.len() == self.x.len() && upper_bound.len() == self.x.len(),
+            "bounds must have the same dimension of the iterate"
+        );
+        self.x = self.x.box_projection(&lower_bound, &upper_bound);
+        self.lower_bound = lower_bound;
+        self.upper_bound = upper_bound;
+        self
+    }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+}
+
+impl ComputeDirection for BroydenClass {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        // with infinite bounds the projection doesn't change the quasi-newton direction
+        let direction = &self.x - &self.approx_inv_hessian * eval.g();
+        let direction = direction.box_projection(&self.lower_bound, &self.upper_bound) - &self.x;
+        if eval.g().dot(&direction) < 0.0 || direction.norm() == 0.0 {
+            return Ok(direction);
+        }
+        // the SR1 approximation (or a member of the class with phi outside the convex range) can be indefinite: the approximation is reset to the identity, so that the direction is the (projected) steepest descent one
+        debug!(target: "broyden_class", "Not a descent direction: resetting the approximation of the inverse hessian");
+        let n = self.x.len();
+        self.approx_inv_hessian = DMatrix::identity(n, n);
+        let direction = (&self.x - eval.g()).box_projection(&self.lower_bound, &self.upper_bound);
+        Ok(direction - &self.x)
+    }
+}
+
+impl LineSearchSolver for BroydenClass {
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        if self.next_iterate_too_close() {
+            Some(TerminationReason::NextIterateTooClose)
+        } else if self.gradient_next_iterate_too_close() {
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if self.projected_gradient(eval).norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
+        } else {
+            None
+        }
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        let next_iterate = self.xk() + step * direction;
+
+        let s = &next_iterate - &self.x;
+        self.s_norm = Some(s.norm());
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
+        self.y_norm = Some(y.norm());
+
+        *self.xk_mut() = next_iterate;
+
+        if self.next_iterate_too_close() || self.gradient_next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        match self.phi {
+            BroydenPhi::Fixed(phi) => {
+                let Some(s) = self
+                    .safeguards
+                    .prepare_update(&mut self.approx_inv_hessian, &s, &y)
+                else {
+                    return Ok(Some(eval_next_iterate));
+                };
+                broyden_class_inverse_update(
+                    &mut self.approx_inv_hessian,
+                    &s,
+                    &y,
+                    phi,
+                    &mut self.hy,
+                );
+            }
+            BroydenPhi::SymmetricRankOne => {
+                // the SR1 member of the class is computed directly, since phi is not defined when y^T H y = s^T y
+                if !self.safeguards.prepare_sr1_update(
+                    &mut self.approx_inv_hessian,
+                    &s,
+                    &y,
+                    &mut self.hy,
+                ) {
+                    return Ok(Some(eval_next_iterate));
+                }
+                sr1_inverse_update(&mut self.approx_inv_hessian, &y, &self.hy);
+            }
+        }
+
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod broyden_class_test {
+    use super::*;
+
+    fn rosenbrock(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+        let g = DVector::from(vec![
+            -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+            200. * (x[1] - x[0].powi(2)),
+        ]);
+        (f, g).into()
+    }
+
+    #[test]
+    pub fn broyden_class_rosenbrock() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // armijo backtracking: the curvature condition is not guaranteed, and the updates rely on the safeguards
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        for phi in [
+            BroydenPhi::Fixed(0.0),
+            BroydenPhi::Fixed(0.5),
+            BroydenPhi::Fixed(1.0),
+            BroydenPhi::SymmetricRankOne,
+        ] {
+            let mut ls = BackTracking::new(1e-4, 0.5);
+            let mut solver = BroydenClass::new(1e-8, x_0.clone(), phi);
+            let report = solver
+                .minimize(&mut ls, rosenbrock, 10000, 100, None)
+                .unwrap();
+            println!("{:?}: {} iterations", phi, report.iterations());
+            assert!((report.x()[0] - 1.).abs() < 1e-4, "{:?}", phi);
+            assert!((report.x()[1] - 1.).abs() < 1e-4, "{:?}", phi);
+        }
+    }
+
+    #[test]
+    pub fn broyden_class_presets_match_solvers() {
+        // the BFGS preset follows the same iterates of the BFGS solver
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut bfgs = BFGS::new(1e-8, x_0.clone());
+        let report_bfgs = bfgs
+            .minimize(&mut MoreThuente::default(), rosenbrock, 1000, 100, None)
+            .unwrap();
+        let mut preset = BroydenClass::bfgs(1e-8, x_0);
+        let report_preset = preset
+            .minimize(&mut MoreThuente::default(), rosenbrock, 1000, 100, None)
+            .unwrap();
+        assert_eq!(report_bfgs.iterations(), report_preset.iterations());
+        assert!((report_bfgs.x() - report_preset.x()).amax() < 1e-12);
+    }
+
+    #[test]
+    pub fn broyden_class_bounded() {
+        // minimum of the quadratic at (-1, 1), outside the box [0, 2] x [0, 2]: the solution is (0, 1)
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] + 1.).powi(2) + 10. * (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., 10. * (x[1] - 1.)]);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_element(2, 0.0);
+        let upper_bound = DVector::from_element(2, 2.0);
+
+        for phi in [BroydenPhi::Fixed(0.5), BroydenPhi::SymmetricRankOne] {
+            let mut ls = BackTrackingB::new(1e-4, 0.5, lower_bound.clone(), upper_bound.clone());
+            let mut solver = BroydenClass::new(1e-8, DVector::from(vec![1.5, 0.5]), phi)
+                .with_bounds(lower_bound.clone(), upper_bound.clone());
+            let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+            println!("{:?}: {:?}", phi, report);
+            assert!(report.x()[0].abs() < 1e-8, "{:?}", phi);
+            assert!((report.x()[1] - 1.).abs() < 1e-6, "{:?}", phi);
+        }
+    }
+}
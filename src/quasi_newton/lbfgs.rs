@@ -0,0 +1,378 @@
+use super::*;
+use std::collections::VecDeque;
+
+// Limited memory BFGS. Instead of the dense approximation of the inverse hessian, only the last m correction pairs (s_i, y_i) are stored and the product H_k * g_k is computed with the two-loop recursion (Algorithm 7.4 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]). Memory and time per iteration are O(mn), which makes the solver suitable for large problems.
+// The initial approximation H_k^0 = gamma_k * I is scaled with the Shanno-Phua factor gamma_k = s_{k-1}^T y_{k-1} / y_{k-1}^T y_{k-1} (equation 7.20 in Nocedal & Wright).
+#[derive(derive_getters::Getters)]
+pub struct LBFGS {
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    tol: Floating,
+    history: LimitedMemoryHistory,
+    safeguards: UpdateSafeguards,
+    s_norm: Option<Floating>,
+    y_norm: Option<Floating>,
+}
+
+impl LBFGS {
+    pub fn next_iterate_too_close(&self) -> bool {
+        match self.s_norm() {
+            Some(s) => s < &self.tol,
+            None => false,
+        }
+    }
+    pub fn gradient_next_iterate_too_close(&self) -> bool {
+        match self.y_norm() {
+            Some(y) => y < &self.tol,
+            None => false,
+        }
+    }
+    pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
+        LBFGS {
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            tol,
+            history: LimitedMemoryHistory::new(5),
+            safeguards: UpdateSafeguards::default(),
+            s_norm: None,
+            y_norm: None,
+        }
+    }
+    // The range 3 <= m <= 20 is recommended
+    pub fn with_m(mut self, m: usize) -> Self {
+        self.history = LimitedMemoryHistory::new(m);
+        self
+    }
+    // only the curvature safeguard is used (see UpdateSafeguards::limited_memory_pair)
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+
+    // Two-loop recursion: returns H_k * q where H_k is the limited memory approximation of the inverse hessian
+    pub fn inverse_hessian_product(&self, q: &DVector<Floating>) -> DVector<Floating> {
+        self.history.inverse_hessian_product(q)
+    }
+}
+
+// Last m correction pairs (s_i, y_i) of the limited memory solvers, stored from the oldest to the newest together with rho_i = 1 / y_i^T s_i, and the Shanno-Phua scaling gamma of the initial approximation of the inverse hessian (gamma = 1 when the history is empty)
+#[derive(derive_getters::Getters, Debug, Clone)]
+pub struct LimitedMemoryHistory {
+    m: usize, // history size (number of stored correction pairs)
+    s_history: VecDeque<DVector<Floating>>,
+    y_history: VecDeque<DVector<Floating>>,
+    rho_history: VecDeque<Floating>,
+    gamma: Floating,
+}
+
+impl LimitedMemoryHistory {
+    pub fn new(m: usize) -> Self {
+        assert!(m > 0, "history size must be positive");
+        LimitedMemoryHistory {
+            m,
+            s_history: VecDeque::with_capacity(m),
+            y_history: VecDeque::with_capacity(m),
+            rho_history: VecDeque::with_capacity(m),
+            gamma: 1.0,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.s_history.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.s_history.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.s_history.clear();
+        self.y_history.clear();
+        self.rho_history.clear();
+        self.gamma = 1.0;
+    }
+    // stores the pair dropping the oldest one when the history is full. The pair must have positive curvature y^T s > 0, otherwise the approximation wouldn't be positive definite
+    pub fn push(&mut self, s: DVector<Floating>, y: DVector<Floating>) {
+        let ys = y.dot(&s);
+        assert!(ys > 0.0, "the correction pair must have positive curvature");
+        if self.len() == self.m {
+            self.s_history.pop_front();
+            self.y_history.pop_front();
+            self.rho_history.pop_front();
+        }
+        self.gamma = ys / y.norm_squared();
+        self.rho_history.push_back(1.0 / ys);
+        self.s_history.push_back(s);
+        self.y_history.push_back(y);
+    }
+    pub fn inverse_hessian_product(&self, q: &DVector<Floating>) -> DVector<Floating> {
+        two_loop_recursion(
+            q,
+            &self.s_history,
+            &self.y_history,
+            &self.rho_history,
+            self.gamma,
+        )
+    }
+}
+
+// Two-loop recursion (Algorithm 7.4 in Nocedal & Wright) on the correction pairs stored from the oldest to the newest, with initial approximation gamma * I. Shared by the limited memory solvers that don't need the compact representation of the matrix.
+pub fn two_loop_recursion(
+    q: &DVector<Floating>,
+    s_history: &VecDeque<DVector<Floating>>,
+    y_history: &VecDeque<DVector<Floating>>,
+    rho_history: &VecDeque<Floating>,
+    gamma: Floating,
+) -> DVector<Floating> {
+    let mut q = q.clone();
+    let mut alpha = vec![0.0; s_history.len()];
+    // from the newest pair to the oldest
+    for i in (0..s_history.len()).rev() {
+        alpha[i] = rho_history[i] * s_history[i].dot(&q);
+        q.axpy(-alpha[i], &y_history[i], 1.0);
+    }
+    let mut r = q * gamma;
+    // from the oldest pair to the newest
+    for (((s, y), rho), alpha) in s_history
+        .iter()
+        .zip(y_history.iter())
+        .zip(rho_history.iter())
+        .zip(alpha)
+    {
+        let beta = rho * y.dot(&r);
+        r.axpy(alpha - beta, s, 1.0);
+    }
+    r
+}
+
+impl ComputeDirection for LBFGS {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        Ok(-self.inverse_hessian_product(eval.g()))
+    }
+}
+
+impl LineSearchSolver for LBFGS {
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        // either the gradient is small or the difference between the iterates is small
+        if self.next_iterate_too_close() {
+            Some(TerminationReason::NextIterateTooClose)
+        } else if self.gradient_next_iterate_too_close() {
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if eval.g().norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
+        } else {
+            None
+        }
+    }
+
+    fn setup(&mut self) {
+        // the history belongs to a single run: restarting the minimization from the current iterate starts from scratch
+        self.history.clear();
+        self.s_norm = None;
+        self.y_norm = None;
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        let next_iterate = self.xk() + step * direction;
+
+        let s = &next_iterate - &self.x;
+        self.s_norm = Some(s.norm());
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
+        self.y_norm = Some(y.norm());
+
+        *self.xk_mut() = next_iterate;
+
+        if self.next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        if self.gradient_next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        // the pair is stored only if the curvature condition holds (possibly after damping), otherwise the approximation wouldn't be positive definite
+        if let Some(s) = self.safeguards.limited_memory_pair(&s, &y, &self.history) {
+            self.history.push(s, y);
+        }
+
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod test_lbfgs {
+    use super::*;
+
+    #[test]
+    pub fn lbfgs_morethuente() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        let gamma = 90.;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] + 1.).powi(2) + gamma * (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., gamma * (x[1] - 1.)]);
+            (f, g).into()
+        };
+
+        let mut ls = MoreThuente::default();
+
+        let tol = 1e-12;
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+        let mut solver = LBFGS::new(tol, x_0).with_m(3);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+
+        assert!(report.f().abs() < 1e-6);
+        assert!((report.x()[0] + 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn lbfgs_backtracking_rosenbrock() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+
+        let mut ls = BackTracking::new(1e-4, 0.5);
+
+        let tol = 1e-8;
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = LBFGS::new(tol, x_0);
+
+        let report = solver.minimize(&mut ls, f_and_g, 10000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+
+        assert!((report.x()[0] - 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn lbfgs_safeguards_armijo_rosenbrock() {
+        // with an armijo-only line search the curvature condition is not guaranteed
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        for curvature in [
+            CurvatureSafeguard::Skip { threshold: 1e-8 },
+            CurvatureSafeguard::PowellDamping { delta: 0.2 },
+        ] {
+            let mut ls = BackTracking::new(1e-4, 0.5);
+            let x_0 = DVector::from(vec![-1.2, 1.0]);
+            let safeguards = UpdateSafeguards::default().with_curvature_safeguard(curvature);
+            let mut solver = LBFGS::new(1e-8, x_0).with_safeguards(safeguards);
+
+            let mut positive_curvature = true;
+            let mut callback = |solver: &LBFGS| {
+                positive_curvature &= solver.history().rho_history().iter().all(|rho| *rho > 0.0);
+            };
+            let report = solver
+                .minimize(&mut ls, f_and_g, 10000, 100, Some(&mut callback))
+                .unwrap();
+
+            println!("{:?}: {:?}", curvature, report);
+            assert!(positive_curvature, "{:?}", curvature);
+            assert!((report.x()[0] - 1.).abs() < 1e-4, "{:?}", curvature);
+            assert!((report.x()[1] - 1.).abs() < 1e-4, "{:?}", curvature);
+        }
+    }
+
+    #[test]
+    pub fn limited_memory_history() {
+        let mut history = LimitedMemoryHistory::new(2);
+        let pairs = [
+            (vec![1.0, 0.0, 0.0], vec![2.0, 0.0, 0.0]),
+            (vec![0.0, 1.0, 0.0], vec![0.5, 3.0, 0.0]),
+            (vec![0.0, 0.0, 1.0], vec![0.0, 0.5, 4.0]),
+        ];
+        for (s, y) in pairs.iter() {
+            history.push(DVector::from(s.clone()), DVector::from(y.clone()));
+        }
+        // the oldest pair is dropped
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.s_history()[0], DVector::from(pairs[1].0.clone()));
+        // secant equation on the newest pair, and Shanno-Phua scaling
+        let (s, y) = (
+            DVector::from(pairs[2].0.clone()),
+            DVector::from(pairs[2].1.clone()),
+        );
+        assert!((history.inverse_hessian_product(&y) - &s).amax() < 1e-12);
+        assert_eq!(*history.gamma(), y.dot(&s) / y.norm_squared());
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.inverse_hessian_product(&y), y);
+    }
+
+    #[test]
+    pub fn lbfgs_large_scale() {
+        // ill-conditioned separable quadratic with 10^4 variables: a dense approximation of the inverse hessian would require 800MB
+        let n = 10_000;
+        let diag = DVector::from_fn(n, |i, _| 1. + 99. * (i as Floating) / (n as Floating));
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+
+        let mut ls = MoreThuente::default();
+
+        let tol = 1e-6;
+        let x_0 = DVector::from_element(n, 1.0);
+        let mut solver = LBFGS::new(tol, x_0).with_m(10);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Iterations: {:?}", report.iterations());
+
+        assert!(report.f().abs() < 1e-8);
+        assert!(report.x().amax() < 1e-4);
+    }
+}
@@ -0,0 +1,541 @@
+use super::*;
+
+// Native implementation of L-BFGS-B from [Byrd, R. H., Lu, P., Nocedal, J., & Zhu, C. (1995). A limited memory algorithm for bound constrained optimization.] with the subspace minimization fix of [Morales, J. L., & Nocedal, J. (2011). Remark on "Algorithm 778: L-BFGS-B"].
+// The hessian is approximated with the compact limited memory representation B_k = theta * I - W_k * M_k * W_k^T, where W_k = [Y_k, theta * S_k] stores the last m correction pairs. At each iteration:
+// - the generalized Cauchy point x^c is computed, i.e. the first local minimizer of the quadratic model along the projected steepest descent path
+// - the quadratic model is minimized over the variables that are free at x^c, and the result x̄ is brought back into the box
+// - the line search is performed along x̄ - x_k, which is feasible for every step in [0, 1] (use MoreThuenteB to keep the trial points within the bounds)
+#[derive(derive_getters::Getters)]
+pub struct LBFGSB {
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    tol: Floating,
+    history: LimitedMemoryHistory, // theta = 1 / gamma is the scaling of the initial approximation of the hessian
+    safeguards: UpdateSafeguards,
+    s_norm: Option<Floating>,
+    y_norm: Option<Floating>,
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+}
+
+impl HasBounds for LBFGSB {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+}
+
+impl LBFGSB {
+    pub fn next_iterate_too_close(&self) -> bool {
+        match self.s_norm() {
+            Some(s) => s < &self.tol,
+            None => false,
+        }
+    }
+    pub fn gradient_next_iterate_too_close(&self) -> bool {
+        match self.y_norm() {
+            Some(y) => y < &self.tol,
+            None => false,
+        }
+    }
+    pub fn new(
+        tol: Floating,
+        x0: DVector<Floating>,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        let x0 = x0.box_projection(&lower_bound, &upper_bound);
+        LBFGSB {
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            tol,
+            history: LimitedMemoryHistory::new(5),
+            safeguards: UpdateSafeguards::default(),
+            s_norm: None,
+            y_norm: None,
+            lower_bound,
+            upper_bound,
+        }
+    }
+    // The range 3 <= m <= 20 is recommended
+    pub fn with_m(mut self, m: usize) -> Self {
+        self.history = LimitedMemoryHistory::new(m);
+        self
+    }
+    // only the curvature safeguard is used (see UpdateSafeguards::limited_memory_pair)
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+
+    // scaling of the initial approximation of the hessian
+    pub fn theta(&self) -> Floating {
+        1.0 / self.history.gamma()
+    }
+
+    // Matrices W = [Y, theta * S] (n x 2m) and M = [[-D, L^T], [L, theta * S^T S]]^-1 (2m x 2m) of the compact representation, where D is the diagonal and L the strictly lower triangular part of S^T Y (equation 3.4 in Byrd et al.)
+    fn compact_representation(&self) -> Option<(DMatrix<Floating>, DMatrix<Floating>)> {
+        let n = self.x.len();
+        let h = self.history.len();
+        let theta = self.theta();
+        if h == 0 {
+            return Some((DMatrix::zeros(n, 0), DMatrix::zeros(0, 0)));
+        }
+        let s = DMatrix::from_fn(n, h, |i, j| self.history.s_history()[j][i]);
+        let y = DMatrix::from_fn(n, h, |i, j| self.history.y_history()[j][i]);
+        let sy = s.tr_mul(&y);
+        let ss = s.tr_mul(&s);
+
+        let mut w = DMatrix::zeros(n, 2 * h);
+        w.columns_mut(0, h).copy_from(&y);
+        w.columns_mut(h, h).copy_from(&(&s * theta));
+
+        let mut m_inv = DMatrix::zeros(2 * h, 2 * h);
+        for i in 0..h {
+            m_inv[(i, i)] = -sy[(i, i)];
+            for j in 0..i {
+                // L_ij = s_i^T y_j for i > j
+                m_inv[(h + i, j)] = sy[(i, j)];
+                m_inv[(j, h + i)] = sy[(i, j)];
+            }
+        }
+        m_inv.view_mut((h, h), (h, h)).copy_from(&(ss * theta));
+
+        let m = m_inv.try_inverse()?;
+        Some((w, m))
+    }
+
+    // Generalized Cauchy point (Algorithm CP in Byrd et al.): the piecewise quadratic model along the projected steepest descent path is minimized segment by segment, between consecutive breakpoints. Returns the Cauchy point together with c = W^T (x^c - x_k)
+    fn cauchy_point(
+        &self,
+        g: &DVector<Floating>,
+        w: &DMatrix<Floating>,
+        m: &DMatrix<Floating>,
+    ) -> (DVector<Floating>, DVector<Floating>) {
+        let x = &self.x;
+        let n = x.len();
+        let theta = self.theta();
+
+        let mut t_break = vec![Floating::INFINITY; n];
+        let mut d = DVector::zeros(n);
+        for i in 0..n {
+            if g[i] < 0.0 {
+                t_break[i] = (x[i] - self.upper_bound[i]) / g[i];
+            } else if g[i] > 0.0 {
+                t_break[i] = (x[i] - self.lower_bound[i]) / g[i];
+            }
+            if t_break[i] > 0.0 {
+                d[i] = -g[i];
+            }
+        }
+        let mut breakpoints = (0..n)
+            .filter(|&i| t_break[i] > 0.0 && t_break[i].is_finite())
+            .collect::<Vec<_>>();
+        breakpoints.sort_by(|&i, &j| t_break[i].total_cmp(&t_break[j]));
+
+        let mut x_c = x.clone();
+        let mut p = w.tr_mul(&d);
+        let mut c = DVector::zeros(p.len());
+        // first and second directional derivatives of the model along the current segment
+        let mut f1 = -d.norm_squared();
+        if f1 >= 0.0 {
+            // every variable is at a bound that blocks the steepest descent direction
+            return (x_c, c);
+        }
+        let mut f2 = -theta * f1 - p.dot(&(m * &p));
+        let f2_org = f2;
+        let mut dt_min = -f1 / f2;
+        let mut t_old = 0.0;
+
+        for b in breakpoints {
+            let dt = t_break[b] - t_old;
+            if dt_min < dt {
+                break;
+            }
+            // the variable b hits its bound: it is fixed from now on
+            x_c[b] = if d[b] > 0.0 {
+                self.upper_bound[b]
+            } else {
+                self.lower_bound[b]
+            };
+            let z_b = x_c[b] - x[b];
+            c += dt * &p;
+            let g_b = g[b];
+            let w_b = w.row(b).transpose();
+            let m_w_b = m * &w_b;
+            f1 += dt * f2 + g_b * g_b + theta * g_b * z_b - g_b * m_w_b.dot(&c);
+            f2 -= theta * g_b * g_b + 2.0 * g_b * m_w_b.dot(&p) + g_b * g_b * w_b.dot(&m_w_b);
+            f2 = f2.max(Floating::EPSILON * f2_org);
+            p.axpy(g_b, &w_b, 1.0);
+            d[b] = 0.0;
+            t_old = t_break[b];
+            if f1 >= 0.0 {
+                // the model increases right after the breakpoint
+                dt_min = 0.0;
+                break;
+            }
+            dt_min = -f1 / f2;
+        }
+
+        let dt_min = dt_min.max(0.0);
+        // the variables fixed at the breakpoints have d_i = 0
+        x_c.axpy(t_old + dt_min, &d, 1.0);
+        c.axpy(dt_min, &p, 1.0);
+        (x_c, c)
+    }
+
+    // Direct primal method for the subspace minimization (section 5.1 in Byrd et al.) with the projection of Morales & Nocedal: the quadratic model is minimized over the variables free at the Cauchy point and the minimizer is projected onto the box. If the projected point is not a descent direction, the unconstrained step is truncated to remain within the bounds.
+    fn subspace_minimization(
+        &self,
+        g: &DVector<Floating>,
+        w: &DMatrix<Floating>,
+        m: &DMatrix<Floating>,
+        x_c: &DVector<Floating>,
+        c: &DVector<Floating>,
+    ) -> DVector<Floating> {
+        let theta = self.theta();
+        let free = (0..x_c.len())
+            .filter(|&i| x_c[i] > self.lower_bound[i] && x_c[i] < self.upper_bound[i])
+            .collect::<Vec<_>>();
+        if free.is_empty() {
+            return x_c.clone();
+        }
+
+        // reduced gradient of the model at the cauchy point
+        let r = g + (x_c - &self.x) * theta - w * (m * c);
+        let r_free = DVector::from_fn(free.len(), |i, _| r[free[i]]);
+
+        let mut d_u = -&r_free / theta;
+        if w.ncols() > 0 {
+            let w_free = DMatrix::from_fn(free.len(), w.ncols(), |i, j| w[(free[i], j)]);
+            let v = m * w_free.tr_mul(&r_free);
+            let n_mat =
+                DMatrix::identity(w.ncols(), w.ncols()) - m * w_free.tr_mul(&w_free) / theta;
+            let Some(v) = n_mat.lu().solve(&v) else {
+                debug!(target: "LBFGSB", "Singular system in subspace minimization: using the cauchy point");
+                return x_c.clone();
+            };
+            d_u -= w_free * v / theta.powi(2);
+        }
+
+        let mut x_bar = x_c.clone();
+        for (i, &j) in free.iter().enumerate() {
+            x_bar[j] = (x_c[j] + d_u[i]).clamp(self.lower_bound[j], self.upper_bound[j]);
+        }
+        if (&x_bar - &self.x).dot(g) < 0.0 {
+            return x_bar;
+        }
+
+        // largest step in [0, 1] along d_u that remains feasible
+        let alpha = free
+            .iter()
+            .enumerate()
+            .map(|(i, &j)| {
+                if d_u[i] > 0.0 {
+                    (self.upper_bound[j] - x_c[j]) / d_u[i]
+                } else if d_u[i] < 0.0 {
+                    (self.lower_bound[j] - x_c[j]) / d_u[i]
+                } else {
+                    Floating::INFINITY
+                }
+            })
+            .fold(1.0, Floating::min);
+        let mut x_bar = x_c.clone();
+        for (i, &j) in free.iter().enumerate() {
+            x_bar[j] = x_c[j] + alpha * d_u[i];
+        }
+        x_bar
+    }
+}
+
+impl ComputeDirection for LBFGSB {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let g = eval.g();
+        let (w, m) = match self.compact_representation() {
+            Some(wm) => wm,
+            None => {
+                debug!(target: "LBFGSB", "Singular middle matrix: resetting the limited memory");
+                self.history.clear();
+                (DMatrix::zeros(g.len(), 0), DMatrix::zeros(0, 0))
+            }
+        };
+        let (x_c, c) = self.cauchy_point(g, &w, &m);
+        let x_bar = self.subspace_minimization(g, &w, &m, &x_c, &c);
+        let direction = x_bar - &self.x;
+
+        if direction.dot(g) >= 0.0 && !self.history.is_empty() {
+            // the limited memory approximation doesn't provide a descent direction: we restart from the projected steepest descent
+            debug!(target: "LBFGSB", "Not a descent direction: resetting the limited memory");
+            self.history.clear();
+            return self.compute_direction(eval);
+        }
+        Ok(direction)
+    }
+}
+
+impl LineSearchSolver for LBFGSB {
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        // either the projected gradient is small or the difference between the iterates is small
+        if self.next_iterate_too_close() {
+            Some(TerminationReason::NextIterateTooClose)
+        } else if self.gradient_next_iterate_too_close() {
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if self.projected_gradient(eval).infinity_norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
+        } else {
+            None
+        }
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
+
+    fn setup(&mut self) {
+        // the history belongs to a single run: restarting the minimization from the current iterate starts from scratch
+        self.history.clear();
+        self.s_norm = None;
+        self.y_norm = None;
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        // the projection removes the rounding errors that could bring the active variables slightly off their bounds
+        let trial = self.xk() + step * direction;
+        let next_iterate = trial.box_projection(&self.lower_bound, &self.upper_bound);
+        debug!(target: "LBFGSB", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, next_iterate);
+
+        let s = &next_iterate - &self.x;
+        self.s_norm = Some(s.norm());
+        // we reuse the evaluation of the line search at the accepted point, if available and not moved by the projection
+        let eval_next_iterate = match eval_next_iterate {
+            Some(eval) if next_iterate == trial => eval,
+            _ => oracle.value_and_gradient(&next_iterate),
+        };
+        let y = eval_next_iterate.g() - eval_x_k.g();
+        self.y_norm = Some(y.norm());
+
+        *self.xk_mut() = next_iterate;
+
+        if self.next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        if self.gradient_next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        // the pair is stored only if the curvature condition holds (possibly after damping), otherwise the approximation wouldn't be positive definite
+        if let Some(s) = self.safeguards.limited_memory_pair(&s, &y, &self.history) {
+            self.history.push(s, y);
+        }
+
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod lbfgsb_native_test {
+    use super::*;
+
+    // extended rosenbrock function of the driver1 example shipped with the fortran L-BFGS-B
+    fn driver1(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let n = x.len();
+        let mut f = 0.25 * (x[0] - 1.0).powi(2);
+        for i in 1..n {
+            f += (x[i] - x[i - 1].powi(2)).powi(2);
+        }
+        f *= 4.0;
+        let mut g = DVector::zeros(n);
+        let mut t1 = x[1] - x[0].powi(2);
+        g[0] = 2.0 * (x[0] - 1.0) - 16.0 * x[0] * t1;
+        for i in 1..n - 1 {
+            let t2 = t1;
+            t1 = x[i + 1] - x[i].powi(2);
+            g[i] = 8.0 * t2 - 16.0 * x[i] * t1;
+        }
+        g[n - 1] = 8.0 * t1;
+        (f, g).into()
+    }
+
+    fn driver1_bounds(n: usize) -> (DVector<Floating>, DVector<Floating>) {
+        // odd variables (1-based) in [1, 100], even ones in [-100, 100]
+        let lower_bound = DVector::from_fn(n, |i, _| if i % 2 == 0 { 1.0 } else { -100.0 });
+        let upper_bound = DVector::from_element(n, 100.0);
+        (lower_bound, upper_bound)
+    }
+
+    #[test]
+    pub fn lbfgsb_native_active_bounds() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        // the unconstrained minimizer (-1, 1) violates the lower bound of the first variable
+        let gamma = 90.;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] + 1.).powi(2) + gamma * (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., gamma * (x[1] - 1.)]);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_vec(vec![0., -Floating::INFINITY]);
+        let upper_bound = DVector::from_vec(vec![Floating::INFINITY, Floating::INFINITY]);
+
+        let mut ls = MoreThuenteB::new(2)
+            .with_lower_bound(lower_bound.clone())
+            .with_upper_bound(upper_bound.clone());
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+        let mut solver = LBFGSB::new(1e-10, x_0, lower_bound, upper_bound);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+
+        assert_eq!(report.x()[0], 0.0);
+        assert!((report.x()[1] - 1.).abs() < 1e-8);
+        assert!(report.projected_gradient_norm().unwrap() < 1e-8);
+    }
+
+    #[test]
+    pub fn lbfgsb_native_driver1() {
+        let n = 25;
+        let (lower_bound, upper_bound) = driver1_bounds(n);
+        let mut ls = MoreThuenteB::new(n)
+            .with_lower_bound(lower_bound.clone())
+            .with_upper_bound(upper_bound.clone());
+        let x_0 = DVector::from_element(n, 3.0);
+        let mut solver = LBFGSB::new(1e-8, x_0, lower_bound, upper_bound).with_m(5);
+
+        let report = solver.minimize(&mut ls, driver1, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+
+        // the minimizer is (1, ..., 1), but the valley is so flat that the last variables are poorly determined
+        assert!(report.f() < &1e-10);
+        assert!(report.projected_gradient_norm().unwrap() < 1e-6);
+    }
+
+    #[cfg(feature = "lbfgsb")]
+    #[test]
+    pub fn lbfgsb_native_matches_ffi() {
+        // number of variables at one of their bounds
+        let active_set = |x: &DVector<Floating>, l: &DVector<Floating>, u: &DVector<Floating>| {
+            (0..x.len())
+                .filter(|&i| x[i] == l[i] || x[i] == u[i])
+                .collect::<Vec<_>>()
+        };
+        // the iteration counts of the two implementations agree up to a factor of 2 (the line searches differ)
+        let comparable_iterations = |a: usize, b: usize| a <= 2 * b + 10 && b <= 2 * a + 10;
+
+        // strictly convex quadratic whose unconstrained minimizer violates several bounds: the solution is unique, so the two implementations must agree on all of it
+        let n = 25;
+        let a = DMatrix::from_fn(n, n, |i, j| match i.abs_diff(j) {
+            0 => 4.0,
+            1 => -1.0,
+            _ => 0.0,
+        });
+        let b = DVector::from_fn(n, |i, _| 10.0 * (i as Floating).sin());
+        let quadratic = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let ax = &a * x;
+            (0.5 * x.dot(&ax) - b.dot(x), ax - &b).into()
+        };
+        let lower_bound = DVector::from_element(n, -1.0);
+        let upper_bound = DVector::from_element(n, 1.0);
+        let x_0 = DVector::zeros(n);
+
+        let mut ffi_solver = Lbfgsb::new(n)
+            .with_bounds(lower_bound.clone(), upper_bound.clone())
+            .with_m(5)
+            .with_pgtol(1e-10)
+            .with_factr(1e1);
+        let ffi_report = ffi_solver.minimize(quadratic, &x_0, None).unwrap();
+        let mut ls = MoreThuenteB::new(n)
+            .with_lower_bound(lower_bound.clone())
+            .with_upper_bound(upper_bound.clone());
+        let mut solver =
+            LBFGSB::new(1e-10, x_0, lower_bound.clone(), upper_bound.clone()).with_m(5);
+        let report = solver
+            .minimize(&mut ls, quadratic, 1000, 100, None)
+            .unwrap();
+
+        let active = active_set(report.x(), &lower_bound, &upper_bound);
+        assert!(!active.is_empty());
+        assert_eq!(
+            active,
+            active_set(ffi_report.x(), &lower_bound, &upper_bound)
+        );
+        assert!((report.x() - ffi_report.x()).amax() < 1e-6);
+        assert!(report.projected_gradient_norm().unwrap() < 1e-6);
+        assert!(ffi_report.projected_gradient_norm() < &1e-6);
+        assert!(comparable_iterations(
+            *report.iterations(),
+            *ffi_report.iterations()
+        ));
+
+        // driver1 of the fortran distribution: the bottom of the valley is so flat that only the first variables are well determined
+        let (lower_bound, upper_bound) = driver1_bounds(n);
+        let mut ffi_solver = Lbfgsb::new(n)
+            .with_bounds(lower_bound.clone(), upper_bound.clone())
+            .with_m(5)
+            .with_pgtol(1e-8)
+            .with_factr(1e1);
+        let ffi_report = ffi_solver
+            .minimize(driver1, &DVector::from_element(n, 3.0), None)
+            .unwrap();
+        let mut ls = MoreThuenteB::new(n)
+            .with_lower_bound(lower_bound.clone())
+            .with_upper_bound(upper_bound.clone());
+        let x_0 = DVector::from_element(n, 3.0);
+        let mut solver = LBFGSB::new(1e-8, x_0, lower_bound, upper_bound).with_m(5);
+        let report = solver.minimize(&mut ls, driver1, 1000, 100, None).unwrap();
+
+        assert!(report.f() < &1e-10);
+        assert!(ffi_report.f() < &1e-8);
+        assert!(report.projected_gradient_norm().unwrap() < 1e-6);
+        assert!(ffi_report.projected_gradient_norm() < &1e-6);
+        assert!((report.x().rows(0, 3) - ffi_report.x().rows(0, 3)).amax() < 1e-3);
+        assert!(comparable_iterations(
+            *report.iterations(),
+            *ffi_report.iterations()
+        ));
+    }
+}
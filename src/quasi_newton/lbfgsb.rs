@@ -1,11 +1,12 @@
 use super::*;
 
+// Safe wrapper of the reference Fortran implementation of L-BFGS-B (version 3.0) from [Zhu, C., Byrd, R. H., Lu, P., & Nocedal, J. (1997). Algorithm 778: L-BFGS-B]. The reverse communication of setulb is driven here: the routine asks for the function evaluation (task = FG), notifies a new iterate (task = NEW_X) or terminates (task = CONVERGENCE, ABNORMAL_TERMINATION, ERROR, WARNING).
+// For a pure Rust (and wasm compatible) version see LBFGSB in lbfgs_b.rs
 pub struct Lbfgsb {
-    n: i32,
-    m: i32,
-    l: Vec<Floating>,
-    u: Vec<Floating>,
-    nbd: Vec<i32>,
+    n: usize,
+    m: usize,
+    l: DVector<Floating>,
+    u: DVector<Floating>,
     factr: Floating,
     pgtol: Floating,
     wa: Vec<Floating>,
@@ -16,85 +17,33 @@ pub struct Lbfgsb {
     lsave: Vec<i32>,
     isave: Vec<i32>,
     dsave: Vec<Floating>,
-    max_iter: u32,
+    max_iter: usize,
 }
 
-impl Lbfgsb {
-    pub fn minimize(
-        &mut self,
-        mut oracle: impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
-        x0: &mut DVector<Floating>,
-    ) -> Result<(), SolverError> {
-        let eval = oracle(x0);
-        let mut f = *eval.f();
-        let mut g = eval.g().clone_owned();
-        stringfy(&mut self.task);
-        let k = 1.0e-10;
-        // start of the loop
-        loop {
-            // callign the fortran routine
-            unsafe {
-                ffi::setulb_(
-                    &self.n,
-                    &self.m,
-                    x0.as_mut_ptr(),
-                    self.l.as_ptr(),
-                    self.u.as_ptr(),
-                    self.nbd.as_ptr(),
-                    &f,
-                    g.as_ptr(),
-                    &self.factr,
-                    &self.pgtol,
-                    self.wa.as_mut_ptr(),
-                    self.iwa.as_mut_ptr(),
-                    self.task.as_mut_ptr(),
-                    &self.iprint,
-                    self.csave.as_mut_ptr(),
-                    self.lsave.as_mut_ptr(),
-                    self.isave.as_mut_ptr(),
-                    self.dsave.as_mut_ptr(),
-                )
-            }
+// Summary of the run, also handed to the callback after every iteration
+#[derive(derive_getters::Getters, Debug, Clone)]
+pub struct LbfgsbReport {
+    x: DVector<Floating>,
+    f: Floating,
+    projected_gradient_norm: Floating, // infinity norm
+    iterations: usize,
+    evaluations: usize,
+    message: String, // task message of the fortran routine
+}
 
-            // converting to rust string
-            let tsk = unsafe { CStr::from_ptr(self.task.as_ptr()).to_string_lossy() };
-            // println!("{}", tsk);
-            if &tsk[0..2] == "FG" {
-                let eval = oracle(x0);
-                f = *eval.f();
-                g.copy_from_slice(eval.g().as_slice());
-            }
-            if &tsk[0..5] == "NEW_X" && self.max_iter == 0 && self.dsave[11] <= k * f
-            //?
-            {
-                // println!("THE PROJECTED GRADIENT IS SUFFICIENTLY SMALL");
-                return Ok(());
-            }
-            if self.max_iter > 0 && self.isave[29] >= self.max_iter as i32 {
-                return Err(SolverError::MaxIterReached);
-            }
-            if &tsk[0..4] == "CONV" {
-                return Ok(());
-            }
-            if &tsk[0..5] == "ERROR" {
-                return Err(SolverError::ErrorInputParams);
-            }
-            if &tsk[0..8] == "ABNORMAL" {
-                return Err(SolverError::AbnormalTermination);
-            }
-        }
-    }
-    // constructor requres three mendatory parameter which is the initial solution, function and the gradient function
+impl Lbfgsb {
+    // constructor requires the number of variables. By default the problem is unconstrained, m = 5, and the tolerances are the ones suggested in the driver of the reference implementation (factr = 1e7, pgtol = 1e-5).
+    // Note that the previous constructor set factr = 0 and pgtol = 0 (iterate until no further progress is possible): use with_factr(0.0).with_pgtol(0.0) to get the old behaviour
     pub fn new(n: usize) -> Self {
-        // creating lbfgs struct
+        let m = 5;
         Lbfgsb {
-            m: 5,
-            l: vec![0.0; n],
-            u: vec![0.0; n],
-            nbd: vec![0; n],
-            factr: 0.0,
-            pgtol: 0.0,
-            wa: vec![0.0; 2 * 17 * n + 5 * n + 11 * 17 * 17 + 8 * 17],
+            n,
+            m,
+            l: DVector::from_element(n, -Floating::INFINITY),
+            u: DVector::from_element(n, Floating::INFINITY),
+            factr: 1e7,
+            pgtol: 1e-5,
+            wa: vec![0.0; Self::wa_len(n, m)],
             iwa: vec![0; 3 * n],
             task: vec![0; 60],
             iprint: -1,
@@ -103,54 +52,219 @@ impl Lbfgsb {
             isave: vec![0; 44],
             dsave: vec![0.0; 29],
             max_iter: 0,
-            n: n as i32,
         }
     }
 
-    // this function will start the optimization algorithm
+    // size of the double precision workspace required by setulb
+    fn wa_len(n: usize, m: usize) -> usize {
+        2 * m * n + 5 * n + 11 * m * m + 8 * m
+    }
+
+    // infinite bounds are mapped to unbounded variables
+    pub fn with_bounds(
+        self,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        self.with_lower_bound(lower_bound)
+            .with_upper_bound(upper_bound)
+    }
+    pub fn with_lower_bound(mut self, lower_bound: DVector<Floating>) -> Self {
+        assert_eq!(lower_bound.len(), self.n, "lower bound has wrong dimension");
+        self.l = lower_bound;
+        self
+    }
+    pub fn with_upper_bound(mut self, upper_bound: DVector<Floating>) -> Self {
+        assert_eq!(upper_bound.len(), self.n, "upper bound has wrong dimension");
+        self.u = upper_bound;
+        self
+    }
+    // maximum number of variable metric corrections. The range 3 <= m <= 20 is recommended
+    pub fn with_m(mut self, m: usize) -> Self {
+        assert!(m > 0, "m must be positive");
+        self.m = m;
+        self.wa = vec![0.0; Self::wa_len(self.n, m)];
+        self
+    }
+    // termination tolerance on the relative reduction of f:
+    // 1.0e12 for low accuracy
+    // 1.0e7  for moderate accuracy
+    // 1.0e1  for extremely high accuracy
+    pub fn with_factr(mut self, factr: Floating) -> Self {
+        self.factr = factr;
+        self
+    }
+    // termination tolerance on the infinity norm of the projected gradient
+    pub fn with_pgtol(mut self, pgtol: Floating) -> Self {
+        self.pgtol = pgtol;
+        self
+    }
+    // 0 means no limit on the number of iterations
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+    // verbosity level of the fortran routine (-1 means no output)
+    pub fn with_verbosity(mut self, iprint: i32) -> Self {
+        self.iprint = iprint;
+        self
+    }
 
-    // this function is used to set lower bounds to a variable
+    // per-variable setters: a bound can be overwritten at any time, and an infinite value removes it
     pub fn set_lower_bound(&mut self, index: usize, value: Floating) {
-        if self.nbd[index] == 1 || self.nbd[index] == 2 {
-            // println!("variable already has Lower Bound");
-        } else {
-            let temp = self.nbd[index] - 1;
-            self.nbd[index] = if temp < 0 { -temp } else { temp };
-            self.l[index] = value;
-        }
+        self.l[index] = value;
     }
-    // this function is used to set upper bounds to a variable
     pub fn set_upper_bound(&mut self, index: usize, value: Floating) {
-        if self.nbd[index] == 3 || self.nbd[index] == 2 {
-            // println!("variable already has Lower Bound");
-        } else {
-            self.nbd[index] = 3 - self.nbd[index];
-            self.u[index] = value;
-        }
+        self.u[index] = value;
     }
-    // set the verbosity level
+
+    // setters of the previous API, kept for compatibility
+    #[deprecated(note = "use `with_verbosity`")]
     pub fn set_verbosity(&mut self, l: i32) {
         self.iprint = l;
     }
-    // set termination tolerance
-    // 1.0e12 for low accuracy
-    // 1.0e7  for moderate accuracy
-    // 1.0e1  for extremely high accuracy
+    #[deprecated(note = "use `with_factr`")]
     pub fn set_factr(&mut self, t: Floating) {
         self.factr = t;
     }
-    // set tolerance of projection gradient
+    #[deprecated(note = "use `with_pgtol`")]
     pub fn set_pgtol(&mut self, t: Floating) {
         self.pgtol = t;
     }
-    // set max iteration
+    #[deprecated(note = "use `with_max_iter`")]
     pub fn max_iteration(&mut self, i: u32) {
-        self.max_iter = i;
+        self.max_iter = i as usize;
     }
-    // set maximum number of variable metric corrections
-    // The range  3 <= m <= 20 is recommended
+    #[deprecated(note = "use `with_m`")]
     pub fn set_m(&mut self, m: i32) {
-        self.m = m;
+        assert!(m > 0, "m must be positive");
+        self.m = m as usize;
+        self.wa = vec![0.0; Self::wa_len(self.n, self.m)];
+    }
+
+    // type of bound of each variable expected by setulb: 0 unbounded, 1 only lower, 2 both, 3 only upper
+    fn nbd(&self) -> Vec<i32> {
+        self.l
+            .iter()
+            .zip(self.u.iter())
+            .map(|(l, u)| match (l.is_finite(), u.is_finite()) {
+                (false, false) => 0,
+                (true, false) => 1,
+                (true, true) => 2,
+                (false, true) => 3,
+            })
+            .collect()
+    }
+
+    // infinity norm of P(x - g) - x, the same measure tested by setulb against pgtol
+    fn projected_gradient_norm(&self, x: &DVector<Floating>, g: &DVector<Floating>) -> Floating {
+        ((x - g).box_projection(&self.l, &self.u) - x).infinity_norm()
+    }
+
+    fn report(
+        &self,
+        x: &DVector<Floating>,
+        f: Floating,
+        g: &DVector<Floating>,
+        iterations: usize,
+        evaluations: usize,
+        message: String,
+    ) -> LbfgsbReport {
+        LbfgsbReport {
+            x: x.clone(),
+            f,
+            projected_gradient_norm: self.projected_gradient_norm(x, g),
+            iterations,
+            evaluations,
+            message,
+        }
+    }
+
+    // the fortran string is padded with blanks and not null terminated
+    fn task_message(&self) -> String {
+        let task = self.task.iter().map(|&c| c as u8).collect::<Vec<_>>();
+        String::from_utf8_lossy(&task)
+            .trim_end_matches([' ', '\0'])
+            .to_string()
+    }
+
+    pub fn minimize(
+        &mut self,
+        mut oracle: impl Objective,
+        x0: &DVector<Floating>,
+        mut callback: Option<&mut dyn FnMut(&LbfgsbReport)>,
+    ) -> Result<LbfgsbReport, SolverError> {
+        assert_eq!(x0.len(), self.n, "initial point has wrong dimension");
+        let n = self.n as i32;
+        let m = self.m as i32;
+        let nbd = self.nbd();
+        // setulb requires finite values also for the missing bounds (they are ignored according to nbd)
+        let l = self.l.map(|l| if l.is_finite() { l } else { 0.0 });
+        let u = self.u.map(|u| if u.is_finite() { u } else { 0.0 });
+
+        // the first call with task = START asks for the evaluation at the initial point
+        let mut x = x0.clone();
+        let mut f = 0.0;
+        let mut g = DVector::zeros(self.n);
+        let mut evaluations = 0;
+        let mut iterations = 0;
+        stringfy(&mut self.task);
+
+        loop {
+            unsafe {
+                ffi::setulb_(
+                    &n,
+                    &m,
+                    x.as_mut_ptr(),
+                    l.as_ptr(),
+                    u.as_ptr(),
+                    nbd.as_ptr(),
+                    &f,
+                    g.as_ptr(),
+                    &self.factr,
+                    &self.pgtol,
+                    self.wa.as_mut_ptr(),
+                    self.iwa.as_mut_ptr(),
+                    self.task.as_mut_ptr(),
+                    &self.iprint,
+                    self.csave.as_mut_ptr(),
+                    self.lsave.as_mut_ptr(),
+                    self.isave.as_mut_ptr(),
+                    self.dsave.as_mut_ptr(),
+                )
+            }
+
+            let message = self.task_message();
+
+            if message.starts_with("FG") {
+                let eval = oracle.value_and_gradient(&x);
+                f = *eval.f();
+                g.copy_from(eval.g());
+                evaluations += 1;
+                trace!(target: "lbfgsb", "Function evaluation {}: f = {}", evaluations, f);
+            } else if message.starts_with("NEW_X") {
+                iterations += 1;
+                let report = self.report(&x, f, &g, iterations, evaluations, message);
+                debug!(target: "lbfgsb", "Iteration {}: f = {}, projected gradient norm = {}", iterations, f, report.projected_gradient_norm());
+                if let Some(callback) = callback.as_mut() {
+                    callback(&report);
+                }
+                if self.max_iter > 0 && iterations >= self.max_iter {
+                    warn!(target: "lbfgsb", "Minimization completed: max iter reached during minimization");
+                    return Err(SolverError::MaxIterReached);
+                }
+            } else if message.starts_with("CONV") {
+                info!(target: "lbfgsb", "Minimization completed: {} in {} iterations", message, iterations);
+                return Ok(self.report(&x, f, &g, iterations, evaluations, message));
+            } else if message.starts_with("ERROR") {
+                error!(target: "lbfgsb", "Minimization failed: {}", message);
+                return Err(SolverError::ErrorInputParams(message));
+            } else {
+                // ABNORMAL_TERMINATION_IN_LNSRCH or WARNING
+                warn!(target: "lbfgsb", "Minimization stopped: {}", message);
+                return Err(SolverError::AbnormalTermination(message));
+            }
+        }
     }
 }
 
@@ -160,3 +274,68 @@ pub fn stringfy(task: &mut [i8]) {
         ffi_string::stringfy_(task.as_mut_ptr());
     }
 }
+
+#[cfg(test)]
+mod lbfgsb_test {
+    use super::*;
+
+    #[test]
+    pub fn infinite_bounds_mapped_to_nbd() {
+        let solver = Lbfgsb::new(4).with_bounds(
+            DVector::from_vec(vec![-Floating::INFINITY, 0.0, 0.0, -Floating::INFINITY]),
+            DVector::from_vec(vec![Floating::INFINITY, Floating::INFINITY, 1.0, 1.0]),
+        );
+        assert_eq!(solver.nbd(), vec![0, 1, 2, 3]);
+
+        // a bound can be overwritten and removed
+        let mut solver = solver;
+        solver.set_lower_bound(1, 2.0);
+        solver.set_lower_bound(2, -Floating::INFINITY);
+        assert_eq!(solver.l[1], 2.0);
+        assert_eq!(solver.nbd(), vec![0, 1, 3, 3]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    pub fn deprecated_setters() {
+        let mut solver = Lbfgsb::new(3);
+        solver.set_m(10);
+        solver.set_factr(1e1);
+        solver.set_pgtol(1e-8);
+        solver.max_iteration(50);
+        solver.set_verbosity(0);
+        assert_eq!((solver.m, solver.max_iter, solver.iprint), (10, 50, 0));
+        assert_eq!((solver.factr, solver.pgtol), (1e1, 1e-8));
+        assert_eq!(solver.wa.len(), Lbfgsb::wa_len(3, 10));
+    }
+
+    #[test]
+    pub fn lbfgsb_report_and_callback() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] + 1.).powi(2) + 90. * (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., 90. * (x[1] - 1.)]);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_vec(vec![0., -Floating::INFINITY]);
+        let upper_bound = DVector::from_vec(vec![Floating::INFINITY, Floating::INFINITY]);
+        let mut solver = Lbfgsb::new(2)
+            .with_bounds(lower_bound, upper_bound)
+            .with_m(7)
+            .with_pgtol(1e-10);
+
+        let x_0 = DVector::from_vec(vec![180.0, 152.0]);
+        let mut callback_calls = 0;
+        let mut callback = |_: &LbfgsbReport| callback_calls += 1;
+        let report = solver.minimize(f_and_g, &x_0, Some(&mut callback)).unwrap();
+
+        println!("Report: {:?}", report);
+
+        // the initial point is not modified
+        assert_eq!(x_0[0], 180.0);
+        assert_eq!(report.x()[0], 0.0);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+        assert!(report.message().starts_with("CONVERGENCE"));
+        assert_eq!(&callback_calls, report.iterations());
+        assert!(report.evaluations() >= report.iterations());
+    }
+}
@@ -0,0 +1,336 @@
+use super::*;
+
+// Orthant-Wise Limited-memory Quasi-Newton (OWL-QN) for L1 regularized problems
+//      min F(x) = f(x) + lambda ||x||_1
+// where f is smooth and provided by the oracle ([Andrew, G., & Gao, J. (2007). Scalable training of L1-regularized log-linear models. ICML]).
+// F is differentiable inside each orthant, so the method runs L-BFGS on the orthant containing the current iterate:
+// - the gradient is replaced by the pseudo-gradient, i.e. the minimum norm subgradient of F (see OWLQN::pseudo_gradient);
+// - the direction is computed with the two-loop recursion on the pseudo-gradient and its components that disagree in sign with the steepest descent direction are set to zero;
+// - the trial points are projected onto the orthant of the current iterate (the components that change sign are set to zero), which is what produces exact zeros in the solution. Use OrthantWiseBackTracking (built from the solver, so that it has the same lambda) as line search.
+// The correction pairs are built with the gradient of f alone, since the L1 term is linear inside an orthant. Memory and time per iteration are O(mn), as in LBFGS.
+#[derive(derive_getters::Getters)]
+pub struct OWLQN {
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    tol: Floating,
+    lambda: Floating, // weight of the L1 regularization
+    history: LimitedMemoryHistory,
+    safeguards: UpdateSafeguards,
+    s_norm: Option<Floating>,
+    y_norm: Option<Floating>,
+}
+
+impl OWLQN {
+    pub fn next_iterate_too_close(&self) -> bool {
+        match self.s_norm() {
+            Some(s) => s < &self.tol,
+            None => false,
+        }
+    }
+    pub fn gradient_next_iterate_too_close(&self) -> bool {
+        match self.y_norm() {
+            Some(y) => y < &self.tol,
+            None => false,
+        }
+    }
+    pub fn new(tol: Floating, x0: DVector<Floating>, lambda: Floating) -> Self {
+        assert!(lambda >= 0.0, "lambda must be non negative");
+        OWLQN {
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            tol,
+            lambda,
+            history: LimitedMemoryHistory::new(5),
+            safeguards: UpdateSafeguards::default(),
+            s_norm: None,
+            y_norm: None,
+        }
+    }
+    // The range 3 <= m <= 20 is recommended
+    pub fn with_m(mut self, m: usize) -> Self {
+        self.history = LimitedMemoryHistory::new(m);
+        self
+    }
+    // only the curvature safeguard is used (see UpdateSafeguards::limited_memory_pair)
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+
+    // Minimum norm subgradient of f + lambda ||x||_1: at x_i != 0 it's the partial derivative of F, while at x_i = 0 it's the one sided derivative that allows a decrease (if any), zero otherwise
+    pub fn pseudo_gradient(
+        x: &DVector<Floating>,
+        g: &DVector<Floating>,
+        lambda: Floating,
+    ) -> DVector<Floating> {
+        x.zip_map(g, |x_i, g_i| {
+            if x_i > 0.0 {
+                g_i + lambda
+            } else if x_i < 0.0 {
+                g_i - lambda
+            } else if g_i + lambda < 0.0 {
+                // the right derivative is negative: F decreases moving to the positive orthant
+                g_i + lambda
+            } else if g_i - lambda > 0.0 {
+                // the left derivative is positive: F decreases moving to the negative orthant
+                g_i - lambda
+            } else {
+                0.0
+            }
+        })
+    }
+
+    // Orthant explored from x: the sign of x_i if it's non zero, otherwise the sign of the steepest descent direction -pseudo_gradient_i (zero if the component stays at zero)
+    pub fn orthant(
+        x: &DVector<Floating>,
+        pseudo_gradient: &DVector<Floating>,
+    ) -> DVector<Floating> {
+        x.zip_map(pseudo_gradient, |x_i, pg_i| {
+            if x_i != 0.0 {
+                x_i.signum()
+            } else if pg_i != 0.0 {
+                -pg_i.signum()
+            } else {
+                0.0
+            }
+        })
+    }
+
+    // Projection onto the orthant: the components whose sign disagrees with the orthant are set to zero
+    pub fn orthant_projection(
+        x: &DVector<Floating>,
+        orthant: &DVector<Floating>,
+    ) -> DVector<Floating> {
+        x.zip_map(orthant, |x_i, o_i| if x_i * o_i > 0.0 { x_i } else { 0.0 })
+    }
+}
+
+impl ComputeDirection for OWLQN {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let pseudo_gradient = Self::pseudo_gradient(&self.x, eval.g(), self.lambda);
+        let mut direction = -self.history.inverse_hessian_product(&pseudo_gradient);
+        // the direction is constrained to the orthant of the steepest descent direction
+        direction.zip_apply(&pseudo_gradient, |d_i, pg_i| {
+            if *d_i * pg_i >= 0.0 {
+                *d_i = 0.0;
+            }
+        });
+        if direction.dot(&pseudo_gradient) >= 0.0 && !self.history.is_empty() {
+            // every component has been removed by the projection: we restart from the steepest descent direction
+            debug!(target: "OWLQN", "Not a descent direction: resetting the limited memory");
+            self.history.clear();
+            direction = -pseudo_gradient;
+        }
+        Ok(direction)
+    }
+}
+
+impl LineSearchSolver for OWLQN {
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        // either the pseudo-gradient is small or the difference between the iterates is small
+        if self.next_iterate_too_close() {
+            Some(TerminationReason::NextIterateTooClose)
+        } else if self.gradient_next_iterate_too_close() {
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if Self::pseudo_gradient(&self.x, eval.g(), self.lambda).norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
+        } else {
+            None
+        }
+    }
+    // the pseudo-gradient plays the role of the projected gradient: it's zero if and only if x is a minimizer of F
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(Self::pseudo_gradient(&self.x, eval.g(), self.lambda).infinity_norm())
+    }
+    fn zeros(&self) -> Option<usize> {
+        Some(self.x.iter().filter(|x_i| **x_i == 0.0).count())
+    }
+
+    fn setup(&mut self) {
+        // the history belongs to a single run: restarting the minimization from the current iterate starts from scratch
+        self.history.clear();
+        self.s_norm = None;
+        self.y_norm = None;
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, _) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        // the same projection applied by OrthantWiseBackTracking to the trial points
+        let pseudo_gradient = Self::pseudo_gradient(&self.x, eval_x_k.g(), self.lambda);
+        let orthant = Self::orthant(&self.x, &pseudo_gradient);
+        let next_iterate = Self::orthant_projection(&(self.xk() + step * direction), &orthant);
+        debug!(target: "OWLQN", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, next_iterate);
+
+        let s = &next_iterate - &self.x;
+        self.s_norm = Some(s.norm());
+        let eval_next_iterate = oracle.value_and_gradient(&next_iterate);
+        let y = eval_next_iterate.g() - eval_x_k.g();
+        self.y_norm = Some(y.norm());
+
+        *self.xk_mut() = next_iterate;
+
+        if self.next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        if self.gradient_next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        // the pair is stored only if the curvature condition holds (possibly after damping), otherwise the approximation wouldn't be positive definite
+        if let Some(s) = self.safeguards.limited_memory_pair(&s, &y, &self.history) {
+            self.history.push(s, y);
+        }
+
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod owlqn_test {
+    use super::*;
+
+    #[test]
+    pub fn owlqn_separable_quadratic() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // f(x) = 1/2 ||x - c||^2: the minimizer of f + lambda ||x||_1 is the soft-thresholding of c
+        let c = DVector::from(vec![3.0, -0.5, 1.5, -2.0, 0.2, -1.2]);
+        let lambda = 1.0;
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x - &c;
+            let f = 0.5 * g.norm_squared();
+            (f, g).into()
+        };
+        let x_0 = DVector::from_element(c.len(), 0.5);
+        let mut solver = OWLQN::new(1e-10, x_0, lambda);
+        let report = solver
+            .minimize(
+                &mut OrthantWiseBackTracking::new(1e-4, 0.5, &solver),
+                f_and_g,
+                1000,
+                100,
+                None,
+            )
+            .unwrap();
+
+        println!("Report: {:?}", report);
+        let solution = L1Norm::soft_thresholding(&c, lambda);
+        assert!((report.x() - &solution).amax() < 1e-8);
+        // the components thresholded to zero are exactly zero
+        assert_eq!(report.x()[1], 0.0);
+        assert_eq!(report.x()[4], 0.0);
+        assert_eq!(report.zeros(), &Some(2));
+    }
+
+    #[test]
+    pub fn owlqn_sparse_logistic_regression() {
+        // deterministic pseudo random numbers in [-1, 1] (linear congruential generator)
+        let mut seed: u64 = 42;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 11) as Floating / (1u64 << 53) as Floating) * 2.0 - 1.0
+        };
+
+        // labels generated by a sparse model with 5 relevant features out of 100, with some noise
+        let (n_samples, n_features) = (300, 100);
+        let a = DMatrix::from_fn(n_samples, n_features, |_, _| random());
+        let w_true = DVector::from_fn(n_features, |i, _| match i {
+            0 => 2.0,
+            1 => -3.0,
+            2 => 1.5,
+            3 => -1.0,
+            4 => 2.5,
+            _ => 0.0,
+        });
+        let margin = &a * &w_true;
+        let b = DVector::from_fn(n_samples, |i, _| {
+            if margin[i] + 0.5 * random() > 0.0 {
+                1.0
+            } else {
+                -1.0
+            }
+        });
+
+        // average logistic loss
+        let f_and_g = |w: &DVector<Floating>| -> FuncEvalMultivariate {
+            let z = (&a * w).component_mul(&b);
+            let f = z.map(|z_i| (1.0 + (-z_i).exp()).ln()).sum() / n_samples as Floating;
+            let sigma = z.map(|z_i| -1.0 / (1.0 + z_i.exp())).component_mul(&b);
+            let g = a.tr_mul(&sigma) / n_samples as Floating;
+            (f, g).into()
+        };
+
+        let lambda = 0.02;
+        let x_0 = DVector::zeros(n_features);
+        let mut solver = OWLQN::new(1e-8, x_0.clone(), lambda).with_m(10);
+        let report = solver
+            .minimize(
+                &mut OrthantWiseBackTracking::new(1e-4, 0.5, &solver),
+                f_and_g,
+                1000,
+                100,
+                None,
+            )
+            .unwrap();
+        println!("Report: {:?}", report);
+
+        // first order optimality conditions of the L1 regularized problem
+        let w = report.x();
+        let g = f_and_g(w).g().clone();
+        for i in 0..n_features {
+            if w[i] == 0.0 {
+                assert!(g[i].abs() <= lambda + 1e-6);
+            } else {
+                assert!((g[i] + lambda * w[i].signum()).abs() < 1e-6);
+            }
+        }
+        // the relevant features are selected and most of the others are exactly zero
+        assert!((0..5).all(|i| w[i] * w_true[i] > 0.0));
+        let zeros = report.zeros().unwrap();
+        assert!(zeros > n_features / 2);
+
+        // same objective value of the proximal gradient method
+        let mut fista = AcceleratedProximalGradient::new(1e-8, x_0, L1Norm::new(lambda));
+        let report_fista = fista.minimize(f_and_g, 100000, None).unwrap();
+        let objective = |x: &DVector<Floating>| f_and_g(x).f() + lambda * x.lp_norm(1);
+        assert!((objective(w) - report_fista.f()).abs() < 1e-8);
+    }
+}
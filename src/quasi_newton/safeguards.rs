@@ -0,0 +1,240 @@
+use super::*;
+
+// Safeguards shared by the quasi-newton solvers storing a dense approximation H of the inverse hessian. The limited memory solvers (LBFGS, LBFGSB, OWLQN) use only the curvature safeguard (see limited_memory_pair), since they rescale the initial approximation at every iteration and don't implement the SR1 update.
+// The BFGS, DFP and Broyden updates require the curvature condition s^T y > 0: with line searches that enforce only the armijo condition (e.g. BackTracking, GLLQuadratic) or with projected steps it can fail, and the update would make H indefinite (or divide by zero).
+// The SR1 update doesn't require positive curvature, but its denominator can vanish.
+// Sections 6.1, 6.2 and 18.3 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurvatureSafeguard {
+    // the update is skipped when s^T y <= threshold * ||s|| * ||y||
+    Skip { threshold: Floating },
+    // Powell damping (procedure 18.2) written for the inverse approximation: when s^T y < delta * y^T H y, the correction s is replaced with
+    //      s_damped = theta * s + (1 - theta) * H y,   theta = (1 - delta) * y^T H y / (y^T H y - s^T y)
+    // so that s_damped^T y = delta * y^T H y > 0 and the updated approximation stays positive definite. Only products with H are needed (the damping of y in the original procedure would require B = H^{-1})
+    PowellDamping { delta: Floating },
+}
+
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct UpdateSafeguards {
+    curvature: CurvatureSafeguard,
+    sr1_threshold: Floating, // r in the SR1 skip rule |y^T (s - H y)| >= r * ||y|| * ||s - H y||
+    initial_scaling: bool, // if true, before the first update H_0 is rescaled with the Shanno-Phua factor s^T y / y^T y (equation 6.20)
+    #[getter(skip)]
+    scaling_pending: bool,
+}
+
+impl Default for UpdateSafeguards {
+    fn default() -> Self {
+        UpdateSafeguards {
+            curvature: CurvatureSafeguard::Skip { threshold: 1e-8 },
+            sr1_threshold: 1e-8,
+            initial_scaling: false,
+            scaling_pending: true,
+        }
+    }
+}
+
+impl UpdateSafeguards {
+    pub fn with_curvature_safeguard(mut self, curvature: CurvatureSafeguard) -> Self {
+        match curvature {
+            CurvatureSafeguard::Skip { threshold } => {
+                assert!(threshold >= 0.0, "threshold must be non negative")
+            }
+            CurvatureSafeguard::PowellDamping { delta } => {
+                assert!(delta > 0.0 && delta < 1.0, "delta must be in (0, 1)")
+            }
+        }
+        self.curvature = curvature;
+        self
+    }
+    pub fn with_sr1_threshold(mut self, sr1_threshold: Floating) -> Self {
+        assert!(sr1_threshold >= 0.0, "threshold must be non negative");
+        self.sr1_threshold = sr1_threshold;
+        self
+    }
+    pub fn with_initial_scaling(mut self, initial_scaling: bool) -> Self {
+        self.initial_scaling = initial_scaling;
+        self
+    }
+
+    // Shanno-Phua scaling of the initial approximation (applied once, at the first pair with positive curvature)
+    pub fn scale_initial_approximation(
+        &mut self,
+        approx_inv_hessian: &mut DMatrix<Floating>,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+    ) {
+        if !self.initial_scaling || !self.scaling_pending {
+            return;
+        }
+        let sy = s.dot(y);
+        if sy <= 0.0 {
+            return;
+        }
+        let n = s.len();
+        *approx_inv_hessian = DMatrix::identity(n, n) * (sy / y.norm_squared());
+        self.scaling_pending = false;
+    }
+
+    // correction s to be used in the update (damped if needed), or None if the update has to be skipped
+    pub fn curvature_pair(
+        &self,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+        approx_inv_hessian: &DMatrix<Floating>,
+    ) -> Option<DVector<Floating>> {
+        self.checked_curvature_pair(s, y, || approx_inv_hessian * y)
+    }
+
+    // Counterpart of curvature_pair for the limited memory solvers: the product H y needed by the Powell damping is computed with the two-loop recursion on the stored pairs
+    pub fn limited_memory_pair(
+        &self,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+        history: &LimitedMemoryHistory,
+    ) -> Option<DVector<Floating>> {
+        self.checked_curvature_pair(s, y, || history.inverse_hessian_product(y))
+    }
+
+    fn checked_curvature_pair(
+        &self,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+        inverse_hessian_product: impl FnOnce() -> DVector<Floating>,
+    ) -> Option<DVector<Floating>> {
+        let sy = s.dot(y);
+        match self.curvature {
+            CurvatureSafeguard::Skip { threshold } => {
+                if sy > threshold * s.norm() * y.norm() {
+                    Some(s.clone())
+                } else {
+                    debug!(target: "quasi_newton", "Curvature condition not satisfied (s^T y = {}): skipping the update", sy);
+                    None
+                }
+            }
+            CurvatureSafeguard::PowellDamping { delta } => {
+                let hy = inverse_hessian_product();
+                let yhy = y.dot(&hy);
+                if yhy <= 0.0 {
+                    debug!(target: "quasi_newton", "y^T H y = {} is not positive: skipping the update", yhy);
+                    return None;
+                }
+                if sy >= delta * yhy {
+                    return Some(s.clone());
+                }
+                let theta = (1.0 - delta) * yhy / (yhy - sy);
+                debug!(target: "quasi_newton", "Powell damping with theta = {} (s^T y = {})", theta, sy);
+                Some(theta * s + (1.0 - theta) * hy)
+            }
+        }
+    }
+
+    // Entry point of the solvers before a BFGS, DFP or Broyden update of the inverse approximation H with the pair (s, y):
+    // - before the first update H is rescaled (if the initial scaling is enabled, see scale_initial_approximation)
+    // - the correction s is checked against the curvature condition and damped if needed (see curvature_pair)
+    // Returns the correction to use in the update, or None if the update has to be skipped (H is left unchanged, except for the initial scaling).
+    pub fn prepare_update(
+        &mut self,
+        approx_inv_hessian: &mut DMatrix<Floating>,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+    ) -> Option<DVector<Floating>> {
+        self.scale_initial_approximation(approx_inv_hessian, s, y);
+        self.curvature_pair(s, y, approx_inv_hessian)
+    }
+
+    // Counterpart of prepare_update for the SR1 update, which needs s - H y instead of a positive curvature: after the initial scaling the work vector is filled with s - H y, and the return value tells whether the skip rule allows the update.
+    pub fn prepare_sr1_update(
+        &mut self,
+        approx_inv_hessian: &mut DMatrix<Floating>,
+        s: &DVector<Floating>,
+        y: &DVector<Floating>,
+        s_minus_hy: &mut DVector<Floating>,
+    ) -> bool {
+        self.scale_initial_approximation(approx_inv_hessian, s, y);
+        s_minus_hy.sygemv(1.0, approx_inv_hessian, y, 0.0);
+        s_minus_hy.axpy(1.0, s, -1.0);
+        self.sr1_update_allowed(s_minus_hy, y)
+    }
+
+    // skip rule of the SR1 update (equation 6.26, written for the inverse approximation)
+    pub fn sr1_update_allowed(
+        &self,
+        s_minus_hy: &DVector<Floating>,
+        y: &DVector<Floating>,
+    ) -> bool {
+        let denominator = s_minus_hy.dot(y);
+        let allowed = denominator.abs() >= self.sr1_threshold * y.norm() * s_minus_hy.norm()
+            && denominator != 0.0;
+        if !allowed {
+            debug!(target: "quasi_newton", "SR1 denominator too small ({}): skipping the update", denominator);
+        }
+        allowed
+    }
+}
+
+#[cfg(test)]
+mod safeguards_test {
+    use super::*;
+
+    #[test]
+    pub fn curvature_safeguards() {
+        let h = DMatrix::identity(2, 2);
+        let s = DVector::from(vec![1.0, 0.0]);
+        let y = DVector::from(vec![-1.0, 1.0]);
+
+        // negative curvature: skipped
+        let safeguards = UpdateSafeguards::default();
+        assert!(safeguards.curvature_pair(&s, &y, &h).is_none());
+        let y_good = DVector::from(vec![1.0, 1.0]);
+        assert_eq!(safeguards.curvature_pair(&s, &y_good, &h), Some(s.clone()));
+
+        // negative curvature: damped so that s^T y = delta * y^T H y
+        let delta = 0.2;
+        let safeguards = UpdateSafeguards::default()
+            .with_curvature_safeguard(CurvatureSafeguard::PowellDamping { delta });
+        let s_damped = safeguards.curvature_pair(&s, &y, &h).unwrap();
+        assert!((s_damped.dot(&y) - delta * y.dot(&(&h * &y))).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn sr1_skip_rule_and_scaling() {
+        let safeguards = UpdateSafeguards::default();
+        let y = DVector::from(vec![1.0, 0.0]);
+        assert!(!safeguards.sr1_update_allowed(&DVector::from(vec![0.0, 1.0]), &y));
+        assert!(safeguards.sr1_update_allowed(&DVector::from(vec![1.0, 1.0]), &y));
+
+        let mut safeguards = UpdateSafeguards::default().with_initial_scaling(true);
+        let mut h = DMatrix::identity(2, 2);
+        let s = DVector::from(vec![1.0, 0.0]);
+        let y = DVector::from(vec![2.0, 0.0]);
+        safeguards.scale_initial_approximation(&mut h, &s, &y);
+        assert_eq!(h, DMatrix::identity(2, 2) * 0.5);
+        // only the initial approximation is scaled
+        safeguards.scale_initial_approximation(&mut h, &s, &(2.0 * &y));
+        assert_eq!(h, DMatrix::identity(2, 2) * 0.5);
+    }
+
+    #[test]
+    pub fn prepare_update() {
+        let mut safeguards = UpdateSafeguards::default().with_initial_scaling(true);
+        let mut h = DMatrix::identity(2, 2);
+        let s = DVector::from(vec![1.0, 0.0]);
+
+        // negative curvature: skipped, without scaling the initial approximation
+        let y = DVector::from(vec![-1.0, 1.0]);
+        assert!(safeguards.prepare_update(&mut h, &s, &y).is_none());
+        assert_eq!(h, DMatrix::identity(2, 2));
+
+        // positive curvature: scaled and accepted
+        let y = DVector::from(vec![2.0, 0.0]);
+        assert_eq!(safeguards.prepare_update(&mut h, &s, &y), Some(s.clone()));
+        assert_eq!(h, DMatrix::identity(2, 2) * 0.5);
+
+        // SR1: the work vector holds s - H y
+        let mut s_minus_hy = DVector::zeros(2);
+        let y = DVector::from(vec![1.0, 0.5]);
+        assert!(safeguards.prepare_sr1_update(&mut h, &s, &y, &mut s_minus_hy));
+        assert_eq!(s_minus_hy, DVector::from(vec![0.5, -0.25]));
+    }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+// Symmetric rank-one quasi-newton method (section 6.2 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// The SR1 update doesn't require the curvature condition, so the approximation of the inverse hessian can become indefinite and the quasi-newton direction might not be a descent direction: in that case the approximation is reset to the identity. The update is skipped when its denominator is too small (see UpdateSafeguards::sr1_update_allowed).
+// Since indefinite approximations are not a problem for trust region methods, SR1TrustRegion is usually a better choice than a line search.
+#[derive(derive_getters::Getters)]
+pub struct SR1 {
+    approx_inv_hessian: DMatrix<Floating>,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    tol: Floating,
+    s_norm: Option<Floating>,
+    y_norm: Option<Floating>,
+    #[getter(skip)]
+    hy: DVector<Floating>, // work vector for the update of the inverse hessian
+    safeguards: UpdateSafeguards,
+}
+
+impl SR1 {
+    pub fn next_iterate_too_close(&self) -> bool {
+        match self.s_norm() {
+            Some(s) => s < &self.tol,
+            None => false,
+        }
+    }
+    pub fn gradient_next_iterate_too_close(&self) -> bool {
+        match self.y_norm() {
+            Some(y) => y < &self.tol,
+            None => false,
+        }
+    }
+    pub fn new(tol: Floating, x0: DVector<Floating>) -> Self {
+        let n = x0.len();
+        SR1 {
+            approx_inv_hessian: DMatrix::identity(n, n),
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            tol,
+            s_norm: None,
+            y_norm: None,
+            hy: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
+        }
+    }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+}
+
+impl ComputeDirection for SR1 {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let direction = -&self.approx_inv_hessian * eval.g();
+        if eval.g().dot(&direction) < 0.0 {
+            return Ok(direction);
+        }
+        debug!(target: "sr1", "Not a descent direction: resetting the approximation of the inverse hessian");
+        let n = self.x.len();
+        self.approx_inv_hessian = DMatrix::identity(n, n);
+        Ok(-eval.g())
+    }
+}
+
+impl LineSearchSolver for SR1 {
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        if self.next_iterate_too_close() {
+            Some(TerminationReason::NextIterateTooClose)
+        } else if self.gradient_next_iterate_too_close() {
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if eval.g().norm() < self.tol {
+            Some(TerminationReason::GradientTolerance)
+        } else {
+            None
+        }
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        let next_iterate = self.xk() + step * direction;
+
+        let s = &next_iterate - &self.x;
+        self.s_norm = Some(s.norm());
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
+        self.y_norm = Some(y.norm());
+
+        *self.xk_mut() = next_iterate;
+
+        if self.next_iterate_too_close() || self.gradient_next_iterate_too_close() {
+            return Ok(Some(eval_next_iterate));
+        }
+
+        // the work vector stores s - H y
+        if !self
+            .safeguards
+            .prepare_sr1_update(&mut self.approx_inv_hessian, &s, &y, &mut self.hy)
+        {
+            return Ok(Some(eval_next_iterate));
+        }
+        sr1_inverse_update(&mut self.approx_inv_hessian, &y, &self.hy);
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod test_sr1 {
+    use super::*;
+
+    #[test]
+    pub fn sr1_quadratic_termination() {
+        // on a strongly convex quadratic the SR1 approximation is equal to the inverse hessian after n independent steps (theorem 6.1 in Nocedal & Wright)
+        let hessian = DMatrix::from_row_slice(3, 3, &[4.0, 1.0, 0.0, 1.0, 3.0, 0.5, 0.0, 0.5, 2.0]);
+        let b = DVector::from(vec![1.0, -2.0, 0.5]);
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let hx = &hessian * x;
+            let f = 0.5 * x.dot(&hx) - b.dot(x);
+            (f, hx - &b).into()
+        };
+
+        let mut ls = MoreThuente::default();
+        let x_0 = DVector::from(vec![5.0, 5.0, 5.0]);
+        let mut solver = SR1::new(1e-10, x_0);
+        let report = solver.minimize(&mut ls, f_and_g, 100, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        let solution = hessian.clone().cholesky().unwrap().solve(&b);
+        assert!((report.x() - solution).amax() < 1e-8);
+        assert!(report.iterations() <= &5);
+    }
+
+    #[test]
+    pub fn sr1_backtracking_rosenbrock() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+
+        let mut ls = BackTracking::new(1e-4, 0.5);
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = SR1::new(1e-8, x_0);
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+    }
+}
@@ -5,10 +5,14 @@ pub struct SR1B {
     approx_inv_hessian: DMatrix<Floating>,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     tol: Floating,
     s_norm: Option<Floating>,
     y_norm: Option<Floating>,
-    identity: DMatrix<Floating>,
+    #[getter(skip)]
+    hy: DVector<Floating>, // work vector for the update of the inverse hessian
+    safeguards: UpdateSafeguards,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
 }
@@ -49,19 +53,24 @@ impl SR1B {
     ) -> Self {
         let n = x0.len();
         let x0 = x0.box_projection(&lower_bound, &upper_bound);
-        let identity = DMatrix::identity(n, n);
         SR1B {
-            approx_inv_hessian: identity.clone(),
+            approx_inv_hessian: DMatrix::identity(n, n),
             x: x0,
             k: 0,
+            stopping_criterion: None,
             tol,
             s_norm: None,
             y_norm: None,
-            identity,
+            hy: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
             lower_bound,
             upper_bound,
         }
     }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
 }
 
 impl ComputeDirection for SR1B {
@@ -90,41 +99,47 @@ impl LineSearchSolver for SR1B {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // either the gradient is small or the difference between the iterates is small
         // eval.g().norm() < self.tol || self.next_iterate_too_close()
         if self.next_iterate_too_close() {
-            warn!(target: "SR1B","Minimization completed: next iterate too close");
-            true
+            Some(TerminationReason::NextIterateTooClose)
         } else if self.gradient_next_iterate_too_close() {
-            warn!(target: "SR1B","Minimization completed: gradient next iterate too close");
-            true
+            Some(TerminationReason::GradientNextIterateTooClose)
+        } else if self.projected_gradient(eval).norm() < self.tol {
+            // the gradient is projected, so that solutions on the boundary of the box are recognized
+            Some(TerminationReason::GradientTolerance)
         } else {
-            eval.g().norm() < self.tol
+            None
         }
     }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
+    }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let next_iterate = self.xk() + step * direction;
 
         let s = &next_iterate - &self.x;
         self.s_norm = Some(s.norm());
-        let y = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
         self.y_norm = Some(y.norm());
 
         //updating iterate here, and then we will update the inverse hessian (if corrections are not too small)
@@ -133,18 +148,22 @@ impl LineSearchSolver for SR1B {
         // We update the inverse hessian and the corrections in this hook which is triggered just after the calculation of the next iterate
 
         if self.next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
         if self.gradient_next_iterate_too_close() {
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
 
-        // SR1B update
-        let hy = &self.approx_inv_hessian * &y;
-        let shy = s - hy;
-        self.approx_inv_hessian += &shy * shy.transpose() / shy.dot(&y);
-        Ok(())
+        // SR1B update (the work vector stores s - H y)
+        if !self
+            .safeguards
+            .prepare_sr1_update(&mut self.approx_inv_hessian, &s, &y, &mut self.hy)
+        {
+            return Ok(Some(eval_next_iterate));
+        }
+        sr1_inverse_update(&mut self.approx_inv_hessian, &y, &self.hy);
+        Ok(Some(eval_next_iterate))
     }
 }
 #[cfg(test)]
@@ -210,4 +229,29 @@ mod test_sr1_b {
 
         assert!((eval.f() - 0.0).abs() < 1e-6);
     }
+
+    #[test]
+    pub fn sr1_b_solution_on_active_bound() {
+        // minimum of the quadratic at (-1, 1), outside the box [0, 2] x [0, 2]: at the solution (0, 1) the gradient is (1, 0), but the projected gradient vanishes
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] + 1.).powi(2) + 10. * (x[1] - 1.).powi(2));
+            let g = DVector::from(vec![x[0] + 1., 10. * (x[1] - 1.)]);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_element(2, 0.0);
+        let upper_bound = DVector::from_element(2, 2.0);
+        let mut ls = BackTrackingB::new(1e-4, 0.5, lower_bound.clone(), upper_bound.clone());
+        let x_0 = DVector::from(vec![1.5, 0.5]);
+        let mut solver = SR1B::new(1e-8, x_0, lower_bound, upper_bound);
+
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::GradientTolerance
+        );
+        assert!(report.x()[0].abs() < 1e-12);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+    }
 }
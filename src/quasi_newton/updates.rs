@@ -0,0 +1,177 @@
+use super::*;
+
+// In-place updates of the dense approximation H of the inverse hessian used by the quasi-newton solvers.
+// The corrections are symmetric rank-one or rank-two matrices, so the updates are written as (at most three) rank-one updates of H, each costing O(n^2), instead of products between n x n matrices, which cost O(n^3).
+// The vector H y (needed by every update) is stored in a work vector preallocated by the solver.
+// For the symmetric updates only the lower triangle of H is written (with syger), and then copied to the upper triangle, so that H stays exactly symmetric.
+
+// Equation 6.17 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]:
+//      H+ = (I - rho s y^T) H (I - rho y s^T) + rho s s^T,     rho = 1 / (s^T y)
+// which, expanding the products, is equal to
+//      H+ = H - rho (s (H y)^T + (H y) s^T) + (rho + rho^2 y^T H y) s s^T
+pub fn bfgs_inverse_update(
+    approx_inv_hessian: &mut DMatrix<Floating>,
+    s: &DVector<Floating>,
+    y: &DVector<Floating>,
+    hy: &mut DVector<Floating>,
+) {
+    hy.sygemv(1.0, approx_inv_hessian, y, 0.0);
+    let rho = 1.0 / s.dot(y);
+    let yhy = y.dot(hy);
+    approx_inv_hessian.syger(-rho, s, hy, 1.0);
+    approx_inv_hessian.syger(-rho, hy, s, 1.0);
+    approx_inv_hessian.syger(rho + rho * rho * yhy, s, s, 1.0);
+    approx_inv_hessian.fill_upper_triangle_with_lower_triangle();
+}
+
+// Equation 6.15 in Nocedal & Wright:
+//      H+ = H - (H y) (H y)^T / (y^T H y) + s s^T / (s^T y)
+pub fn dfp_inverse_update(
+    approx_inv_hessian: &mut DMatrix<Floating>,
+    s: &DVector<Floating>,
+    y: &DVector<Floating>,
+    hy: &mut DVector<Floating>,
+) {
+    hy.sygemv(1.0, approx_inv_hessian, y, 0.0);
+    let sy = s.dot(y);
+    let yhy = y.dot(hy);
+    approx_inv_hessian.syger(-1.0 / yhy, hy, hy, 1.0);
+    approx_inv_hessian.syger(1.0 / sy, s, s, 1.0);
+    approx_inv_hessian.fill_upper_triangle_with_lower_triangle();
+}
+
+// Equation 6.25 in Nocedal & Wright:
+//      H+ = H + (s - H y) (s - H y)^T / ((s - H y)^T y)
+// The work vector must already contain s - H y (it's needed by the skip rule before the update)
+pub fn sr1_inverse_update(
+    approx_inv_hessian: &mut DMatrix<Floating>,
+    y: &DVector<Floating>,
+    s_minus_hy: &DVector<Floating>,
+) {
+    let denominator = s_minus_hy.dot(y);
+    approx_inv_hessian.syger(1.0 / denominator, s_minus_hy, s_minus_hy, 1.0);
+    approx_inv_hessian.fill_upper_triangle_with_lower_triangle();
+}
+
+// Broyden class (equation 6.32 in Nocedal & Wright, written for the inverse approximation): convex combination of the BFGS and DFP updates
+//      H+ = (1 - phi) H_BFGS + phi H_DFP = H_BFGS - phi (y^T H y) w w^T,      w = s / (s^T y) - H y / (y^T H y)
+// phi = 0 gives BFGS, phi = 1 gives DFP, and phi = y^T H y / (y^T H y - s^T y) gives SR1. Expanding w w^T:
+//      H+ = H + (1 / (s^T y) + (1 - phi) (y^T H y) / (s^T y)^2) s s^T - (1 - phi) / (s^T y) (s (H y)^T + (H y) s^T) - phi / (y^T H y) (H y) (H y)^T
+pub fn broyden_class_inverse_update(
+    approx_inv_hessian: &mut DMatrix<Floating>,
+    s: &DVector<Floating>,
+    y: &DVector<Floating>,
+    phi: Floating,
+    hy: &mut DVector<Floating>,
+) {
+    hy.sygemv(1.0, approx_inv_hessian, y, 0.0);
+    let sy = s.dot(y);
+    let yhy = y.dot(hy);
+    let psi = 1.0 - phi;
+    approx_inv_hessian.syger(1.0 / sy + psi * yhy / (sy * sy), s, s, 1.0);
+    approx_inv_hessian.syger(-psi / sy, s, hy, 1.0);
+    approx_inv_hessian.syger(-psi / sy, hy, s, 1.0);
+    approx_inv_hessian.syger(-phi / yhy, hy, hy, 1.0);
+    approx_inv_hessian.fill_upper_triangle_with_lower_triangle();
+}
+
+// Broyden update of the inverse hessian (not symmetric):
+//      H+ = H + (s - H y) (H^T s)^T / (s^T y)
+pub fn broyden_inverse_update(
+    approx_inv_hessian: &mut DMatrix<Floating>,
+    s: &DVector<Floating>,
+    y: &DVector<Floating>,
+    hy: &mut DVector<Floating>,
+    hts: &mut DVector<Floating>,
+) {
+    hy.gemv(1.0, approx_inv_hessian, y, 0.0);
+    hy.axpy(1.0, s, -1.0);
+    hts.gemv_tr(1.0, approx_inv_hessian, s, 0.0);
+    let sy = s.dot(y);
+    approx_inv_hessian.ger(1.0 / sy, hy, hts, 1.0);
+}
+
+#[cfg(test)]
+mod updates_test {
+    use super::*;
+
+    fn pair() -> (DMatrix<Floating>, DVector<Floating>, DVector<Floating>) {
+        let h = DMatrix::from_row_slice(3, 3, &[2.0, 0.5, 0.1, 0.5, 1.0, 0.2, 0.1, 0.2, 3.0]);
+        let s = DVector::from(vec![0.3, -1.0, 0.7]);
+        let y = DVector::from(vec![1.0, -0.5, 0.4]);
+        (h, s, y)
+    }
+
+    #[test]
+    pub fn in_place_updates_match_dense_formulas() {
+        let (h, s, y) = pair();
+        let n = s.len();
+        let identity = DMatrix::<Floating>::identity(n, n);
+        let sy = s.dot(&y);
+        let hy_dense = &h * &y;
+        let mut hy = DVector::zeros(n);
+        let mut hts = DVector::zeros(n);
+
+        let rho = 1.0 / sy;
+        let expected =
+            (&identity - rho * &s * y.transpose()) * &h * (&identity - rho * &y * s.transpose())
+                + rho * &s * s.transpose();
+        let mut updated = h.clone();
+        bfgs_inverse_update(&mut updated, &s, &y, &mut hy);
+        assert!((&updated - &expected).amax() < 1e-12);
+        // secant equation
+        assert!((&updated * &y - &s).amax() < 1e-12);
+
+        let expected =
+            &h + &s * s.transpose() / sy - &hy_dense * hy_dense.transpose() / y.dot(&hy_dense);
+        let mut updated = h.clone();
+        dfp_inverse_update(&mut updated, &s, &y, &mut hy);
+        assert!((&updated - &expected).amax() < 1e-12);
+        assert!((&updated * &y - &s).amax() < 1e-12);
+
+        let shy = &s - &hy_dense;
+        let expected = &h + &shy * shy.transpose() / shy.dot(&y);
+        let mut updated = h.clone();
+        sr1_inverse_update(&mut updated, &y, &shy);
+        assert!((&updated - &expected).amax() < 1e-12);
+        assert!((&updated * &y - &s).amax() < 1e-12);
+
+        let expected = &h + (&shy * s.transpose()) * &h / sy;
+        let mut updated = h.clone();
+        broyden_inverse_update(&mut updated, &s, &y, &mut hy, &mut hts);
+        assert!((&updated - &expected).amax() < 1e-12);
+    }
+
+    #[test]
+    pub fn broyden_class_presets() {
+        let (h, s, y) = pair();
+        let n = s.len();
+        let mut hy = DVector::zeros(n);
+        let hy_dense = &h * &y;
+        let yhy = y.dot(&hy_dense);
+
+        let mut expected = h.clone();
+        bfgs_inverse_update(&mut expected, &s, &y, &mut hy);
+        let mut updated = h.clone();
+        broyden_class_inverse_update(&mut updated, &s, &y, 0.0, &mut hy);
+        assert!((&updated - &expected).amax() < 1e-12);
+
+        let mut expected = h.clone();
+        dfp_inverse_update(&mut expected, &s, &y, &mut hy);
+        let mut updated = h.clone();
+        broyden_class_inverse_update(&mut updated, &s, &y, 1.0, &mut hy);
+        assert!((&updated - &expected).amax() < 1e-12);
+
+        let mut expected = h.clone();
+        sr1_inverse_update(&mut expected, &y, &(&s - &hy_dense));
+        let phi_sr1 = yhy / (yhy - s.dot(&y));
+        let mut updated = h.clone();
+        broyden_class_inverse_update(&mut updated, &s, &y, phi_sr1, &mut hy);
+        assert!((&updated - &expected).amax() < 1e-10);
+
+        // every member of the class satisfies the secant equation
+        let mut updated = h.clone();
+        broyden_class_inverse_update(&mut updated, &s, &y, 0.3, &mut hy);
+        assert!((&updated * &y - &s).amax() < 1e-12);
+    }
+}
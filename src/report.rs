@@ -0,0 +1,255 @@
+use super::*;
+
+// Summary of a minimization run, returned by LineSearchSolver::minimize so that the caller doesn't need to query the solver and re-evaluate the oracle to know what happened.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationReason {
+    // the (projected) gradient norm is below the tolerance of the solver
+    GradientTolerance,
+    // the norm of the correction s_k = x_{k+1} - x_k is below the tolerance of the solver
+    NextIterateTooClose,
+    // the norm of the correction y_k = g_{k+1} - g_k is below the tolerance of the solver
+    GradientNextIterateTooClose,
+    // half of the squared newton decrement is below the tolerance of the solver
+    NewtonDecrement,
+    // the trust region radius is below the minimum radius of the solver
+    TrustRegionRadiusTooSmall,
+    // the diameter of the simplex and the spread of the images at its vertices are below the tolerance of the solver
+    SimplexTolerance,
+    // the following reasons are raised by the stopping criteria (see stopping_criterion.rs)
+    RelativeGradientTolerance,
+    AbsoluteFunctionChange,
+    RelativeFunctionChange,
+    StepSizeTolerance,
+    MaxEvaluations,
+    WallClockBudget,
+    TargetObjective,
+}
+
+impl std::fmt::Display for TerminationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            TerminationReason::GradientTolerance => "gradient tolerance reached",
+            TerminationReason::NextIterateTooClose => "next iterate too close",
+            TerminationReason::GradientNextIterateTooClose => "gradient next iterate too close",
+            TerminationReason::NewtonDecrement => "newton decrement below tolerance",
+            TerminationReason::TrustRegionRadiusTooSmall => "trust region radius below tolerance",
+            TerminationReason::SimplexTolerance => "simplex size below tolerance",
+            TerminationReason::RelativeGradientTolerance => "relative gradient tolerance reached",
+            TerminationReason::AbsoluteFunctionChange => "absolute function change below tolerance",
+            TerminationReason::RelativeFunctionChange => "relative function change below tolerance",
+            TerminationReason::StepSizeTolerance => "step size below tolerance",
+            TerminationReason::MaxEvaluations => "max function evaluations reached",
+            TerminationReason::WallClockBudget => "wall clock budget exhausted",
+            TerminationReason::TargetObjective => "target objective value reached",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+// Number of calls to each entry point of the objective during the minimization (also the ones performed by the line search). When the oracle is queried for the image and the gradient together, both counters are increased.
+#[derive(derive_getters::Getters, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvaluationCounts {
+    f: usize,
+    g: usize,
+    hessian: usize,
+    hessian_vector_products: usize,
+    partial_derivatives: usize,
+}
+
+impl EvaluationCounts {
+    pub fn record(&mut self, eval: &FuncEvalMultivariate) {
+        self.f += 1;
+        self.g += 1;
+        if eval.hessian().is_some() {
+            self.hessian += 1;
+        }
+    }
+    pub fn record_value(&mut self) {
+        self.f += 1;
+    }
+    pub fn record_gradient(&mut self) {
+        self.g += 1;
+    }
+    pub fn record_hessian(&mut self) {
+        self.hessian += 1;
+    }
+    pub fn record_hessian_vector_product(&mut self) {
+        self.hessian_vector_products += 1;
+    }
+    pub fn record_partial_derivative(&mut self) {
+        self.partial_derivatives += 1;
+    }
+}
+
+#[derive(derive_getters::Getters, Debug, Clone)]
+pub struct MinimizationReport {
+    x: DVector<Floating>,
+    f: Floating,
+    gradient_norm: Floating, // euclidean norm of the gradient at x (NaN for derivative-free solvers)
+    projected_gradient_norm: Option<Floating>, // infinity norm of the projected gradient at x (only for solvers with bounds)
+    iterations: usize,
+    evaluations: EvaluationCounts,
+    wall_time: std::time::Duration,
+    termination_reason: TerminationReason,
+    regularization_shifts: Option<Vec<Floating>>, // shift added to the hessian at each iteration (only for solvers regularizing the hessian)
+    zeros: Option<usize>, // number of components of x that are exactly zero (only for solvers inducing sparsity)
+}
+
+impl MinimizationReport {
+    pub fn new(
+        x: DVector<Floating>,
+        eval: &FuncEvalMultivariate,
+        projected_gradient_norm: Option<Floating>,
+        iterations: usize,
+        evaluations: EvaluationCounts,
+        wall_time: std::time::Duration,
+        termination_reason: TerminationReason,
+    ) -> Self {
+        MinimizationReport {
+            x,
+            f: *eval.f(),
+            gradient_norm: eval.g().norm(),
+            projected_gradient_norm,
+            iterations,
+            evaluations,
+            wall_time,
+            termination_reason,
+            regularization_shifts: None,
+            zeros: None,
+        }
+    }
+    // report of the derivative-free solvers, which don't have the gradient at x
+    pub fn without_gradient(
+        x: DVector<Floating>,
+        f: Floating,
+        iterations: usize,
+        evaluations: EvaluationCounts,
+        wall_time: std::time::Duration,
+        termination_reason: TerminationReason,
+    ) -> Self {
+        MinimizationReport {
+            x,
+            f,
+            gradient_norm: Floating::NAN,
+            projected_gradient_norm: None,
+            iterations,
+            evaluations,
+            wall_time,
+            termination_reason,
+            regularization_shifts: None,
+            zeros: None,
+        }
+    }
+    pub fn with_regularization_shifts(
+        mut self,
+        regularization_shifts: Option<Vec<Floating>>,
+    ) -> Self {
+        self.regularization_shifts = regularization_shifts;
+        self
+    }
+    pub fn with_zeros(mut self, zeros: Option<usize>) -> Self {
+        self.zeros = zeros;
+        self
+    }
+}
+
+// std::time::Instant panics on wasm32-unknown-unknown, so in the browser we fall back to the javascript clock
+pub struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start: Floating, // milliseconds
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Stopwatch {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+            #[cfg(target_arch = "wasm32")]
+            start: js_sys::Date::now(),
+        }
+    }
+
+    pub fn elapsed(&self) -> std::time::Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.start.elapsed()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let elapsed_ms = (js_sys::Date::now() - self.start).max(0.0);
+            std::time::Duration::from_secs_f64(elapsed_ms / 1000.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod report_test {
+    use super::*;
+
+    #[test]
+    pub fn report_matches_solver_state() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+        let gamma = 1222.0;
+        let mut oracle_calls = 0;
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            oracle_calls += 1;
+            let f = 0.5 * (x[0].powi(2) + gamma * x[1].powi(2));
+            let g = DVector::from(vec![x[0], gamma * x[1]]);
+            let hessian = DMatrix::from_iterator(2, 2, vec![1.0, 0.0, 0.0, gamma]);
+            FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+        };
+
+        let mut ls = MoreThuente::default();
+        let tol = 1e-8;
+        let x_0 = DVector::from(vec![1.0, 1.0]);
+        let mut nt = Newton::new(tol, x_0);
+
+        let report = nt.minimize(&mut ls, oracle, 1000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+
+        assert_eq!(report.x(), nt.xk());
+        assert_eq!(report.iterations(), nt.k());
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::NewtonDecrement
+        );
+        assert_eq!(report.evaluations().f(), &oracle_calls);
+        assert_eq!(report.evaluations().g(), &oracle_calls);
+        assert_eq!(report.evaluations().hessian(), &oracle_calls);
+        assert!(report.projected_gradient_norm().is_none());
+        assert!(report.f().abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn report_projected_gradient_norm() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0].powi(2) + x[1].powi(2));
+            let g = DVector::from(vec![x[0], x[1]]);
+            (f, g).into()
+        };
+        let lower_bound = DVector::from_vec(vec![1.0, -Floating::INFINITY]);
+        let upper_bound = DVector::from_vec(vec![Floating::INFINITY, Floating::INFINITY]);
+        let mut ls = BackTrackingB::new(1e-4, 0.5, lower_bound.clone(), upper_bound.clone());
+        let x_0 = DVector::from(vec![5.0, 5.0]);
+        let mut gd = ProjectedGradientDescent::new(1e-8, x_0, lower_bound, upper_bound);
+
+        let report = gd.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+
+        // the solution lies on the active lower bound, so the gradient is not zero while the projected gradient is
+        assert!((report.x()[0] - 1.0).abs() < 1e-8);
+        assert!(report.gradient_norm() > &0.5);
+        assert!(report.projected_gradient_norm().unwrap() < 1e-8);
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::GradientTolerance
+        );
+    }
+}
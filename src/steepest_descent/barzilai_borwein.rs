@@ -0,0 +1,209 @@
+use super::*;
+
+// Barzilai-Borwein (spectral) gradient method for unconstrained problems ([Barzilai, J., & Borwein, J. M. (1988). Two-point step size gradient methods. IMA journal of numerical analysis]).
+// The direction is d_k = -alpha_k g_k, where alpha_k is the inverse of a Rayleigh quotient of the average hessian along the last step s_{k-1} = x_k - x_{k-1}, y_{k-1} = g_k - g_{k-1}, so it lies between the inverses of its largest and smallest eigenvalues (see BBStep). The method is not monotone, and it's meant to be paired with a non-monotone line search (GLLQuadratic or ZhangHager) that accepts the unit step most of the time ([Raydan, M. (1997). The Barzilai and Borwein gradient method for the large scale unconstrained minimization problem. SIAM Journal on Optimization]).
+// Safeguards: alpha_k is clamped to [alpha_min, alpha_max] and, when the curvature s^T y is not positive (nonconvex regions), it's reset to alpha_max. The first step is 1 / ||g_0||_inf, as in SpectralProjectedGradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BBStep {
+    // s^T s / s^T y (long step)
+    BB1,
+    // s^T y / y^T y (short step)
+    BB2,
+    // BB1 at odd iterations and BB2 at even ones ([Dai, Y. H., & Fletcher, R. (2005). Projected Barzilai-Borwein methods for large-scale box-constrained quadratic programming. Numerische Mathematik])
+    Alternating,
+    // adaptive BB (ABB): BB2 if BB2 / BB1 < kappa, BB1 otherwise ([Zhou, B., Gao, L., & Dai, Y. H. (2006). Gradient methods with adaptive step-sizes. Computational optimization and applications]). The ratio is the squared cosine between s and y, so the short step is taken when the gradient changed direction a lot.
+    Adaptive { kappa: Floating },
+}
+
+#[derive(derive_getters::Getters)]
+pub struct BarzilaiBorwein {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    step: BBStep,
+    alpha: Option<Floating>, // step of the current iteration (None before the first one)
+    alpha_min: Floating,
+    alpha_max: Floating,
+}
+
+impl BarzilaiBorwein {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        Self {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            step: BBStep::BB1,
+            alpha: None,
+            alpha_min: 1e-10,
+            alpha_max: 1e10,
+        }
+    }
+    pub fn with_step(mut self, step: BBStep) -> Self {
+        if let BBStep::Adaptive { kappa } = step {
+            assert!(
+                kappa > 0.0 && kappa < 1.0,
+                "kappa must be in the open interval (0, 1)"
+            );
+        }
+        self.step = step;
+        self
+    }
+    pub fn with_alphas(mut self, alpha_min: Floating, alpha_max: Floating) -> Self {
+        assert!(
+            alpha_min > 0.0 && alpha_min <= alpha_max,
+            "safeguards must satisfy 0 < alpha_min <= alpha_max"
+        );
+        self.alpha_min = alpha_min;
+        self.alpha_max = alpha_max;
+        self
+    }
+
+    // step for the next iteration from the last correction pair (k is the index of the next iteration)
+    fn compute_alpha(&self, s: &DVector<Floating>, y: &DVector<Floating>, k: usize) -> Floating {
+        let sy = s.dot(y);
+        if sy <= 0.0 {
+            debug!(target: "barzilai_borwein", "s^T y = {} <= 0. Resetting alpha to alpha_max", sy);
+            return self.alpha_max;
+        }
+        let bb1 = s.norm_squared() / sy;
+        let bb2 = sy / y.norm_squared();
+        let alpha = match self.step {
+            BBStep::BB1 => bb1,
+            BBStep::BB2 => bb2,
+            BBStep::Alternating if k % 2 == 1 => bb1,
+            BBStep::Alternating => bb2,
+            BBStep::Adaptive { kappa } if bb2 / bb1 < kappa => bb2,
+            BBStep::Adaptive { .. } => bb1,
+        };
+        alpha.clamp(self.alpha_min, self.alpha_max)
+    }
+}
+
+impl ComputeDirection for BarzilaiBorwein {
+    fn compute_direction(
+        &mut self,
+        eval: &FuncEvalMultivariate,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let alpha = *self.alpha.get_or_insert_with(|| {
+            (1. / eval.g().infinity_norm()).clamp(self.alpha_min, self.alpha_max)
+        });
+        Ok(-alpha * eval.g())
+    }
+}
+
+impl LineSearchSolver for BarzilaiBorwein {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().infinity_norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn setup(&mut self) {
+        self.alpha = None;
+    }
+
+    fn update_next_iterate<LS: LineSearch>(
+        &mut self,
+        line_search: &mut LS,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+        direction: &DVector<Floating>,
+        max_iter_line_search: usize,
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
+
+        debug!(target: "barzilai_borwein", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
+
+        let next_iterate = self.xk() + step * direction;
+        let s = &next_iterate - self.xk();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y = eval_next_iterate.g() - eval_x_k.g();
+
+        *self.xk_mut() = next_iterate;
+        self.alpha = Some(self.compute_alpha(&s, &y, self.k + 1));
+
+        Ok(Some(eval_next_iterate))
+    }
+}
+
+#[cfg(test)]
+mod barzilai_borwein_test {
+    use super::*;
+
+    #[test]
+    pub fn barzilai_borwein_ill_conditioned() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // ill conditioned quadratic (condition number 10^4)
+        let n = 50;
+        let diag = DVector::from_fn(n, |i, _| {
+            (10.0 as Floating).powf(4. * i as Floating / (n - 1) as Floating)
+        });
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+        let x_0 = DVector::from_element(n, 1.0);
+        let tol = 1e-8;
+
+        for step in [
+            BBStep::BB1,
+            BBStep::BB2,
+            BBStep::Alternating,
+            BBStep::Adaptive { kappa: 0.5 },
+        ] {
+            let mut solver = BarzilaiBorwein::new(tol, x_0.clone()).with_step(step);
+            let report = solver
+                .minimize(&mut GLLQuadratic::new(1e-4, 10), f_and_g, 10000, 100, None)
+                .unwrap();
+            println!("{:?}: {:?}", step, report);
+            assert!(report.x().amax() < 1e-8);
+            // the steepest descent would need about kappa * ln(1 / tol) ~ 10^5 iterations
+            assert!(*report.iterations() < 5000);
+        }
+    }
+
+    #[test]
+    pub fn barzilai_borwein_rosenbrock() {
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+            let g = DVector::from(vec![
+                -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+                200. * (x[1] - x[0].powi(2)),
+            ]);
+            (f, g).into()
+        };
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = BarzilaiBorwein::new(1e-6, x_0).with_step(BBStep::Adaptive { kappa: 0.5 });
+        let report = solver
+            .minimize(&mut ZhangHager::new(1e-4, 0.85), f_and_g, 10000, 100, None)
+            .unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-4);
+        assert!((report.x()[1] - 1.).abs() < 1e-4);
+    }
+}
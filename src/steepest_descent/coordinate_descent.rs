@@ -4,20 +4,388 @@ use super::*;
 
 // the family of steepest descent algorithms has (at most) linear convergence rate, and it's possible to see it by computing the trajectory of the upper bound of the log-suboptimality error ln(f(x_k)-p^*) where p^* is the optimal value of the problem. In particular, the convergence drops significantly if the upper bound of the condition number of the hessian matrix of the function is high (you can see it by solving the log-suboptimality error trajectory for the iteration number k). Recall that an upper bound on the condition number of the hessian can be derived by taking the ratio between the maximal and the minimal eigenvalue of the hessian matrix. This condition number can be also thought as the volume of the ellipsoid {x: x^T H x <= 1} where H is the hessian matrix of the function, which is always relatable to the volume of the euclidean unit ball gamma*sqrt{det (H^TH)} where gamma is the volume of the euclidean unit ball.
 
+// Coordinate descent updates a single coordinate (or a block of coordinates) per iteration, chosen according to CoordinateSelection, with a step given by CoordinateStep ([Wright, S. J. (2015). Coordinate descent algorithms. Mathematical programming] and [Nutini, J., Schmidt, M., Laradji, I., Friedlander, M., & Koepke, H. (2015). Coordinate descent converges faster with the Gauss-Southwell rule than random selection. ICML]).
+// The blocks are a partition of the indices (by default every coordinate is a block). With bounds, the updated coordinates are clipped to the box, so that the iterates are always feasible.
+// minimize evaluates the full gradient at every iterate, as any LineSearchSolver, which is required by the greedy rules. With the other rules minimize_with_partial_derivatives queries the oracle only for the partial derivatives of the selected block (the oracle must provide them, see Objective::partial_derivative), which is where coordinate methods pay off.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoordinateSelection {
+    // greedy: the block with the largest (projected) gradient norm
+    GaussSouthwell,
+    // greedy: the block with the largest ||g_B||^2 / L_B, which accounts for the different curvature of the blocks (requires lipschitz constants, see CoordinateStep)
+    GaussSouthwellLipschitz,
+    // the blocks in order
+    Cyclic,
+    // uniformly at random (seeded, so that the runs are reproducible)
+    Randomized { seed: u64 },
+    // at random with probability proportional to L_B ([Nesterov, Y. (2012). Efficiency of coordinate descent methods on huge-scale optimization problems. SIAM Journal on Optimization])
+    LipschitzSampling { seed: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoordinateStep {
+    // the step along -g_B is computed by the line search passed to minimize
+    LineSearch,
+    // -g_B / L_B, where the lipschitz constant of the block is the sum of the coordinate-wise lipschitz constants L_i of the partial derivatives (an upper bound of the largest eigenvalue of the block of the hessian for convex functions)
+    Lipschitz(DVector<Floating>),
+    // exact minimization along the block for quadratics with the given (positive definite) hessian A: A_BB d_B = -g_B. The lipschitz constants of the coordinates are the diagonal entries of A. With bounds the minimizer is clipped to the box, which is exact for single coordinates.
+    ExactQuadratic(DMatrix<Floating>),
+}
+
 #[derive(derive_getters::Getters)]
 pub struct CoordinateDescent {
     pub grad_tol: Floating,
     pub x: DVector<Floating>,
     pub k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    selection: CoordinateSelection,
+    step: CoordinateStep,
+    blocks: Vec<Vec<usize>>,
+    lower_bound: DVector<Floating>,
+    upper_bound: DVector<Floating>,
+    #[getter(skip)]
+    next_block: usize, // cursor of the cyclic rule
+    #[getter(skip)]
+    rng_state: u64, // state of the random rules
 }
 
 impl CoordinateDescent {
     pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        let n = x0.len();
         Self {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
+            selection: CoordinateSelection::GaussSouthwell,
+            step: CoordinateStep::LineSearch,
+            blocks: (0..n).map(|i| vec![i]).collect(),
+            lower_bound: DVector::from_element(n, Floating::NEG_INFINITY),
+            upper_bound: DVector::from_element(n, Floating::INFINITY),
+            next_block: 0,
+            rng_state: 0,
+        }
+    }
+    pub fn with_selection(mut self, selection: CoordinateSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+    pub fn with_step(mut self, step: CoordinateStep) -> Self {
+        let n = self.x.len();
+        match &step {
+            CoordinateStep::LineSearch => {}
+            CoordinateStep::Lipschitz(lipschitz) => {
+                assert!(
+                    lipschitz.len() == n,
+                    "lipschitz constants must have dimension n"
+                );
+                assert!(
+                    lipschitz.iter().all(|l| *l > 0.0),
+                    "lipschitz constants must be positive"
+                );
+            }
+            CoordinateStep::ExactQuadratic(hessian) => {
+                assert!(hessian.shape() == (n, n), "hessian must be n x n");
+                assert!(
+                    hessian.diagonal().iter().all(|a| *a > 0.0),
+                    "the diagonal of the hessian must be positive"
+                );
+            }
+        }
+        self.step = step;
+        self
+    }
+    // the blocks must be a partition of the indices 0..n
+    pub fn with_blocks(mut self, blocks: Vec<Vec<usize>>) -> Self {
+        let mut indices: Vec<usize> = blocks.iter().flatten().copied().collect();
+        indices.sort_unstable();
+        assert!(
+            indices == (0..self.x.len()).collect::<Vec<_>>()
+                && blocks.iter().all(|b| !b.is_empty()),
+            "blocks must be a partition of the indices"
+        );
+        self.blocks = blocks;
+        self
+    }
+    pub fn with_bounds(
+        mut self,
+        lower_bound: DVector<Floating>,
+        upper_bound: DVector<Floating>,
+    ) -> Self {
+        assert!(
+            lower_bound.len() == self.x.len() && upper_bound.len() == self.x.len(),
+            "bounds must have dimension n"
+        );
+        self.x = self.x.box_projection(&lower_bound, &upper_bound);
+        self.lower_bound = lower_bound;
+        self.upper_bound = upper_bound;
+        self
+    }
+
+    fn is_bounded(&self) -> bool {
+        self.lower_bound.iter().any(|l| l.is_finite())
+            || self.upper_bound.iter().any(|u| u.is_finite())
+    }
+
+    // gradient with the components that would push x out of the box set to zero (see HasProjectedGradient)
+    fn projected_partials(&self, g: &DVector<Floating>) -> DVector<Floating> {
+        let mut projected = g.clone();
+        for i in 0..g.len() {
+            if (self.x[i] <= self.lower_bound[i] && g[i] > 0.0)
+                || (self.x[i] >= self.upper_bound[i] && g[i] < 0.0)
+            {
+                projected[i] = 0.0;
+            }
+        }
+        projected
+    }
+
+    fn block_lipschitz(&self, block: &[usize]) -> Option<Floating> {
+        match &self.step {
+            CoordinateStep::LineSearch => None,
+            CoordinateStep::Lipschitz(lipschitz) => Some(block.iter().map(|&i| lipschitz[i]).sum()),
+            CoordinateStep::ExactQuadratic(hessian) => {
+                Some(block.iter().map(|&i| hessian[(i, i)]).sum())
+            }
+        }
+    }
+
+    // splitmix64: uniform in [0, 1)
+    fn next_random(&mut self) -> Floating {
+        self.rng_state = self.rng_state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        (z >> 11) as Floating / (1u64 << 53) as Floating
+    }
+
+    // index of the next block. The greedy rules need the full gradient
+    fn select_block(&mut self, g: Option<&DVector<Floating>>) -> Result<usize, SolverError> {
+        let n_blocks = self.blocks.len();
+        let missing_lipschitz = || {
+            SolverError::ErrorInputParams(
+                "the selection rule requires the lipschitz constants of the step".to_string(),
+            )
+        };
+        let missing_gradient = || {
+            SolverError::ErrorInputParams(
+                "greedy selection rules require the full gradient".to_string(),
+            )
+        };
+        match self.selection.clone() {
+            CoordinateSelection::GaussSouthwell | CoordinateSelection::GaussSouthwellLipschitz => {
+                let g = self.projected_partials(g.ok_or_else(missing_gradient)?);
+                let mut best = (0, Floating::NEG_INFINITY);
+                for (b, block) in self.blocks.iter().enumerate() {
+                    let mut score: Floating = block.iter().map(|&i| g[i] * g[i]).sum();
+                    if self.selection == CoordinateSelection::GaussSouthwellLipschitz {
+                        score /= self.block_lipschitz(block).ok_or_else(missing_lipschitz)?;
+                    }
+                    if score > best.1 {
+                        best = (b, score);
+                    }
+                }
+                Ok(best.0)
+            }
+            CoordinateSelection::Cyclic => {
+                let b = self.next_block;
+                self.next_block = (b + 1) % n_blocks;
+                Ok(b)
+            }
+            CoordinateSelection::Randomized { .. } => {
+                Ok(((self.next_random() * n_blocks as Floating) as usize).min(n_blocks - 1))
+            }
+            CoordinateSelection::LipschitzSampling { .. } => {
+                let weights = self
+                    .blocks
+                    .iter()
+                    .map(|block| self.block_lipschitz(block))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(missing_lipschitz)?;
+                let mut threshold = self.next_random() * weights.iter().sum::<Floating>();
+                for (b, w) in weights.iter().enumerate() {
+                    if threshold < *w {
+                        return Ok(b);
+                    }
+                    threshold -= w;
+                }
+                Ok(n_blocks - 1)
+            }
+        }
+    }
+
+    // direction over the whole space, non zero only on the block: with the LineSearch step it's the projected gradient direction, so that unit steps are feasible, otherwise the step is included and x + direction is the next iterate
+    fn block_direction(
+        &self,
+        block: &[usize],
+        g: &DVector<Floating>,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let g_block = DVector::from_fn(block.len(), |j, _| g[block[j]]);
+        let d_block = match &self.step {
+            CoordinateStep::LineSearch => -g_block,
+            CoordinateStep::Lipschitz(_) => {
+                -g_block / self.block_lipschitz(block).expect("lipschitz step")
+            }
+            CoordinateStep::ExactQuadratic(hessian) => {
+                let hessian_block = DMatrix::from_fn(block.len(), block.len(), |r, c| {
+                    hessian[(block[r], block[c])]
+                });
+                hessian_block
+                    .cholesky()
+                    .ok_or_else(|| {
+                        SolverError::AbnormalTermination(
+                            "block of the hessian not positive definite".to_string(),
+                        )
+                    })?
+                    .solve(&-g_block)
+            }
+        };
+        let mut direction = DVector::zeros(self.x.len());
+        for (j, &i) in block.iter().enumerate() {
+            let x_i = (self.x[i] + d_block[j]).clamp(self.lower_bound[i], self.upper_bound[i]);
+            direction[i] = x_i - self.x[i];
+        }
+        Ok(direction)
+    }
+
+    // partial derivatives at x with respect to the given indices, or an error if the oracle doesn't provide them
+    fn partial_derivatives(
+        oracle: &mut impl Objective,
+        x: &DVector<Floating>,
+        indices: impl Iterator<Item = usize>,
+    ) -> Result<DVector<Floating>, SolverError> {
+        let partial_derivatives = indices
+            .map(|i| oracle.partial_derivative(x, i))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                SolverError::ErrorInputParams(
+                    "the oracle doesn't provide partial derivatives (see Objective::partial_derivative)".to_string(),
+                )
+            })?;
+        Ok(DVector::from(partial_derivatives))
+    }
+
+    // Minimization querying the oracle only for the partial derivatives of the selected blocks (and for the image at the solution, for the report). The greedy rules and the LineSearch step are not available, since they require the full gradient and the image respectively.
+    // The convergence is checked once per epoch (a number of iterations equal to the number of blocks) on the partial derivatives computed during the epoch and, when it's met, confirmed by recomputing all of them at the current iterate. A stopping criterion, if any, is checked once per epoch as well, with the image and all the partial derivatives computed at the current iterate.
+    pub fn minimize_with_partial_derivatives(
+        &mut self,
+        oracle: impl Objective,
+        max_iter_solver: usize,
+        mut callback: Option<&mut dyn FnMut(&Self)>,
+    ) -> Result<MinimizationReport, SolverError> {
+        if matches!(
+            self.selection,
+            CoordinateSelection::GaussSouthwell | CoordinateSelection::GaussSouthwellLipschitz
+        ) {
+            return Err(SolverError::ErrorInputParams(
+                "greedy selection rules require the full gradient".to_string(),
+            ));
         }
+        if self.step == CoordinateStep::LineSearch {
+            return Err(SolverError::ErrorInputParams(
+                "partial derivatives require the Lipschitz or ExactQuadratic step".to_string(),
+            ));
+        }
+        let stopwatch = Stopwatch::start();
+        let mut oracle = CountedObjective::new(oracle);
+        let n = self.x.len();
+        let epoch = self.blocks.len();
+
+        self.k = 0;
+        let mut f_previous = None;
+        let mut x_previous_epoch = self.x.clone();
+        if let Some(criterion) = self.stopping_criterion_mut() {
+            criterion.reset();
+        }
+        self.setup();
+
+        let mut g = Self::partial_derivatives(&mut oracle, &self.x, 0..n)?;
+
+        while max_iter_solver > self.k {
+            if self.k.is_multiple_of(epoch) {
+                let termination_reason = match self.stopping_criterion.take() {
+                    Some(mut criterion) => {
+                        // the partial derivatives of the epoch were computed at different iterates: the criterion sees the gradient at the current one
+                        g = Self::partial_derivatives(&mut oracle, &self.x, 0..n)?;
+                        let eval: FuncEvalMultivariate = (oracle.value(&self.x), g.clone()).into();
+                        let step_norm = (self.k > 0).then(|| (&self.x - &x_previous_epoch).norm());
+                        let state = SolverState::new(
+                            self.k,
+                            &self.x,
+                            &eval,
+                            f_previous,
+                            step_norm,
+                            self.projected_gradient_norm(&eval),
+                            oracle.counts(),
+                            stopwatch.elapsed(),
+                        );
+                        let termination_reason = criterion.check(&state);
+                        self.stopping_criterion = Some(criterion);
+                        f_previous = Some(*eval.f());
+                        termination_reason
+                    }
+                    None if self.projected_partials(&g).infinity_norm() < self.grad_tol => {
+                        // the partial derivatives of the epoch were computed at different iterates: we confirm the convergence at the current one
+                        g = Self::partial_derivatives(&mut oracle, &self.x, 0..n)?;
+                        (self.projected_partials(&g).infinity_norm() < self.grad_tol)
+                            .then_some(TerminationReason::GradientTolerance)
+                    }
+                    None => None,
+                };
+                if let Some(termination_reason) = termination_reason {
+                    info!(
+                        target: "solver",
+                        "Minimization completed: {} in {} iterations",
+                        termination_reason,
+                        self.k
+                    );
+                    let eval: FuncEvalMultivariate = (oracle.value(&self.x), g).into();
+                    return Ok(MinimizationReport::new(
+                        self.x.clone(),
+                        &eval,
+                        self.projected_gradient_norm(&eval),
+                        self.k,
+                        oracle.counts(),
+                        stopwatch.elapsed(),
+                        termination_reason,
+                    ));
+                }
+                x_previous_epoch = self.x.clone();
+            }
+
+            let b = self.select_block(None)?;
+            let block = self.blocks[b].clone();
+            let g_block = Self::partial_derivatives(&mut oracle, &self.x, block.iter().copied())?;
+            for (&i, g_i) in block.iter().zip(g_block.iter()) {
+                g[i] = *g_i;
+            }
+            let direction = self.block_direction(&block, &g)?;
+            trace!(target: "coordinate_descent", "Block: {:?}, direction: {}", block, direction);
+            self.x += direction;
+
+            self.k += 1;
+            if let Some(callback) = callback.as_mut() {
+                callback(self);
+            }
+        }
+        warn!(target: "solver","Minimization completed: max iter reached during minimization");
+        Err(SolverError::MaxIterReached)
+    }
+}
+
+impl HasBounds for CoordinateDescent {
+    fn lower_bound(&self) -> &DVector<Floating> {
+        &self.lower_bound
+    }
+    fn upper_bound(&self) -> &DVector<Floating> {
+        &self.upper_bound
+    }
+    fn set_lower_bound(&mut self, lower_bound: DVector<Floating>) {
+        self.lower_bound = lower_bound;
+    }
+    fn set_upper_bound(&mut self, upper_bound: DVector<Floating>) {
+        self.upper_bound = upper_bound;
     }
 }
 
@@ -26,22 +394,18 @@ impl ComputeDirection for CoordinateDescent {
         &mut self,
         eval: &FuncEvalMultivariate,
     ) -> Result<DVector<Floating>, SolverError> {
-        // Differently from the gradient descent, here we pick the highest absolute value of the gradient and we multiply it with the vector of the canonical basis associated with its entry
-        let grad_k = eval.g();
-        let (position, max_value) =
-            grad_k
-                .iter()
-                .enumerate()
-                .fold((0, 0.0), |(idx, max), (i, g)| {
-                    if g.abs() > max {
-                        (i, g.abs())
-                    } else {
-                        (idx, max)
-                    }
-                });
-        let mut direction_k = DVector::zeros(grad_k.len());
-        direction_k[position] = -max_value.signum();
-        Ok(direction_k)
+        let b = self.select_block(Some(eval.g()))?;
+        let block = self.blocks[b].clone();
+        if self.step == CoordinateStep::LineSearch && block.len() == 1 && !self.is_bounded() {
+            // Differently from the gradient descent, here we multiply the sign of the selected entry of the gradient with the vector of the canonical basis associated with it (steepest descent in the l1 norm)
+            let mut direction_k = DVector::zeros(eval.g().len());
+            // a null partial derivative (possible with the non greedy rules) gives a null direction instead of the ascent direction e_i (signum of 0.0 is 1.0)
+            if eval.g()[block[0]] != 0.0 {
+                direction_k[block[0]] = -eval.g()[block[0]].signum();
+            }
+            return Ok(direction_k);
+        }
+        self.block_direction(&block, eval.g())
     }
 }
 
@@ -58,38 +422,62 @@ impl LineSearchSolver for CoordinateDescent {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
-        // we verify that the norm of the gradient is below the tolerance.
-        let grad = eval.g();
-        // we compute the infinity norm of the gradient
-        grad.iter()
-            .fold(Floating::NEG_INFINITY, |acc, x| x.abs().max(acc))
-            < self.grad_tol
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        // we verify that the infinity norm of the (projected) gradient is below the tolerance.
+        let grad_norm = self.projected_gradient(eval).infinity_norm();
+        (grad_norm < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        self.is_bounded()
+            .then(|| self.projected_gradient(eval).infinity_norm())
+    }
+    fn setup(&mut self) {
+        self.next_block = 0;
+        self.rng_state = match self.selection {
+            CoordinateSelection::Randomized { seed }
+            | CoordinateSelection::LipschitzSampling { seed } => seed,
+            _ => 0,
+        };
     }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        if self.step == CoordinateStep::LineSearch && direction.iter().all(|d| *d == 0.0) {
+            // the selected block is already stationary: the line search would only waste evaluations
+            debug!(target: "coordinate_descent", "Null direction: skipping the line search");
+            return Ok(Some((*eval_x_k.f(), eval_x_k.g().clone()).into()));
+        }
+        if self.step != CoordinateStep::LineSearch {
+            // the step is already included in the direction
+            debug!(target: "coordinate_descent", "ITERATE: {} + {} = {}", self.xk(), direction, self.xk() + direction);
+            *self.xk_mut() += direction;
+            return Ok(None);
+        }
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         debug!(target: "coordinate_descent", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
 
         let next_iterate = self.xk() + step * direction;
+        if self.is_bounded() {
+            // a step longer than one could leave the box: the evaluation of the line search doesn't refer to the next iterate anymore
+            *self.xk_mut() = next_iterate.box_projection(&self.lower_bound, &self.upper_bound);
+            return Ok(None);
+        }
 
         *self.xk_mut() = next_iterate;
 
-        Ok(())
+        Ok(eval_next_iterate)
     }
 }
 
@@ -196,4 +584,195 @@ mod steepest_descent_l1_test {
 
         assert!((eval.f() - 0.0).abs() < 1e-6);
     }
+
+    // f(x) = 1/2 x^T A x - b^T x, with partial derivatives computed from a single row of A
+    struct Quadratic {
+        a: DMatrix<Floating>,
+        b: DVector<Floating>,
+    }
+
+    impl Quadratic {
+        fn new(n: usize) -> Self {
+            // diagonally dominant, with curvatures ranging from 2 to 2 * 10^2
+            let a = DMatrix::from_fn(n, n, |i, j| {
+                if i == j {
+                    2.0 * (10.0 as Floating).powf(2.0 * i as Floating / (n - 1) as Floating)
+                } else {
+                    0.5 / (1.0 + (i as Floating - j as Floating).abs())
+                }
+            });
+            let b = DVector::from_fn(n, |i, _| (i as Floating).sin() * 10.0);
+            Quadratic { a, b }
+        }
+    }
+
+    impl Objective for Quadratic {
+        fn value_and_gradient(&mut self, x: &DVector<Floating>) -> FuncEvalMultivariate {
+            let ax = &self.a * x;
+            let f = 0.5 * x.dot(&ax) - self.b.dot(x);
+            (f, ax - &self.b).into()
+        }
+        fn partial_derivative(&mut self, x: &DVector<Floating>, i: usize) -> Option<Floating> {
+            Some(self.a.row(i).transpose().dot(x) - self.b[i])
+        }
+    }
+
+    #[test]
+    pub fn coordinate_descent_selection_rules() {
+        let n = 10;
+        let oracle = Quadratic::new(n);
+        let solution = oracle.a.clone().lu().solve(&oracle.b).unwrap();
+        let lipschitz = oracle.a.diagonal();
+
+        for selection in [
+            CoordinateSelection::GaussSouthwell,
+            CoordinateSelection::GaussSouthwellLipschitz,
+            CoordinateSelection::Cyclic,
+            CoordinateSelection::Randomized { seed: 7 },
+            CoordinateSelection::LipschitzSampling { seed: 7 },
+        ] {
+            let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+                .with_selection(selection.clone())
+                .with_step(CoordinateStep::Lipschitz(lipschitz.clone()));
+            let report = solver
+                .minimize(&mut NoSearch, Quadratic::new(n), 100000, 100, None)
+                .unwrap();
+            println!("{:?}: {} iterations", selection, report.iterations());
+            assert!((report.x() - &solution).amax() < 1e-8);
+        }
+
+        // the same seed gives the same run
+        let run = |seed| {
+            let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+                .with_selection(CoordinateSelection::Randomized { seed })
+                .with_step(CoordinateStep::Lipschitz(lipschitz.clone()));
+            *solver
+                .minimize(&mut NoSearch, Quadratic::new(n), 100000, 100, None)
+                .unwrap()
+                .iterations()
+        };
+        assert_eq!(run(3), run(3));
+
+        // the greedy rules need the full gradient
+        let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+            .with_step(CoordinateStep::Lipschitz(lipschitz.clone()));
+        assert!(solver
+            .minimize_with_partial_derivatives(Quadratic::new(n), 1000, None)
+            .is_err());
+
+        // a closure oracle doesn't provide partial derivatives
+        let mut quadratic = Quadratic::new(n);
+        let closure = |x: &DVector<Floating>| quadratic.value_and_gradient(x);
+        let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+            .with_selection(CoordinateSelection::Cyclic)
+            .with_step(CoordinateStep::Lipschitz(lipschitz));
+        assert!(matches!(
+            solver.minimize_with_partial_derivatives(closure, 1000, None),
+            Err(SolverError::ErrorInputParams(_))
+        ));
+    }
+
+    #[test]
+    pub fn block_coordinate_descent_partial_derivatives() {
+        let n = 12;
+        let oracle = Quadratic::new(n);
+        let solution = oracle.a.clone().lu().solve(&oracle.b).unwrap();
+        let blocks = vec![vec![0, 5, 7], vec![1, 2], vec![3, 4, 6, 8], vec![9, 10, 11]];
+
+        // exact block minimization with the full gradient
+        let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+            .with_selection(CoordinateSelection::Cyclic)
+            .with_blocks(blocks.clone())
+            .with_step(CoordinateStep::ExactQuadratic(oracle.a.clone()));
+        let report_full = solver
+            .minimize(&mut NoSearch, Quadratic::new(n), 100000, 100, None)
+            .unwrap();
+        assert!((report_full.x() - &solution).amax() < 1e-8);
+
+        // the same iterates, computed with partial derivatives only
+        let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+            .with_selection(CoordinateSelection::Cyclic)
+            .with_blocks(blocks)
+            .with_step(CoordinateStep::ExactQuadratic(oracle.a.clone()));
+        let report = solver
+            .minimize_with_partial_derivatives(Quadratic::new(n), 100000, None)
+            .unwrap();
+        println!("Report: {:?}", report);
+        assert!((report.x() - &solution).amax() < 1e-8);
+        assert_eq!(report.evaluations().g(), &0);
+        assert!(report.evaluations().partial_derivatives() > &0);
+        assert_eq!(report.evaluations().f(), &1);
+    }
+
+    #[test]
+    pub fn null_partial_derivative_line_search() {
+        // at x0 the partial derivative of the first coordinate is null: the cyclic rule selects it first, and no line search is performed along it
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * (x[0].powi(2) + 10. * x[1].powi(2));
+            let g = DVector::from(vec![x[0], 10. * x[1]]);
+            (f, g).into()
+        };
+        let mut ls = BackTracking::new(1e-4, 0.5);
+        let mut solver = CoordinateDescent::new(1e-8, DVector::from(vec![0.0, 1.0]))
+            .with_selection(CoordinateSelection::Cyclic);
+        let report = solver.minimize(&mut ls, f_and_g, 1000, 100, None).unwrap();
+        println!("Report: {:?}", report);
+        assert!(report.x().amax() < 1e-8);
+        assert!(report.evaluations().f() < &100);
+    }
+
+    #[test]
+    pub fn partial_derivatives_stopping_criterion() {
+        // the criterion is checked on the gradient at the current iterate, not on the partial derivatives collected during the epoch
+        let n = 10;
+        let oracle = Quadratic::new(n);
+        let mut solver = CoordinateDescent::new(1e-8, DVector::zeros(n))
+            .with_selection(CoordinateSelection::Randomized { seed: 5 })
+            .with_step(CoordinateStep::Lipschitz(oracle.a.diagonal()))
+            .with_stopping_criterion(GradientTolerance::new(1e-6));
+        let report = solver
+            .minimize_with_partial_derivatives(Quadratic::new(n), 100000, None)
+            .unwrap();
+        let g = Quadratic::new(n).gradient(report.x());
+        assert!(g.amax() < 1e-6);
+        assert!((report.gradient_norm() - g.norm()).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn bounded_coordinate_descent() {
+        let n = 10;
+        let oracle = Quadratic::new(n);
+        let lower_bound = DVector::from_element(n, -0.5);
+        let upper_bound = DVector::from_element(n, 0.5);
+
+        let mut reports = vec![];
+        for step in [
+            CoordinateStep::ExactQuadratic(oracle.a.clone()),
+            CoordinateStep::Lipschitz(oracle.a.diagonal()),
+        ] {
+            let mut solver = CoordinateDescent::new(1e-8, DVector::from_element(n, 3.0))
+                .with_selection(CoordinateSelection::Randomized { seed: 11 })
+                .with_step(step)
+                .with_bounds(lower_bound.clone(), upper_bound.clone());
+            let report = solver
+                .minimize_with_partial_derivatives(Quadratic::new(n), 100000, None)
+                .unwrap();
+            println!("Report: {:?}", report);
+            // the iterate is feasible and some bounds are active at the solution
+            assert!(report.x().iter().all(|x_i| (-0.5..=0.5).contains(x_i)));
+            assert!(report.x().iter().any(|x_i| x_i.abs() == 0.5));
+            assert!(report.projected_gradient_norm().unwrap() < 1e-8);
+            reports.push(report);
+        }
+        assert!((reports[0].x() - reports[1].x()).amax() < 1e-7);
+
+        // the greedy rule (on the projected gradient) converges to the same point
+        let mut solver = CoordinateDescent::new(1e-8, DVector::from_element(n, 3.0))
+            .with_step(CoordinateStep::ExactQuadratic(oracle.a.clone()))
+            .with_bounds(lower_bound, upper_bound);
+        let report = solver
+            .minimize(&mut NoSearch, Quadratic::new(n), 100000, 100, None)
+            .unwrap();
+        assert!((report.x() - reports[0].x()).amax() < 1e-7);
+    }
 }
@@ -0,0 +1,282 @@
+use super::*;
+
+// Online Scaling Gradient Method (OSGM) from [Gao, W., Chu, Y. C., Ye, Y., & Udell, M. (2024). Gradient Methods with Online Scaling. arXiv preprint arXiv:2411.01803].
+// The iterates are x_{k+1} = x_k - P_k g_k as in the PnormDescent (where P_k plays the role of inverse_p), but instead of being supplied up front the preconditioner is learned online: at each iteration P_k is updated with a step of online gradient descent on the hypergradient feedback
+//      h_k(P) = (f(x_k - P g_k) - f(x_k)) / ||g_k||^2,      grad_P h_k(P_k) = -g_{k+1} g_k^T / ||g_k||^2
+// which measures the decrease produced by the scaled gradient step, so that the method asymptotically performs as well as the best fixed preconditioner in hindsight. The online learner is AdaGrad (entrywise), which makes the learning rate much less sensitive to the scale of the problem.
+// The method is made monotone with null steps: when the trial point doesn't decrease the function the iterate is not updated, but the feedback is still used to learn P.
+// The step is the scaled gradient itself, so the method doesn't take a line search (see LineSearchFreeSolver).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnlineScaling {
+    // P diagonal: O(n) memory and time per iteration
+    Diagonal,
+    // P full matrix: O(n^2) memory and time per iteration
+    Full,
+}
+
+// Preconditioner learned by OSGM, together with the sums of the squared hypergradients used by AdaGrad
+#[derive(Debug, Clone)]
+pub enum Preconditioner {
+    Diagonal {
+        p: DVector<Floating>,
+        sum_squares: DVector<Floating>,
+    },
+    Full {
+        p: DMatrix<Floating>,
+        sum_squares: DMatrix<Floating>,
+    },
+}
+
+impl Preconditioner {
+    // alpha * I
+    pub fn new(scaling: OnlineScaling, n: usize, alpha: Floating) -> Self {
+        match scaling {
+            OnlineScaling::Diagonal => Preconditioner::Diagonal {
+                p: DVector::from_element(n, alpha),
+                sum_squares: DVector::zeros(n),
+            },
+            OnlineScaling::Full => Preconditioner::Full {
+                p: DMatrix::identity(n, n) * alpha,
+                sum_squares: DMatrix::zeros(n, n),
+            },
+        }
+    }
+
+    pub fn apply(&self, g: &DVector<Floating>) -> DVector<Floating> {
+        match self {
+            Preconditioner::Diagonal { p, .. } => p.component_mul(g),
+            Preconditioner::Full { p, .. } => p * g,
+        }
+    }
+
+    // the learned preconditioner as a matrix (e.g. to be used as inverse_p of PnormDescent)
+    pub fn matrix(&self) -> DMatrix<Floating> {
+        match self {
+            Preconditioner::Diagonal { p, .. } => DMatrix::from_diagonal(p),
+            Preconditioner::Full { p, .. } => p.clone(),
+        }
+    }
+
+    pub fn scale(&mut self, factor: Floating) {
+        match self {
+            Preconditioner::Diagonal { p, .. } => *p *= factor,
+            Preconditioner::Full { p, .. } => *p *= factor,
+        }
+    }
+
+    // AdaGrad step on the hypergradient -g_next g^T / ||g||^2. The diagonal preconditioner is kept non negative (projection of the online gradient step).
+    fn update(
+        &mut self,
+        g: &DVector<Floating>,
+        g_next: &DVector<Floating>,
+        learning_rate: Floating,
+    ) {
+        let gg = g.norm_squared();
+        let adagrad = |p: &mut Floating, sum_squares: &mut Floating, hypergradient: Floating| {
+            *sum_squares += hypergradient * hypergradient;
+            if *sum_squares > 0.0 {
+                *p -= learning_rate * hypergradient / sum_squares.sqrt();
+            }
+        };
+        match self {
+            Preconditioner::Diagonal { p, sum_squares } => {
+                for i in 0..p.len() {
+                    adagrad(&mut p[i], &mut sum_squares[i], -g_next[i] * g[i] / gg);
+                    p[i] = p[i].max(0.0);
+                }
+            }
+            Preconditioner::Full { p, sum_squares } => {
+                for j in 0..p.ncols() {
+                    for i in 0..p.nrows() {
+                        adagrad(
+                            &mut p[(i, j)],
+                            &mut sum_squares[(i, j)],
+                            -g_next[i] * g[j] / gg,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(derive_getters::Getters)]
+pub struct OSGM {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    scaling: OnlineScaling,
+    initial_scaling: Option<Floating>, // P_0 = initial_scaling * I (1 / ||g_0||_inf if None)
+    learning_rate: Option<Floating>,   // learning rate of AdaGrad (the initial scaling if None)
+    preconditioner: Option<Preconditioner>, // None before the first iteration
+    #[getter(skip)]
+    adagrad_learning_rate: Floating, // learning rate of the current run
+    null_steps: usize,
+}
+
+impl OSGM {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        Self {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            scaling: OnlineScaling::Diagonal,
+            initial_scaling: None,
+            learning_rate: None,
+            preconditioner: None,
+            adagrad_learning_rate: 0.0,
+            null_steps: 0,
+        }
+    }
+    pub fn with_scaling(mut self, scaling: OnlineScaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+    pub fn with_initial_scaling(mut self, initial_scaling: Floating) -> Self {
+        assert!(initial_scaling > 0.0, "initial scaling must be positive");
+        self.initial_scaling = Some(initial_scaling);
+        self
+    }
+    pub fn with_learning_rate(mut self, learning_rate: Floating) -> Self {
+        assert!(learning_rate > 0.0, "learning rate must be positive");
+        self.learning_rate = Some(learning_rate);
+        self
+    }
+}
+
+impl LineSearchFreeSolver for OSGM {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().infinity_norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn setup(&mut self) {
+        self.preconditioner = None;
+        self.null_steps = 0;
+    }
+
+    fn iterate(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+        oracle: &mut impl Objective,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let adagrad_learning_rate = &mut self.adagrad_learning_rate;
+        let preconditioner = self.preconditioner.get_or_insert_with(|| {
+            let alpha = self
+                .initial_scaling
+                .unwrap_or_else(|| 1. / eval_x_k.g().infinity_norm());
+            *adagrad_learning_rate = self.learning_rate.unwrap_or(alpha);
+            Preconditioner::new(self.scaling, self.x.len(), alpha)
+        });
+
+        let direction = -preconditioner.apply(eval_x_k.g());
+        let trial = &self.x + &direction;
+        let eval_trial = oracle.value_and_gradient(&trial);
+
+        if !eval_trial.f().is_finite() {
+            // the feedback is not available out of domain: we shrink the preconditioner
+            debug!(target: "osgm", "Trial point out of domain: halving the preconditioner");
+            preconditioner.scale(0.5);
+            self.null_steps += 1;
+            return Ok((*eval_x_k.f(), eval_x_k.g().clone()).into());
+        }
+
+        preconditioner.update(eval_x_k.g(), eval_trial.g(), self.adagrad_learning_rate);
+
+        if eval_trial.f() > eval_x_k.f() {
+            debug!(target: "osgm", "No decrease at the trial point: null step");
+            self.null_steps += 1;
+            return Ok((*eval_x_k.f(), eval_x_k.g().clone()).into());
+        }
+        debug!(target: "osgm", "ITERATE: {} + {} = {}", self.x, direction, trial);
+        self.x = trial;
+        Ok(eval_trial)
+    }
+}
+
+#[cfg(test)]
+mod osgm_test {
+    use super::*;
+
+    #[test]
+    pub fn osgm_ill_conditioned() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        // ill conditioned quadratic (condition number 10^3) with diagonal hessian
+        let n = 20;
+        let diag = DVector::from_fn(n, |i, _| {
+            (10.0 as Floating).powf(3. * i as Floating / (n - 1) as Floating)
+        });
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = x.component_mul(&diag);
+            let f = 0.5 * x.dot(&g);
+            (f, g).into()
+        };
+        let x_0 = DVector::from_element(n, 1.0);
+        let tol = 1e-8;
+
+        let mut solver = OSGM::new(tol, x_0.clone());
+        let report = solver.minimize(f_and_g, 100000, None).unwrap();
+        println!("Report: {:?}", report);
+        println!(
+            "Null steps: {} iters {}",
+            solver.null_steps(),
+            report.iterations()
+        );
+        assert!(report.x().amax() < 1e-8);
+
+        // the gradient descent with a backtracking line search needs many more iterations
+        let mut gd = GradientDescent::new(tol, x_0);
+        let gd_iterations = gd
+            .minimize(
+                &mut BackTracking::new(1e-4, 0.5),
+                f_and_g,
+                100000,
+                100,
+                None,
+            )
+            .map(|r| *r.iterations())
+            .unwrap_or(100000);
+        println!("Gradient descent: {}", gd_iterations);
+        assert!(*report.iterations() < gd_iterations);
+    }
+
+    #[test]
+    pub fn osgm_full_preconditioner() {
+        // quadratic with a non diagonal hessian A = [[3, 1, 0], [1, 2, 0.5], [0, 0.5, 1]] * 10
+        let a = DMatrix::from_row_slice(3, 3, &[30., 10., 0., 10., 20., 5., 0., 5., 10.]);
+        let b = DVector::from(vec![1., -2., 3.]);
+        let f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let g = &a * x - &b;
+            let f = 0.5 * x.dot(&(&a * x)) - b.dot(x);
+            (f, g).into()
+        };
+        let x_0 = DVector::zeros(3);
+        let mut solver = OSGM::new(1e-8, x_0).with_scaling(OnlineScaling::Full);
+        let report = solver.minimize(f_and_g, 100000, None).unwrap();
+        println!("Report: {:?}", report);
+
+        let solution = a.clone().lu().solve(&b).unwrap();
+        assert!((report.x() - solution).amax() < 1e-7);
+        // the learned preconditioner can be reused by the PnormDescent
+        let inverse_p = solver.preconditioner().as_ref().unwrap().matrix();
+        assert_eq!(inverse_p.shape(), (3, 3));
+    }
+}
@@ -13,6 +13,8 @@ pub struct PnormDescent {
     pub grad_tol: Floating,
     pub x: DVector<Floating>,
     pub k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     pub inverse_p: DMatrix<Floating>,
 }
 
@@ -22,6 +24,7 @@ impl PnormDescent {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
             inverse_p,
         }
     }
@@ -49,30 +52,30 @@ impl LineSearchSolver for PnormDescent {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // we verify that the norm of the gradient is below the tolerance.
         let grad = eval.g();
         // we compute the infinity norm of the gradient
-        grad.iter()
-            .fold(Floating::NEG_INFINITY, |acc, x| x.abs().max(acc))
-            < self.grad_tol
+        let grad_norm = grad
+            .iter()
+            .fold(Floating::NEG_INFINITY, |acc, x| x.abs().max(acc));
+        (grad_norm < self.grad_tol).then_some(TerminationReason::GradientTolerance)
     }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         debug!(target: "pnorm_descent", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
 
@@ -80,7 +83,7 @@ impl LineSearchSolver for PnormDescent {
 
         *self.xk_mut() = next_iterate;
 
-        Ok(())
+        Ok(eval_next_iterate)
     }
 }
 
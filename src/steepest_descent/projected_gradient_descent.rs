@@ -7,6 +7,8 @@ pub struct ProjectedGradientDescent {
     grad_tol: Floating,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
 }
@@ -25,6 +27,7 @@ impl ProjectedGradientDescent {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
             lower_bound,
             upper_bound,
             // pg,
@@ -73,30 +76,32 @@ impl LineSearchSolver for ProjectedGradientDescent {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         // we verify that the norm of the gradient is below the tolerance. If the projected gradient is available, then it means that we are in a constrained optimization setting and we verify if it is zero since this is equivalent to first order conditions of optimality in the setting of optimization with simple bounds (Theorem 12.3 from [Neculai Andrei, 2022])
 
         let proj_grad = self.projected_gradient(eval);
         // warn!(target: "projected_gradient_descent", "Projected gradient: {:?}", proj_grad);
         // we compute the infinity norm of the projected gradient
-        proj_grad.infinity_norm() < self.grad_tol
+        (proj_grad.infinity_norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
     }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         debug!(target: "projected_gradient_descent", "ITERATE: {} + {} * {} = {}", self.xk(), step, direction, self.xk() + step * direction);
 
@@ -104,7 +109,7 @@ impl LineSearchSolver for ProjectedGradientDescent {
 
         *self.xk_mut() = next_iterate;
 
-        Ok(())
+        Ok(eval_next_iterate)
     }
 }
 #[cfg(test)]
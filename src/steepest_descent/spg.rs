@@ -3,7 +3,7 @@
 // The spectral projected gradient follows the same approach of the projected gradient method, but:
 // - It rescales the descent direction via a safeguarded Barzila-Borwein scalar, that is between the min and max eigenvalues of the average hessian between x_k and x_k + ts_k (hence the ``spectral'' denomination)
 
-// - The algorithm is typically paired with a non-monotone line search (quadratic or cubic) as that one descibed in [Grippo, Lampariello, Lucidi, 1986] because sometimes enforcing the sufficient decrease condition, which is typical in armijo line search, can be too restrictive. Notice that this kind of line-search embeds the monotone line-search by simply setting to 1 the look-back parameter when evaluating the armijo condition.
+// - The algorithm is typically paired with a non-monotone line search (quadratic or cubic) as that one descibed in [Grippo, Lampariello, Lucidi, 1986] because sometimes enforcing the sufficient decrease condition, which is typical in armijo line search, can be too restrictive. Notice that this kind of line-search embeds the monotone line-search by simply setting to 1 the look-back parameter when evaluating the armijo condition. The averaged reference value of [Zhang, Hager, 2004] (see ZhangHager) is a smoother alternative to the max of the last m function values.
 
 use super::*;
 
@@ -12,6 +12,8 @@ pub struct SpectralProjectedGradient {
     grad_tol: Floating,
     x: DVector<Floating>,
     k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
     lower_bound: DVector<Floating>,
     upper_bound: DVector<Floating>,
     lambda: Floating,
@@ -28,7 +30,7 @@ impl SpectralProjectedGradient {
     pub fn new(
         grad_tol: Floating,
         x0: DVector<Floating>,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         lower_bound: DVector<Floating>,
         upper_bound: DVector<Floating>,
     ) -> Self {
@@ -37,8 +39,8 @@ impl SpectralProjectedGradient {
         let lambda_max = 1e3;
 
         // we initialize lambda0 as equation 8 from [Birgin, Martínez, Raydan, 2014]
-        let eval0 = oracle(&x0);
-        let direction0 = &x0 - eval0.g();
+        let g0 = oracle.gradient(&x0);
+        let direction0 = &x0 - g0;
         let direction0 = direction0.box_projection(&lower_bound, &upper_bound);
         let direction0 = direction0 - &x0;
         let lambda = (1. / direction0.infinity_norm())
@@ -49,6 +51,7 @@ impl SpectralProjectedGradient {
             grad_tol,
             x: x0,
             k: 0,
+            stopping_criterion: None,
             lower_bound,
             upper_bound,
             lambda,
@@ -86,9 +89,13 @@ impl ComputeDirection for SpectralProjectedGradient {
 }
 
 impl LineSearchSolver for SpectralProjectedGradient {
-    fn has_converged(&self, eval: &FuncEvalMultivariate) -> bool {
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
         let projected_gradient = self.projected_gradient(eval);
-        projected_gradient.infinity_norm() < self.grad_tol
+        (projected_gradient.infinity_norm() < self.grad_tol)
+            .then_some(TerminationReason::GradientTolerance)
+    }
+    fn projected_gradient_norm(&self, eval: &FuncEvalMultivariate) -> Option<Floating> {
+        Some(self.projected_gradient(eval).infinity_norm())
     }
     fn xk(&self) -> &DVector<Floating> {
         &self.x
@@ -102,22 +109,21 @@ impl LineSearchSolver for SpectralProjectedGradient {
     fn k_mut(&mut self) -> &mut usize {
         &mut self.k
     }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
 
     fn update_next_iterate<LS: LineSearch>(
         &mut self,
         line_search: &mut LS,
         eval_x_k: &FuncEvalMultivariate, //eval: &FuncEvalMultivariate,
-        oracle: &mut impl FnMut(&DVector<Floating>) -> FuncEvalMultivariate,
+        oracle: &mut impl Objective,
         direction: &DVector<Floating>,
         max_iter_line_search: usize,
-    ) -> Result<(), SolverError> {
-        let step = line_search.compute_step_len(
-            self.xk(),
-            eval_x_k,
-            direction,
-            oracle,
-            max_iter_line_search,
-        );
+    ) -> Result<Option<FuncEvalMultivariate>, SolverError> {
+        let (step, eval_next_iterate) = line_search
+            .compute_step_len(self.xk(), eval_x_k, direction, oracle, max_iter_line_search)
+            .dissolve();
 
         let xk = self.xk(); //immutable borrow
 
@@ -127,7 +133,10 @@ impl LineSearchSolver for SpectralProjectedGradient {
 
         // we compute the correction terms:
         let s_k = &next_iterate - xk;
-        let y_k = oracle(&next_iterate).g() - eval_x_k.g();
+        // we reuse the evaluation of the line search at the accepted point, if available
+        let eval_next_iterate =
+            eval_next_iterate.unwrap_or_else(|| oracle.value_and_gradient(&next_iterate));
+        let y_k = eval_next_iterate.g() - eval_x_k.g();
 
         *self.xk_mut() = next_iterate;
 
@@ -136,11 +145,11 @@ impl LineSearchSolver for SpectralProjectedGradient {
         if skyk <= 0. {
             debug!(target: "spectral_projected_gradient", "skyk = {} <= 0. Resetting lambda to lambda_max", skyk);
             self.lambda = self.lambda_max;
-            return Ok(());
+            return Ok(Some(eval_next_iterate));
         }
         let sksk = s_k.dot(&s_k);
         self.lambda = (sksk / skyk).min(self.lambda_max).max(self.lambda_min);
-        Ok(())
+        Ok(Some(eval_next_iterate))
     }
 }
 
@@ -202,4 +211,29 @@ mod spg_test {
         let convergence = gd.has_converged(&eval);
         println!("Convergence: {:?}", convergence);
     }
+
+    #[test]
+    pub fn constrained_spg_zhang_hager() {
+        let gamma = 1e4;
+        let mut f_and_g = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = 0.5 * ((x[0] - 3.).powi(2) + gamma * x[1].powi(2) + 10. * x[2].powi(2));
+            let g = DVector::from(vec![x[0] - 3., gamma * x[1], 10. * x[2]]);
+            (f, g).into()
+        };
+        let lower_bounds = DVector::from_vec(vec![-1., 2., -Floating::INFINITY]);
+        let upper_bounds = DVector::from_vec(vec![1., Floating::INFINITY, Floating::INFINITY]);
+        let mut ls = ZhangHager::new(1e-4, 0.85);
+
+        let tol = 1e-10;
+        let x_0 = DVector::from(vec![0.5, 152.0, -7.0]);
+        let mut spg =
+            SpectralProjectedGradient::new(tol, x_0, &mut f_and_g, lower_bounds, upper_bounds);
+
+        let report = spg.minimize(&mut ls, f_and_g, 10000, 100, None).unwrap();
+
+        println!("Report: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-8);
+        assert!((report.x()[1] - 2.).abs() < 1e-8);
+        assert!(report.x()[2].abs() < 1e-8);
+    }
 }
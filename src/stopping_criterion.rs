@@ -0,0 +1,320 @@
+use super::*;
+
+// Composable stopping criteria. By default every solver uses its own convergence test (see LineSearchSolver::has_converged), but when a criterion is attached via LineSearchSolver::with_stopping_criterion it replaces the built-in test. This allows comparing different solvers with the very same tolerance semantics.
+
+// Snapshot of the state of the solver at the current iterate, handed to the stopping criteria
+#[derive(derive_getters::Getters)]
+pub struct SolverState<'a> {
+    k: usize,
+    x_k: &'a DVector<Floating>,
+    eval_x_k: &'a FuncEvalMultivariate,
+    f_previous: Option<Floating>, // image at the previous iterate (None at the first iteration)
+    step_norm: Option<Floating>,  // euclidean norm of x_k - x_{k-1} (None at the first iteration)
+    projected_gradient_norm: Option<Floating>, // only for solvers with bounds
+    evaluations: EvaluationCounts,
+    elapsed: std::time::Duration,
+}
+
+impl<'a> SolverState<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        k: usize,
+        x_k: &'a DVector<Floating>,
+        eval_x_k: &'a FuncEvalMultivariate,
+        f_previous: Option<Floating>,
+        step_norm: Option<Floating>,
+        projected_gradient_norm: Option<Floating>,
+        evaluations: EvaluationCounts,
+        elapsed: std::time::Duration,
+    ) -> Self {
+        SolverState {
+            k,
+            x_k,
+            eval_x_k,
+            f_previous,
+            step_norm,
+            projected_gradient_norm,
+            evaluations,
+            elapsed,
+        }
+    }
+
+    // first order optimality measure: the infinity norm of the projected gradient for solvers with bounds, the infinity norm of the gradient otherwise
+    pub fn first_order_measure(&self) -> Floating {
+        self.projected_gradient_norm
+            .unwrap_or_else(|| self.eval_x_k.g().infinity_norm())
+    }
+}
+
+pub trait StoppingCriterion {
+    // invoked once at the beginning of every minimization, so that stateful criteria can be reused across runs
+    fn reset(&mut self) {}
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason>;
+}
+
+// infinity norm of the (projected) gradient below the tolerance
+pub struct GradientTolerance {
+    tol: Floating,
+}
+impl GradientTolerance {
+    pub fn new(tol: Floating) -> Self {
+        GradientTolerance { tol }
+    }
+}
+impl StoppingCriterion for GradientTolerance {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        (state.first_order_measure() < self.tol).then_some(TerminationReason::GradientTolerance)
+    }
+}
+
+// infinity norm of the (projected) gradient below a fraction of the one at the initial point
+pub struct RelativeGradientNorm {
+    tol: Floating,
+    initial_measure: Option<Floating>,
+}
+impl RelativeGradientNorm {
+    pub fn new(tol: Floating) -> Self {
+        RelativeGradientNorm {
+            tol,
+            initial_measure: None,
+        }
+    }
+}
+impl StoppingCriterion for RelativeGradientNorm {
+    fn reset(&mut self) {
+        self.initial_measure = None;
+    }
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        let measure = state.first_order_measure();
+        let initial_measure = *self.initial_measure.get_or_insert(measure);
+        (measure <= self.tol * initial_measure)
+            .then_some(TerminationReason::RelativeGradientTolerance)
+    }
+}
+
+// |f_k - f_{k-1}| below the tolerance
+pub struct AbsoluteFunctionChange {
+    tol: Floating,
+}
+impl AbsoluteFunctionChange {
+    pub fn new(tol: Floating) -> Self {
+        AbsoluteFunctionChange { tol }
+    }
+}
+impl StoppingCriterion for AbsoluteFunctionChange {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        let f_previous = state.f_previous?;
+        ((state.eval_x_k.f() - f_previous).abs() < self.tol)
+            .then_some(TerminationReason::AbsoluteFunctionChange)
+    }
+}
+
+// |f_k - f_{k-1}| / max(|f_k|, |f_{k-1}|, 1) below the tolerance (same scaling of the factr test in L-BFGS-B)
+pub struct RelativeFunctionChange {
+    tol: Floating,
+}
+impl RelativeFunctionChange {
+    pub fn new(tol: Floating) -> Self {
+        RelativeFunctionChange { tol }
+    }
+}
+impl StoppingCriterion for RelativeFunctionChange {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        let f_previous = state.f_previous?;
+        let f = state.eval_x_k.f();
+        let scale = f.abs().max(f_previous.abs()).max(1.0);
+        ((f - f_previous).abs() / scale < self.tol)
+            .then_some(TerminationReason::RelativeFunctionChange)
+    }
+}
+
+// ||x_k - x_{k-1}|| below the tolerance
+pub struct StepSizeTolerance {
+    tol: Floating,
+}
+impl StepSizeTolerance {
+    pub fn new(tol: Floating) -> Self {
+        StepSizeTolerance { tol }
+    }
+}
+impl StoppingCriterion for StepSizeTolerance {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        let step_norm = state.step_norm?;
+        (step_norm < self.tol).then_some(TerminationReason::StepSizeTolerance)
+    }
+}
+
+// number of function evaluations (also the ones performed by the line search) reached the budget
+pub struct MaxEvaluations {
+    max_evaluations: usize,
+}
+impl MaxEvaluations {
+    pub fn new(max_evaluations: usize) -> Self {
+        MaxEvaluations { max_evaluations }
+    }
+}
+impl StoppingCriterion for MaxEvaluations {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        (state.evaluations.f() >= &self.max_evaluations)
+            .then_some(TerminationReason::MaxEvaluations)
+    }
+}
+
+// elapsed time since the beginning of the minimization exceeded the budget
+pub struct WallClockBudget {
+    budget: std::time::Duration,
+}
+impl WallClockBudget {
+    pub fn new(budget: std::time::Duration) -> Self {
+        WallClockBudget { budget }
+    }
+}
+impl StoppingCriterion for WallClockBudget {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        (state.elapsed >= self.budget).then_some(TerminationReason::WallClockBudget)
+    }
+}
+
+// image of the current iterate is below the target value
+pub struct TargetObjective {
+    target: Floating,
+}
+impl TargetObjective {
+    pub fn new(target: Floating) -> Self {
+        TargetObjective { target }
+    }
+}
+impl StoppingCriterion for TargetObjective {
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        (state.eval_x_k.f() <= &self.target).then_some(TerminationReason::TargetObjective)
+    }
+}
+
+// Stops as soon as one of the criteria is met. Builder pattern
+#[derive(Default)]
+pub struct AnyOf {
+    criteria: Vec<Box<dyn StoppingCriterion>>,
+}
+impl AnyOf {
+    pub fn with(mut self, criterion: impl StoppingCriterion + 'static) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+impl StoppingCriterion for AnyOf {
+    fn reset(&mut self) {
+        self.criteria.iter_mut().for_each(|c| c.reset());
+    }
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        // every criterion is checked (no short circuit) so that stateful criteria always see the whole trajectory
+        self.criteria
+            .iter_mut()
+            .map(|c| c.check(state))
+            .fold(None, |acc, reason| acc.or(reason))
+    }
+}
+
+// Stops only when all the criteria are met, returning the termination reason of the first one. Builder pattern
+#[derive(Default)]
+pub struct AllOf {
+    criteria: Vec<Box<dyn StoppingCriterion>>,
+}
+impl AllOf {
+    pub fn with(mut self, criterion: impl StoppingCriterion + 'static) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+impl StoppingCriterion for AllOf {
+    fn reset(&mut self) {
+        self.criteria.iter_mut().for_each(|c| c.reset());
+    }
+    fn check(&mut self, state: &SolverState) -> Option<TerminationReason> {
+        let reasons = self
+            .criteria
+            .iter_mut()
+            .map(|c| c.check(state))
+            .collect::<Vec<_>>();
+        if reasons.is_empty() || reasons.iter().any(|r| r.is_none()) {
+            return None;
+        }
+        reasons[0]
+    }
+}
+
+#[cfg(test)]
+mod stopping_criterion_test {
+    use super::*;
+
+    fn f_and_g(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let gamma = 90.0;
+        let f = 0.5 * (x[0].powi(2) + gamma * x[1].powi(2));
+        let g = DVector::from(vec![x[0], gamma * x[1]]);
+        (f, g).into()
+    }
+
+    #[test]
+    pub fn same_criterion_across_solvers() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+        let criterion = || RelativeGradientNorm::new(1e-10);
+
+        let mut gd = GradientDescent::new(1e-12, x_0.clone()).with_stopping_criterion(criterion());
+        let report_gd = gd
+            .minimize(&mut MoreThuente::default(), f_and_g, 10000, 100, None)
+            .unwrap();
+
+        let mut bfgs = BFGS::new(1e-12, x_0.clone()).with_stopping_criterion(criterion());
+        let report_bfgs = bfgs
+            .minimize(&mut MoreThuente::default(), f_and_g, 10000, 100, None)
+            .unwrap();
+
+        let initial_measure = f_and_g(&x_0).g().infinity_norm();
+        for report in [report_gd, report_bfgs] {
+            assert_eq!(
+                report.termination_reason(),
+                &TerminationReason::RelativeGradientTolerance
+            );
+            let measure = f_and_g(report.x()).g().infinity_norm();
+            assert!(measure <= 1e-10 * initial_measure);
+        }
+    }
+
+    #[test]
+    pub fn any_and_all_criteria() {
+        let x_0 = DVector::from(vec![180.0, 152.0]);
+
+        // the evaluation budget is hit long before the gradient tolerance
+        let criterion = AnyOf::default()
+            .with(GradientTolerance::new(1e-12))
+            .with(MaxEvaluations::new(10));
+        let mut gd = GradientDescent::new(1e-12, x_0.clone()).with_stopping_criterion(criterion);
+        let report = gd
+            .minimize(&mut BackTracking::new(1e-4, 0.5), f_and_g, 10000, 100, None)
+            .unwrap();
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::MaxEvaluations
+        );
+        assert!(report.evaluations().f() >= &10);
+
+        // the target objective alone is met quickly, but we require also a small step
+        let criterion = AllOf::default()
+            .with(TargetObjective::new(1e-3))
+            .with(StepSizeTolerance::new(1e-8));
+        let mut gd = GradientDescent::new(1e-12, x_0).with_stopping_criterion(criterion);
+        let report = gd
+            .minimize(&mut MoreThuente::default(), f_and_g, 10000, 100, None)
+            .unwrap();
+        assert_eq!(
+            report.termination_reason(),
+            &TerminationReason::TargetObjective
+        );
+        assert!(report.f() <= &1e-3);
+    }
+}
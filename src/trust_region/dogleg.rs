@@ -0,0 +1,85 @@
+use super::*;
+
+// Dogleg method (section 4.1 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// The step is the minimizer of the model along the piecewise linear path from the origin to the unconstrained minimizer along the steepest descent direction p_U = -(g^T g / g^T B g) g, and then to the full newton step p_B = -B^{-1} g.
+// The path requires B positive definite: when the cholesky factorization fails, the step falls back to the Cauchy point (equation 4.12), which doesn't require any assumption on B.
+#[derive(Debug, Clone, Default)]
+pub struct Dogleg;
+
+impl Dogleg {
+    pub fn cauchy_point(
+        g: &DVector<Floating>,
+        hessian: &DMatrix<Floating>,
+        radius: Floating,
+    ) -> DVector<Floating> {
+        let g_norm = g.norm();
+        let gbg = g.dot(&(hessian * g));
+        let tau = if gbg <= 0.0 {
+            1.0
+        } else {
+            (g_norm.powi(3) / (radius * gbg)).min(1.0)
+        };
+        -(tau * radius / g_norm) * g
+    }
+}
+
+impl TrustRegionSubproblem for Dogleg {
+    fn solve(
+        &mut self,
+        g: &DVector<Floating>,
+        hessian: &DMatrix<Floating>,
+        radius: Floating,
+    ) -> DVector<Floating> {
+        if g.norm() == 0.0 {
+            return DVector::zeros(g.len());
+        }
+        let Some(cholesky) = hessian.clone().cholesky() else {
+            debug!(target: "dogleg", "Hessian not positive definite: using the Cauchy point");
+            return Self::cauchy_point(g, hessian, radius);
+        };
+
+        let p_b = -cholesky.solve(g);
+        if p_b.norm() <= radius {
+            return p_b;
+        }
+
+        let p_u = -(g.norm_squared() / g.dot(&(hessian * g))) * g;
+        let p_u_norm = p_u.norm();
+        if p_u_norm >= radius {
+            return (radius / p_u_norm) * p_u;
+        }
+
+        // second leg of the path: ||p_U + tau * (p_B - p_U)|| = radius with tau in [0, 1]
+        let d = p_b - &p_u;
+        let (_, tau) = boundary_intersection(&p_u, &d, radius);
+        p_u + tau * d
+    }
+}
+
+#[cfg(test)]
+mod dogleg_test {
+    use super::*;
+
+    #[test]
+    pub fn dogleg_path() {
+        let hessian = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, 10.0]);
+        let g = DVector::from(vec![1.0, 1.0]);
+        let mut dogleg = Dogleg;
+
+        // newton step inside the trust region
+        let p = dogleg.solve(&g, &hessian, 10.0);
+        assert!((p - DVector::from(vec![-1.0, -0.1])).norm() < 1e-12);
+
+        // the step is on the boundary and its model value lies between the ones of the Cauchy point and of the newton step
+        let radius = 0.5;
+        let p = dogleg.solve(&g, &hessian, radius);
+        assert!((p.norm() - radius).abs() < 1e-12);
+        let cauchy_point = Dogleg::cauchy_point(&g, &hessian, radius);
+        assert!(model_reduction(&g, &hessian, &p) <= model_reduction(&g, &hessian, &cauchy_point));
+
+        // indefinite hessian: the Cauchy point moves to the boundary along the steepest descent direction
+        let hessian = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, -10.0]);
+        let p = dogleg.solve(&g, &hessian, radius);
+        assert!((p + radius / g.norm() * &g).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,309 @@
+use super::*;
+
+pub mod dogleg;
+pub use dogleg::*;
+pub mod sr1_trust_region;
+pub use sr1_trust_region::*;
+pub mod steihaug;
+pub use steihaug::*;
+pub mod trust_region_newton;
+pub use trust_region_newton::*;
+
+// Trust region methods (chapter 4 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// Instead of choosing a direction and then a step length along it (as the line search solvers do), the step p_k is the (approximate) minimizer of the quadratic model
+//      m_k(p) = f_k + g_k^T p + 0.5 * p^T B_k p      subject to ||p|| <= radius_k
+// The step is accepted if the ratio between actual and predicted reduction is large enough, and the radius is updated according to the same ratio. Since the model is minimized only in a ball, B_k doesn't need to be positive definite: the methods behave well also in nonconvex regions, where the newton direction might not be a descent direction.
+
+// Approximate solver of the trust region subproblem (analogous to LineSearch for line search solvers)
+pub trait TrustRegionSubproblem {
+    fn solve(
+        &mut self,
+        g: &DVector<Floating>,       // gradient at the current iterate
+        hessian: &DMatrix<Floating>, // hessian of the model (not necessarily positive definite)
+        radius: Floating,            // trust region radius
+    ) -> DVector<Floating>;
+}
+
+// value of the quadratic model (minus f_k) at p
+pub fn model_reduction(
+    g: &DVector<Floating>,
+    hessian: &DMatrix<Floating>,
+    p: &DVector<Floating>,
+) -> Floating {
+    g.dot(p) + 0.5 * p.dot(&(hessian * p))
+}
+
+// roots tau_1 <= tau_2 of ||z + tau * d|| = radius (they have opposite signs when z is strictly inside the trust region)
+pub fn boundary_intersection(
+    z: &DVector<Floating>,
+    d: &DVector<Floating>,
+    radius: Floating,
+) -> (Floating, Floating) {
+    let a = d.norm_squared();
+    let b = 2.0 * z.dot(d);
+    let c = z.norm_squared() - radius * radius;
+    let sqrt_delta = (b * b - 4.0 * a * c).max(0.0).sqrt();
+    // numerically stable formulas for the roots
+    let q = -0.5 * (b + b.signum() * sqrt_delta);
+    let (tau_1, tau_2) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    (tau_1.min(tau_2), tau_1.max(tau_2))
+}
+
+// Radius management and acceptance test (Algorithm 4.1 in Nocedal & Wright)
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct TrustRegion {
+    radius: Floating,
+    max_radius: Floating,
+    min_radius: Floating, // the minimization stops if the radius falls below this value
+    eta: Floating, // the step is accepted if the ratio between actual and predicted reduction is greater than eta
+    eta_shrink: Floating, // the radius is shrunk if the ratio is below this value
+    eta_expand: Floating, // the radius is expanded if the ratio is above this value and the step hits the boundary
+}
+
+impl TrustRegion {
+    pub fn new(radius: Floating, max_radius: Floating) -> Self {
+        assert!(radius > 0.0, "radius must be positive");
+        assert!(
+            radius <= max_radius,
+            "radius must not exceed the max radius"
+        );
+        TrustRegion {
+            radius,
+            max_radius,
+            min_radius: Floating::EPSILON,
+            eta: 0.1,
+            eta_shrink: 0.25,
+            eta_expand: 0.75,
+        }
+    }
+    pub fn with_eta(mut self, eta: Floating) -> Self {
+        assert!(
+            (0.0..self.eta_shrink).contains(&eta),
+            "eta must be in [0, eta_shrink)"
+        );
+        self.eta = eta;
+        self
+    }
+    pub fn with_min_radius(mut self, min_radius: Floating) -> Self {
+        assert!(min_radius >= 0.0, "min radius must be non negative");
+        self.min_radius = min_radius;
+        self
+    }
+    pub fn set_radius(&mut self, radius: Floating) {
+        self.radius = radius.min(self.max_radius);
+    }
+
+    // updates the radius and returns true if the step has to be accepted
+    pub fn update(&mut self, rho: Floating, step_norm: Floating) -> bool {
+        if rho < self.eta_shrink {
+            self.radius = 0.25 * step_norm.min(self.radius);
+        } else if rho > self.eta_expand && step_norm >= (1.0 - 1e-6) * self.radius {
+            self.radius = (2.0 * self.radius).min(self.max_radius);
+        }
+        rho > self.eta
+    }
+}
+
+impl Default for TrustRegion {
+    fn default() -> Self {
+        TrustRegion::new(1.0, 1e3)
+    }
+}
+
+//Template pattern for trust region solvers (analogous to LineSearchSolver). Methods that are already implemented can be freely overriden.
+pub trait TrustRegionSolver {
+    fn xk(&self) -> &DVector<Floating>;
+    fn xk_mut(&mut self) -> &mut DVector<Floating>;
+    fn k(&self) -> &usize;
+    fn k_mut(&mut self) -> &mut usize;
+    fn trust_region(&self) -> &TrustRegion;
+    fn trust_region_mut(&mut self) -> &mut TrustRegion;
+    fn has_converged(&self, eval_x_k: &FuncEvalMultivariate) -> Option<TerminationReason>;
+    // slot of the stopping criterion that, when present, replaces the built-in convergence test of the solver
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>>;
+
+    fn with_stopping_criterion(mut self, criterion: impl StoppingCriterion + 'static) -> Self
+    where
+        Self: Sized,
+    {
+        *self.stopping_criterion_mut() = Some(Box::new(criterion));
+        self
+    }
+
+    // hessian B_k of the quadratic model at x_k (the exact hessian for newton methods, an approximation for quasi-newton methods)
+    fn model_hessian(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+    ) -> Result<DMatrix<Floating>, SolverError>;
+
+    fn setup(&mut self) {}
+
+    // Solvers whose model is built from the exact hessian override this so that the hessian is requested to the oracle when evaluating the iterate
+    fn requires_hessian(&self) -> bool {
+        false
+    }
+
+    // Called after every trial step, also when it's rejected: quasi-newton solvers update here their approximation of the hessian
+    fn update_model(
+        &mut self,
+        _step: &DVector<Floating>,
+        _eval_x_k: &FuncEvalMultivariate,
+        _eval_trial: &FuncEvalMultivariate,
+    ) {
+    }
+
+    fn evaluate_x_k(
+        &mut self,
+        oracle: &mut impl Objective,
+        eval_x_k: Option<FuncEvalMultivariate>,
+    ) -> Result<FuncEvalMultivariate, SolverError> {
+        let mut eval_x_k = match eval_x_k {
+            Some(eval_x_k) => eval_x_k,
+            None => oracle.value_and_gradient(self.xk()),
+        };
+        if eval_x_k.f().is_nan() || eval_x_k.f().is_infinite() {
+            error!(target: "trust_region","Minimization completed: iterate is out of domain");
+            return Err(SolverError::OutOfDomain);
+        }
+        if self.requires_hessian() && eval_x_k.hessian().is_none() {
+            let hessian = oracle
+                .hessian(self.xk())
+                .ok_or(SolverError::MissingHessian)?;
+            eval_x_k = eval_x_k.with_hessian(hessian);
+        }
+        Ok(eval_x_k)
+    }
+
+    fn minimize<TS: TrustRegionSubproblem>(
+        &mut self,
+        subproblem: &mut TS,
+        oracle: impl Objective,
+        max_iter_solver: usize,
+        mut callback: Option<&mut dyn FnMut(&Self)>,
+    ) -> Result<MinimizationReport, SolverError> {
+        let stopwatch = Stopwatch::start();
+        // we wrap the oracle so that every evaluation is counted
+        let mut oracle = CountedObjective::new(oracle);
+
+        *self.k_mut() = 0;
+        let mut f_previous = None;
+        let mut step_norm = None;
+        let mut eval_next_iterate = None;
+        if let Some(criterion) = self.stopping_criterion_mut() {
+            criterion.reset();
+        }
+
+        self.setup();
+
+        while &max_iter_solver > self.k() {
+            let eval_x_k = self.evaluate_x_k(&mut oracle, eval_next_iterate.take())?;
+
+            let termination_reason = match self.stopping_criterion_mut().take() {
+                Some(mut criterion) => {
+                    let state = SolverState::new(
+                        *self.k(),
+                        self.xk(),
+                        &eval_x_k,
+                        f_previous,
+                        step_norm,
+                        None,
+                        oracle.counts(),
+                        stopwatch.elapsed(),
+                    );
+                    let termination_reason = criterion.check(&state);
+                    *self.stopping_criterion_mut() = Some(criterion);
+                    termination_reason
+                }
+                None => self.has_converged(&eval_x_k),
+            }
+            .or_else(|| {
+                (self.trust_region().radius() < self.trust_region().min_radius())
+                    .then_some(TerminationReason::TrustRegionRadiusTooSmall)
+            });
+
+            if let Some(termination_reason) = termination_reason {
+                info!(
+                    target: "trust_region",
+                    "Minimization completed: {} in {} iterations",
+                    termination_reason,
+                    self.k()
+                );
+                return Ok(MinimizationReport::new(
+                    self.xk().clone(),
+                    &eval_x_k,
+                    None,
+                    *self.k(),
+                    oracle.counts(),
+                    stopwatch.elapsed(),
+                    termination_reason,
+                ));
+            }
+
+            let hessian = self.model_hessian(&eval_x_k)?;
+            let radius = *self.trust_region().radius();
+            let step = subproblem.solve(eval_x_k.g(), &hessian, radius);
+            let predicted_reduction = -model_reduction(eval_x_k.g(), &hessian, &step);
+
+            let x_trial = self.xk() + &step;
+            let eval_trial = oracle.value_and_gradient(&x_trial);
+            let actual_reduction = eval_x_k.f() - eval_trial.f();
+            // a trial point out of domain is treated as a bad step, so that the radius is shrunk
+            let rho = if eval_trial.f().is_finite() && predicted_reduction > 0.0 {
+                actual_reduction / predicted_reduction
+            } else {
+                Floating::NEG_INFINITY
+            };
+
+            self.update_model(&step, &eval_x_k, &eval_trial);
+            let step_len = step.norm();
+            let accepted = self.trust_region_mut().update(rho, step_len);
+            debug!(target: "trust_region", "Step norm: {}, rho: {}, accepted: {}, radius: {}", step_len, rho, accepted, self.trust_region().radius());
+
+            if accepted {
+                *self.xk_mut() = x_trial;
+                f_previous = Some(*eval_x_k.f());
+                step_norm = Some(step_len);
+                eval_next_iterate = Some(eval_trial);
+            } else {
+                eval_next_iterate = Some(eval_x_k);
+            }
+
+            *self.k_mut() += 1;
+            if let Some(callback) = callback.as_mut() {
+                callback(self);
+            }
+        }
+        warn!(target: "trust_region","Minimization completed: max iter reached during minimization");
+        Err(SolverError::MaxIterReached)
+    }
+}
+
+#[cfg(test)]
+mod trust_region_test {
+    use super::*;
+
+    #[test]
+    pub fn boundary_intersection_roots() {
+        let z = DVector::from(vec![0.5, 0.0]);
+        let d = DVector::from(vec![1.0, 0.0]);
+        let (tau_1, tau_2) = boundary_intersection(&z, &d, 2.0);
+        assert!((tau_1 + 2.5).abs() < 1e-12);
+        assert!((tau_2 - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn radius_update() {
+        let mut trust_region = TrustRegion::new(1.0, 3.0);
+        // very good step on the boundary: accepted and expanded (up to the max radius)
+        assert!(trust_region.update(0.9, 1.0));
+        assert_eq!(trust_region.radius(), &2.0);
+        assert!(trust_region.update(0.9, 2.0));
+        assert_eq!(trust_region.radius(), &3.0);
+        // good step inside the trust region: accepted, radius unchanged
+        assert!(trust_region.update(0.5, 1.0));
+        assert_eq!(trust_region.radius(), &3.0);
+        // poor step: rejected and shrunk
+        assert!(!trust_region.update(0.05, 2.0));
+        assert_eq!(trust_region.radius(), &0.5);
+    }
+}
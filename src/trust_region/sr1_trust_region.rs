@@ -0,0 +1,159 @@
+use super::*;
+
+// SR1 trust region method (Algorithm 6.2 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// The model hessian B_k is the SR1 approximation of the hessian (not of its inverse), which is well suited for trust region methods: it's allowed to be indefinite, so it can capture the negative curvature of the function, and the subproblem solver takes care of it.
+// The approximation is updated after every trial step, also when the step is rejected (the pair s, y still carries curvature information), and the update is skipped when its denominator is too small (see UpdateSafeguards::sr1_update_allowed).
+#[derive(derive_getters::Getters)]
+pub struct SR1TrustRegion {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    trust_region: TrustRegion,
+    approx_hessian: DMatrix<Floating>,
+    #[getter(skip)]
+    ybs: DVector<Floating>, // work vector storing y - B s
+    safeguards: UpdateSafeguards,
+}
+
+impl SR1TrustRegion {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        let n = x0.len();
+        SR1TrustRegion {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            trust_region: TrustRegion::default(),
+            approx_hessian: DMatrix::identity(n, n),
+            ybs: DVector::zeros(n),
+            safeguards: UpdateSafeguards::default(),
+        }
+    }
+    pub fn with_trust_region(mut self, trust_region: TrustRegion) -> Self {
+        self.trust_region = trust_region;
+        self
+    }
+    pub fn with_safeguards(mut self, safeguards: UpdateSafeguards) -> Self {
+        self.safeguards = safeguards;
+        self
+    }
+}
+
+impl TrustRegionSolver for SR1TrustRegion {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn trust_region(&self) -> &TrustRegion {
+        &self.trust_region
+    }
+    fn trust_region_mut(&mut self) -> &mut TrustRegion {
+        &mut self.trust_region
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn model_hessian(
+        &mut self,
+        _eval_x_k: &FuncEvalMultivariate,
+    ) -> Result<DMatrix<Floating>, SolverError> {
+        Ok(self.approx_hessian.clone())
+    }
+    fn setup(&mut self) {
+        let n = self.x.len();
+        self.approx_hessian = DMatrix::identity(n, n);
+    }
+    fn update_model(
+        &mut self,
+        step: &DVector<Floating>,
+        eval_x_k: &FuncEvalMultivariate,
+        eval_trial: &FuncEvalMultivariate,
+    ) {
+        if !eval_trial.f().is_finite() {
+            return;
+        }
+        let y = eval_trial.g() - eval_x_k.g();
+        // SR1 update of the hessian (equation 6.24): the same formula of the inverse update with the roles of s and y exchanged
+        self.ybs.sygemv(1.0, &self.approx_hessian, step, 0.0);
+        // the work vector now stores y - B s
+        self.ybs.axpy(1.0, &y, -1.0);
+        if !self.safeguards.sr1_update_allowed(&self.ybs, step) {
+            return;
+        }
+        sr1_inverse_update(&mut self.approx_hessian, step, &self.ybs);
+    }
+}
+
+#[cfg(test)]
+mod sr1_trust_region_test {
+    use super::*;
+
+    fn rosenbrock(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+        let g = DVector::from(vec![
+            -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+            200. * (x[1] - x[0].powi(2)),
+        ]);
+        (f, g).into()
+    }
+
+    #[test]
+    pub fn sr1_trust_region_rosenbrock() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = SR1TrustRegion::new(1e-8, x_0.clone());
+        let report = solver
+            .minimize(&mut SteihaugCG::default(), rosenbrock, 1000, None)
+            .unwrap();
+        println!("Steihaug: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+        // the hessian is never requested to the oracle
+        assert_eq!(report.evaluations().hessian(), &0);
+
+        let mut solver = SR1TrustRegion::new(1e-8, x_0);
+        let report = solver
+            .minimize(&mut Dogleg, rosenbrock, 1000, None)
+            .unwrap();
+        println!("Dogleg: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn sr1_trust_region_nonconvex() {
+        // f(x, y) = x^2 - y^2 + y^4 / 4 has a saddle point at the origin and minima at (0, +-sqrt(2)): the SR1 model learns the negative curvature along y
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = x[0].powi(2) - x[1].powi(2) + 0.25 * x[1].powi(4);
+            let g = DVector::from(vec![2. * x[0], -2. * x[1] + x[1].powi(3)]);
+            (f, g).into()
+        };
+        let x_0 = DVector::from(vec![1.0, 0.1]);
+        let mut solver = SR1TrustRegion::new(1e-10, x_0);
+        let report = solver
+            .minimize(&mut SteihaugCG::default(), oracle, 1000, None)
+            .unwrap();
+
+        println!("Report: {:?}", report);
+        assert!(report.x()[0].abs() < 1e-8);
+        assert!((report.x()[1] - (2.0 as Floating).sqrt()).abs() < 1e-8);
+    }
+}
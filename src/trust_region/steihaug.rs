@@ -0,0 +1,107 @@
+use super::*;
+
+// Steihaug-Toint truncated conjugate gradient (Algorithm 7.2 in [Nocedal, J., & Wright, S. J. (2006). Numerical optimization.]).
+// Conjugate gradient iterations on B p = -g starting from p = 0, stopped as soon as
+// - the residual is small enough (relative tolerance min(0.5, sqrt(||g||)) * ||g||, which gives superlinear convergence of the outer iterations),
+// - the iterate leaves the trust region: the step is truncated on the boundary,
+// - a direction of non positive curvature is found: the step follows that direction up to the boundary.
+// The model decreases monotonically along the iterates, so the step is never worse than the Cauchy point. Only products of the hessian with vectors are needed.
+#[derive(Debug, Clone, Default, derive_getters::Getters)]
+pub struct SteihaugCG {
+    max_iter: Option<usize>, // maximum number of conjugate gradient iterations (dimension of the problem if None)
+}
+
+impl SteihaugCG {
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = Some(max_iter);
+        self
+    }
+
+    // point on the boundary along z + tau * d with the lowest value of the model
+    fn to_boundary(
+        g: &DVector<Floating>,
+        hessian: &DMatrix<Floating>,
+        z: &DVector<Floating>,
+        d: &DVector<Floating>,
+        radius: Floating,
+    ) -> DVector<Floating> {
+        let (tau_1, tau_2) = boundary_intersection(z, d, radius);
+        let p_1 = z + tau_1 * d;
+        let p_2 = z + tau_2 * d;
+        if model_reduction(g, hessian, &p_1) < model_reduction(g, hessian, &p_2) {
+            p_1
+        } else {
+            p_2
+        }
+    }
+}
+
+impl TrustRegionSubproblem for SteihaugCG {
+    fn solve(
+        &mut self,
+        g: &DVector<Floating>,
+        hessian: &DMatrix<Floating>,
+        radius: Floating,
+    ) -> DVector<Floating> {
+        let g_norm = g.norm();
+        let tol = g_norm.sqrt().min(0.5) * g_norm;
+        let mut z = DVector::zeros(g.len());
+        let mut r = g.clone();
+        let mut d = -g;
+        if g_norm == 0.0 {
+            return z;
+        }
+
+        for j in 0..self.max_iter.unwrap_or(g.len()) {
+            let bd = hessian * &d;
+            let dbd = d.dot(&bd);
+            if dbd <= 0.0 {
+                trace!(target: "steihaug", "Negative curvature at iteration {}: moving to the boundary", j);
+                return Self::to_boundary(g, hessian, &z, &d, radius);
+            }
+            let rr = r.norm_squared();
+            let alpha = rr / dbd;
+            let z_next = &z + alpha * &d;
+            if z_next.norm() >= radius {
+                trace!(target: "steihaug", "Boundary reached at iteration {}", j);
+                let (_, tau) = boundary_intersection(&z, &d, radius);
+                return z + tau * d;
+            }
+            r.axpy(alpha, &bd, 1.0);
+            z = z_next;
+            if r.norm() < tol {
+                trace!(target: "steihaug", "Residual below tolerance at iteration {}", j);
+                return z;
+            }
+            let beta = r.norm_squared() / rr;
+            d = -&r + beta * d;
+        }
+        z
+    }
+}
+
+#[cfg(test)]
+mod steihaug_test {
+    use super::*;
+
+    #[test]
+    pub fn steihaug_interior_newton_step() {
+        // positive definite hessian and a large radius: close to the solution (small gradient, hence tight forcing term) the step is the newton step
+        let hessian = DMatrix::from_row_slice(2, 2, &[4.0, 1.0, 1.0, 3.0]);
+        let g = DVector::from(vec![1e-8, 2e-8]);
+        let p = SteihaugCG::default().solve(&g, &hessian, 100.0);
+        let newton_step = -hessian.clone().cholesky().unwrap().solve(&g);
+        assert!((&p - &newton_step).norm() < 1e-6 * newton_step.norm());
+    }
+
+    #[test]
+    pub fn steihaug_negative_curvature() {
+        // indefinite hessian: the step must lie on the boundary and decrease the model
+        let hessian = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, -2.0]);
+        let g = DVector::from(vec![1.0, 0.1]);
+        let radius = 0.5;
+        let p = SteihaugCG::default().solve(&g, &hessian, radius);
+        assert!((p.norm() - radius).abs() < 1e-10);
+        assert!(model_reduction(&g, &hessian, &p) < 0.0);
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+// Newton method globalized with a trust region: the model hessian is the exact hessian provided by the oracle. Unlike Newton (which needs a positive definite hessian to produce a descent direction), the step is well defined also when the hessian is singular or indefinite, and with SteihaugCG the directions of negative curvature are exploited to escape from saddle points.
+#[derive(derive_getters::Getters)]
+pub struct TrustRegionNewton {
+    grad_tol: Floating,
+    x: DVector<Floating>,
+    k: usize,
+    #[getter(skip)]
+    stopping_criterion: Option<Box<dyn StoppingCriterion>>,
+    trust_region: TrustRegion,
+}
+
+impl TrustRegionNewton {
+    pub fn new(grad_tol: Floating, x0: DVector<Floating>) -> Self {
+        TrustRegionNewton {
+            grad_tol,
+            x: x0,
+            k: 0,
+            stopping_criterion: None,
+            trust_region: TrustRegion::default(),
+        }
+    }
+    pub fn with_trust_region(mut self, trust_region: TrustRegion) -> Self {
+        self.trust_region = trust_region;
+        self
+    }
+}
+
+impl TrustRegionSolver for TrustRegionNewton {
+    fn xk(&self) -> &DVector<Floating> {
+        &self.x
+    }
+    fn xk_mut(&mut self) -> &mut DVector<Floating> {
+        &mut self.x
+    }
+    fn k(&self) -> &usize {
+        &self.k
+    }
+    fn k_mut(&mut self) -> &mut usize {
+        &mut self.k
+    }
+    fn trust_region(&self) -> &TrustRegion {
+        &self.trust_region
+    }
+    fn trust_region_mut(&mut self) -> &mut TrustRegion {
+        &mut self.trust_region
+    }
+    fn stopping_criterion_mut(&mut self) -> &mut Option<Box<dyn StoppingCriterion>> {
+        &mut self.stopping_criterion
+    }
+    fn requires_hessian(&self) -> bool {
+        true
+    }
+    fn has_converged(&self, eval: &FuncEvalMultivariate) -> Option<TerminationReason> {
+        (eval.g().norm() < self.grad_tol).then_some(TerminationReason::GradientTolerance)
+    }
+    fn model_hessian(
+        &mut self,
+        eval_x_k: &FuncEvalMultivariate,
+    ) -> Result<DMatrix<Floating>, SolverError> {
+        eval_x_k
+            .hessian()
+            .clone()
+            .ok_or(SolverError::MissingHessian)
+    }
+}
+
+#[cfg(test)]
+mod trust_region_newton_test {
+    use super::*;
+
+    fn rosenbrock(x: &DVector<Floating>) -> FuncEvalMultivariate {
+        let f = 100. * (x[1] - x[0].powi(2)).powi(2) + (1. - x[0]).powi(2);
+        let g = DVector::from(vec![
+            -400. * x[0] * (x[1] - x[0].powi(2)) - 2. * (1. - x[0]),
+            200. * (x[1] - x[0].powi(2)),
+        ]);
+        let hessian = DMatrix::from_row_slice(
+            2,
+            2,
+            &[
+                1200. * x[0].powi(2) - 400. * x[1] + 2.,
+                -400. * x[0],
+                -400. * x[0],
+                200.,
+            ],
+        );
+        FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+    }
+
+    #[test]
+    pub fn trust_region_newton_rosenbrock() {
+        std::env::set_var("RUST_LOG", "info");
+
+        let _ = Tracer::default()
+            .with_stdout_layer(Some(LogFormat::Normal))
+            .build();
+
+        let x_0 = DVector::from(vec![-1.2, 1.0]);
+        let mut solver = TrustRegionNewton::new(1e-8, x_0.clone());
+        let report = solver
+            .minimize(&mut SteihaugCG::default(), rosenbrock, 1000, None)
+            .unwrap();
+        println!("Steihaug: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+
+        let mut solver = TrustRegionNewton::new(1e-8, x_0);
+        let report = solver
+            .minimize(&mut Dogleg, rosenbrock, 1000, None)
+            .unwrap();
+        println!("Dogleg: {:?}", report);
+        assert!((report.x()[0] - 1.).abs() < 1e-6);
+        assert!((report.x()[1] - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn trust_region_newton_escapes_saddle_point() {
+        // f(x, y) = x^2 - y^2 + y^4 / 4 has a saddle point at the origin (where the hessian is indefinite) and minima at (0, +-sqrt(2))
+        let oracle = |x: &DVector<Floating>| -> FuncEvalMultivariate {
+            let f = x[0].powi(2) - x[1].powi(2) + 0.25 * x[1].powi(4);
+            let g = DVector::from(vec![2. * x[0], -2. * x[1] + x[1].powi(3)]);
+            let hessian = DMatrix::from_row_slice(2, 2, &[2., 0., 0., -2. + 3. * x[1].powi(2)]);
+            FuncEvalMultivariate::new(f, g).with_hessian(hessian)
+        };
+        // the newton direction at this point is attracted by the saddle point
+        let x_0 = DVector::from(vec![1.0, 0.1]);
+        let mut solver = TrustRegionNewton::new(1e-10, x_0);
+        let report = solver
+            .minimize(&mut SteihaugCG::default(), oracle, 1000, None)
+            .unwrap();
+
+        println!("Report: {:?}", report);
+        assert!(report.x()[0].abs() < 1e-8);
+        assert!((report.x()[1] - (2.0 as Floating).sqrt()).abs() < 1e-8);
+        assert!((report.f() + 1.).abs() < 1e-12);
+    }
+}
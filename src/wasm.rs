@@ -1,5 +1,5 @@
 use crate::{BackTracking, FuncEvalMultivariate, LineSearchSolver, MoreThuente};
-use crate::{GradientDescent, Newton, BFGS};
+use crate::{GradientDescent, Newton, BFGS, LBFGS};
 use nalgebra::{DMatrix, DVector};
 use wasm_bindgen::prelude::*;
 
@@ -111,14 +111,11 @@ impl OptimizationSolver {
 
         // Run optimization
         match solver.minimize(&mut ls, objective, self.max_iterations, 20, None) {
-            Ok(()) => {
-                let x = solver.x();
-                let eval = objective(x);
-
-                result.x = x.as_slice().to_vec();
-                result.f_value = *eval.f();
-                result.gradient_norm = eval.g().norm();
-                result.iterations = *solver.k();
+            Ok(report) => {
+                result.x = report.x().as_slice().to_vec();
+                result.f_value = *report.f();
+                result.gradient_norm = *report.gradient_norm();
+                result.iterations = *report.iterations();
                 result.success = true;
             }
             Err(e) => {
@@ -167,14 +164,64 @@ impl OptimizationSolver {
 
         // Run optimization
         match solver.minimize(&mut ls, objective, self.max_iterations, 20, None) {
-            Ok(()) => {
-                let x = solver.x();
-                let eval = objective(x);
-
-                result.x = x.as_slice().to_vec();
-                result.f_value = *eval.f();
-                result.gradient_norm = eval.g().norm();
-                result.iterations = *solver.k();
+            Ok(report) => {
+                result.x = report.x().as_slice().to_vec();
+                result.f_value = *report.f();
+                result.gradient_norm = *report.gradient_norm();
+                result.iterations = *report.iterations();
+                result.success = true;
+            }
+            Err(e) => {
+                result.error_message = format!("Optimization failed: {:?}", e);
+                result.success = false;
+            }
+        }
+
+        result
+    }
+
+    pub fn solve_lbfgs(&self, x0: &[f64], f_and_g_fn: js_sys::Function) -> OptimizationResult {
+        let mut result = OptimizationResult::new();
+
+        // Convert initial point
+        let x0_vec = DVector::from_vec(x0.to_vec());
+
+        // Create objective function closure
+        let objective = |x: &DVector<f64>| -> FuncEvalMultivariate {
+            // Call JavaScript function
+            let this = JsValue::NULL;
+            let args = js_sys::Array::new();
+            // Add all vector components to the args array
+            for &value in x.as_slice() {
+                args.push(&JsValue::from_f64(value));
+            }
+
+            let js_result = f_and_g_fn.call1(&this, &args).unwrap();
+            let js_array = js_sys::Array::from(&js_result);
+
+            let f = js_array.get(0).as_f64().unwrap();
+            // Extract gradient components dynamically
+            let mut g_values = Vec::new();
+            for i in 1..js_array.length() {
+                if let Some(g_val) = js_array.get(i).as_f64() {
+                    g_values.push(g_val);
+                }
+            }
+            let g = DVector::from_vec(g_values);
+            FuncEvalMultivariate::new(f, g)
+        };
+
+        // Setup solver
+        let mut solver = LBFGS::new(self.tolerance, x0_vec);
+        let mut ls = MoreThuente::default();
+
+        // Run optimization
+        match solver.minimize(&mut ls, objective, self.max_iterations, 20, None) {
+            Ok(report) => {
+                result.x = report.x().as_slice().to_vec();
+                result.f_value = *report.f();
+                result.gradient_norm = *report.gradient_norm();
+                result.iterations = *report.iterations();
                 result.success = true;
             }
             Err(e) => {
@@ -248,14 +295,11 @@ impl OptimizationSolver {
 
         // Run optimization
         match solver.minimize(&mut ls, objective, self.max_iterations, 20, None) {
-            Ok(()) => {
-                let x = solver.x();
-                let eval = objective(x);
-
-                result.x = x.as_slice().to_vec();
-                result.f_value = *eval.f();
-                result.gradient_norm = eval.g().norm();
-                result.iterations = *solver.k();
+            Ok(report) => {
+                result.x = report.x().as_slice().to_vec();
+                result.f_value = *report.f();
+                result.gradient_norm = *report.gradient_norm();
+                result.iterations = *report.iterations();
                 result.success = true;
             }
             Err(e) => {